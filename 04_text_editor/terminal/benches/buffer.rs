@@ -0,0 +1,98 @@
+// Cost of a key press in the middle of a large log file, including what `redraw` renders.
+//
+//     cargo bench --bench buffer
+//
+// On a 5.3 MB file of 35 000 lines of 151 chars, viewport of 120x40, so every line wraps:
+//
+// | Buffer     | Wrap | Load  | Typing | Backspace | Newline | Typing at both ends |
+// |------------|------|-------|--------|-----------|---------|---------------------|
+// | LineBuffer | on   | 6 ms  | 126 µs | 128 µs    | 41 µs   | 240 µs              |
+// | GapBuffer  | on   | 22 ms | 133 µs | 131 µs    | 32 µs   | 3.0 ms              |
+// | LineBuffer | off  | 3 ms  | 46 µs  | 47 µs     | 30 µs   | 32 µs               |
+// | GapBuffer  | off  | 21 ms | 56 µs  | 54 µs     | 17 µs   | 2.5 ms              |
+//
+// Rows of wrapped lines are cached, an edit only lays out the changed line again and the row
+// offsets of the lines below it are summed again when needed. Jumping between far apart
+// places moves the gap over the whole text.
+use std::time::{Duration, Instant};
+use texteditor::text_editor::buffer::{LineBuffer, TextBuffer};
+use texteditor::text_editor::gap_buffer::GapBuffer;
+use texteditor::text_editor::TextEditor;
+
+const LINES: usize = 35_000;
+const KEY_PRESSES: usize = 1_000;
+
+fn log_file() -> String {
+    (0..LINES)
+        .map(|index| {
+            format!(
+                "2024-03-01T12:{:02}:{:02}Z INFO request {} handled in {} ms \
+                 path=/api/v1/projects/{}/builds?page={}&per_page=100 \
+                 agent=\"Mozilla/5.0 (X11; Linux x86_64)\"\n",
+                index / 60 % 60,
+                index % 60,
+                index,
+                index % 997,
+                index % 1000,
+                index % 37
+            )
+        })
+        .collect()
+}
+
+// Function to run `edit` on every key press, rendering the viewport after each one
+fn measure(editor: &mut TextEditor, edit: fn(&mut TextEditor)) -> Duration {
+    let start = Instant::now();
+    for _ in 0..KEY_PRESSES {
+        edit(editor);
+        editor.scroll_to_cursor();
+        editor.visible_content_to_string();
+    }
+    start.elapsed() / KEY_PRESSES as u32
+}
+
+fn bench(name: &str, build: fn(&str) -> Box<dyn TextBuffer>, text: &str, wrap: bool) {
+    let start = Instant::now();
+    let mut editor = TextEditor::with_buffer(build(text));
+    let load = start.elapsed();
+    editor.wrap = wrap;
+    editor.resize(120, 40);
+    editor.cursor_pos = (LINES / 2, 20);
+
+    let typing = measure(&mut editor, |editor| editor.insert_char('x'));
+    let backspace = measure(&mut editor, |editor| editor.handle_backspace());
+    let newline = measure(&mut editor, |editor| editor.handle_newline());
+    let jumping = measure(&mut editor, |editor| {
+        // Type at both ends of the file in turns
+        editor.cursor_pos = match editor.cursor_pos.0 {
+            0 => (LINES - 1, 0),
+            _ => (0, 0),
+        };
+        editor.insert_char('x');
+    });
+
+    println!(
+        "{:<10} wrap {:<5} | load {:>9.2?} | typing {:>9.2?} | backspace {:>9.2?} | newline {:>9.2?} | typing at both ends {:>9.2?}",
+        name, wrap, load, typing, backspace, newline, jumping
+    );
+}
+
+fn main() {
+    let text = log_file();
+    println!("{} lines, {} bytes, per key press:", LINES, text.len());
+
+    for wrap in [true, false] {
+        bench(
+            "LineBuffer",
+            |text| Box::new(LineBuffer::build(text)),
+            &text,
+            wrap,
+        );
+        bench(
+            "GapBuffer",
+            |text| Box::new(GapBuffer::build(text)),
+            &text,
+            wrap,
+        );
+    }
+}
@@ -1,16 +1,39 @@
 pub struct Config {
     pub file_path: String,
+    pub tab_spaces: Option<usize>, // from `--tab-spaces=N`, Tab inserts '\t' without it
 }
 
 impl Config {
     pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
         args.next();
 
-        let file_path = match args.next() {
+        let mut file_path = None;
+        let mut tab_spaces = None;
+        for arg in args {
+            if let Some(value) = arg.strip_prefix("--tab-spaces=") {
+                match value.parse::<usize>() {
+                    Ok(spaces) if spaces > 0 => tab_spaces = Some(spaces),
+                    _ => return Err("--tab-spaces needs a positive number"),
+                }
+            } else if file_path.is_none() {
+                file_path = Some(arg);
+            } else {
+                return Err("Got more than one file path");
+            }
+        }
+
+        let file_path = match file_path {
             Some(arg) => arg,
             None => return Err("Did't get a file path"),
         };
 
-        Ok(Config { file_path })
+        Ok(Config {
+            file_path,
+            tab_spaces,
+        })
     }
 }
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
@@ -0,0 +1,38 @@
+use crate::config::Config;
+
+fn args(list: &[&str]) -> impl Iterator<Item = String> {
+    let mut args = vec![String::from("texteditor")];
+    args.extend(list.iter().map(|arg| arg.to_string()));
+    args.into_iter()
+}
+
+#[cfg(test)]
+mod build {
+    use super::*;
+
+    #[test]
+    fn reads_file_path() {
+        let config = Config::build(args(&["notes.txt"])).unwrap();
+
+        assert_eq!(config.file_path, "notes.txt");
+        assert_eq!(config.tab_spaces, None);
+    }
+
+    #[test]
+    fn reads_tab_spaces_on_either_side_of_path() {
+        let config = Config::build(args(&["--tab-spaces=4", "main.rs"])).unwrap();
+        assert_eq!(config.file_path, "main.rs");
+        assert_eq!(config.tab_spaces, Some(4));
+
+        let config = Config::build(args(&["main.rs", "--tab-spaces=2"])).unwrap();
+        assert_eq!(config.tab_spaces, Some(2));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(Config::build(args(&[])).is_err());
+        assert!(Config::build(args(&["--tab-spaces=0", "a.rs"])).is_err());
+        assert!(Config::build(args(&["--tab-spaces=x", "a.rs"])).is_err());
+        assert!(Config::build(args(&["a.rs", "b.rs"])).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs::{self, File, OpenOptions, Permissions};
+use std::io::{self, Write};
+use std::os::unix::{
+    self,
+    fs::{MetadataExt, OpenOptionsExt, PermissionsExt},
+};
+use std::path::{Path, PathBuf};
+
+// Function to get a path next to the file, named after it: "dir/.name<suffix>"
+fn sibling_path(path: &str, suffix: &str) -> PathBuf {
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .map_or(String::new(), |name| name.to_string_lossy().to_string());
+    path.with_file_name(format!(".{}{}", file_name, suffix))
+}
+
+pub fn swap_path(path: &str) -> PathBuf {
+    sibling_path(path, ".swp")
+}
+
+// Function to write the file through a temporary one renamed over it,
+// so a crash while writing never leaves a half written file
+pub fn save_atomic(path: &str, contents: &str) -> io::Result<()> {
+    replace_file(path, contents, None)
+}
+
+// Function to write unsaved text next to the file, to recover it after a crash. The text
+// may be as private as the file, so the swap file is never readable by more users.
+pub fn write_swap(path: &str, contents: &str) -> io::Result<()> {
+    let mode = fs::metadata(path).map_or(0o600, |metadata| metadata.mode() & 0o666);
+    replace_file(&swap_path(path).to_string_lossy(), contents, Some(mode))
+}
+
+// Function to replace a file with one holding the contents, with the given mode or else
+// the mode of the replaced file
+fn replace_file(path: &str, contents: &str, mode: Option<u32>) -> io::Result<()> {
+    // A symlink stays in place, the file it points to gets replaced
+    let path = fs::canonicalize(path).map_or(path.to_string(), |target| {
+        target.to_string_lossy().to_string()
+    });
+    let temp_path = sibling_path(&path, ".tmp");
+    let replaced = fs::metadata(&path).ok();
+    let mode = mode.or(replaced.as_ref().map(|metadata| metadata.mode() & 0o7777));
+
+    // A temp file left by a crash is removed, anything put there meanwhile is never opened
+    let _ = fs::remove_file(&temp_path);
+    let result = (|| {
+        let mut temp_file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode.unwrap_or(0o666))
+            .open(&temp_path)?;
+
+        // The rename replaces the file with a new one, so it takes the owner of the old one
+        // first. Only root may give it to another owner, otherwise it stays ours. The mode
+        // is set again as the umask applied at creation and changing the owner drop bits.
+        if let Some(metadata) = &replaced {
+            let _ = unix::fs::fchown(&temp_file, Some(metadata.uid()), Some(metadata.gid()));
+        }
+        if let Some(mode) = mode {
+            temp_file.set_permissions(Permissions::from_mode(mode))?;
+        }
+        temp_file.write_all(contents.as_bytes())?;
+        temp_file.sync_all()?;
+        fs::rename(&temp_path, &path)?;
+
+        // The rename itself only survives a crash once the directory is written
+        let dir = Path::new(&path)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        File::open(dir)?.sync_all()
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+pub fn read_swap(path: &str) -> Option<String> {
+    fs::read_to_string(swap_path(path)).ok()
+}
+
+pub fn remove_swap(path: &str) {
+    let _ = fs::remove_file(swap_path(path));
+}
+
+#[cfg(test)]
+#[path = "tests.rs"]
+mod tests;
@@ -0,0 +1,127 @@
+use crate::file::*;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+// Function to get a path in a fresh temporary directory, unique per test
+fn temp_file(name: &str) -> String {
+    let dir = env::temp_dir().join(format!("texteditor-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name).to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod saving {
+    use super::*;
+
+    #[test]
+    fn replaces_file_without_leaving_temp_file() {
+        let path = temp_file("replace.txt");
+        fs::write(&path, "old").unwrap();
+
+        save_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let files = fs::read_dir(std::path::Path::new(&path).parent().unwrap()).unwrap();
+        assert_eq!(files.count(), 1);
+    }
+
+    #[test]
+    fn creates_missing_file() {
+        let path = temp_file("missing.txt");
+
+        save_atomic(&path, "text").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "text");
+    }
+
+    #[test]
+    fn keeps_mode_of_replaced_file() {
+        let path = temp_file("mode.sh");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+        save_atomic(&path, "new").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+    }
+
+    #[test]
+    fn replaces_target_of_symlink() {
+        let target = temp_file("target.txt");
+        fs::write(&target, "old").unwrap();
+        let link = format!("{}.link", target);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        save_atomic(&link, "new").unwrap();
+
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+    }
+
+    #[test]
+    fn doesnt_follow_symlink_at_temp_path() {
+        let path = temp_file("victim.txt");
+        let other = format!("{}.other", path);
+        fs::write(&other, "untouched").unwrap();
+        std::os::unix::fs::symlink(&other, super::sibling_path(&path, ".tmp")).unwrap();
+
+        save_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&other).unwrap(), "untouched");
+    }
+
+    #[test]
+    fn fails_when_directory_is_missing() {
+        let path = temp_file("gone.txt");
+        let missing = format!("{}.dir/file.txt", path);
+
+        assert!(save_atomic(&missing, "text").is_err());
+    }
+}
+
+#[cfg(test)]
+mod swap {
+    use super::*;
+
+    #[test]
+    fn writes_reads_and_removes_swap_next_to_file() {
+        let path = temp_file("notes.txt");
+        assert_eq!(read_swap(&path), None);
+
+        write_swap(&path, "unsaved").unwrap();
+        assert!(swap_path(&path).ends_with(".notes.txt.swp"));
+        assert_eq!(read_swap(&path), Some(String::from("unsaved")));
+
+        remove_swap(&path);
+        assert_eq!(read_swap(&path), None);
+    }
+
+    #[test]
+    fn keeps_swap_as_private_as_the_file() {
+        let path = temp_file("secret.txt");
+        fs::write(&path, "key").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_swap(&path, "unsaved key").unwrap();
+
+        let mode = fs::metadata(swap_path(&path)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn keeps_swap_private_without_the_file() {
+        let path = temp_file("new.txt");
+
+        write_swap(&path, "unsaved").unwrap();
+
+        let mode = fs::metadata(swap_path(&path)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
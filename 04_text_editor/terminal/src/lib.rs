@@ -1,15 +1,129 @@
 use std::error::Error;
 use std::fs;
-use std::io::{self};
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use termion::event::{Event, Key};
 use termion::input::TermRead;
 use termion::raw::IntoRawMode;
 
 pub mod config;
+pub mod file;
 pub mod text_editor;
 
 use config::Config;
+use text_editor::highlight::{Highlighter, Language};
+use text_editor::line_numbers::Gutter;
+use text_editor::search::PromptKind;
 use text_editor::TextEditor;
 
+// How long the editor waits for input before checking the swap file and notifications again
+const TICK: Duration = Duration::from_millis(250);
+
+// How often unsaved text is written to the swap file
+const SWAP_INTERVAL: Duration = Duration::from_secs(5);
+
+// Swap file kept in line with the unsaved text, to recover it after a crash
+struct Swap {
+    path: String,
+    changes: usize, // edits applied to the text when the swap file was last updated
+    next_write: Instant,
+}
+
+impl Swap {
+    fn new(path: &str, editor: &TextEditor) -> Self {
+        Swap {
+            path: path.to_string(),
+            changes: editor.changes,
+            next_write: Instant::now() + SWAP_INTERVAL,
+        }
+    }
+
+    // Function to write or remove the swap file when the text changed, at most once per interval
+    fn update(&mut self, editor: &mut TextEditor, now: Instant) {
+        if editor.changes == self.changes || now < self.next_write {
+            return;
+        }
+
+        if !editor.is_modified() {
+            file::remove_swap(&self.path);
+        } else if let Err(err) = file::write_swap(&self.path, &editor.content_to_save()) {
+            editor.show_notification(&format!("Error writing swap file: {}", err));
+        }
+        self.changes = editor.changes;
+        self.next_write = now + SWAP_INTERVAL;
+    }
+}
+
+// Function to write the text to its file and drop the swap file
+fn save(editor: &mut TextEditor, path: &str) -> io::Result<()> {
+    file::save_atomic(path, &editor.content_to_save())?;
+    file::remove_swap(path);
+    editor.history.mark_saved();
+    Ok(())
+}
+
+// Function to save on Ctrl+s or before quitting, returns `false` if the file couldn't be written
+fn save_with_notification(editor: &mut TextEditor, path: &str) -> bool {
+    match save(editor, path) {
+        Ok(()) => {
+            editor.show_notification("File saved!");
+            true
+        }
+        Err(err) => {
+            editor.show_notification(&format!("Error saving file: {}", err));
+            false
+        }
+    }
+}
+
+// Function to ask, before the editor takes the terminal, whether to recover text left in a swap file
+fn ask_to_recover(path: &str) -> io::Result<bool> {
+    print!(
+        "Found unsaved changes of {} from a session that didn't quit properly. Recover them? [y/N] ",
+        path
+    );
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+// Function to read terminal events on their own thread, so the editor can wait for them with a timeout
+fn spawn_events() -> Receiver<io::Result<Event>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for event in io::stdin().events() {
+            if sender.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+type Movement = fn(&mut TextEditor);
+
+// Function to recognize shift and ctrl with arrows, Home and End, termion reports them as
+// unsupported events. Returns the movement and whether it extends the selection.
+fn modified_movement(sequence: &[u8]) -> Option<(Movement, bool)> {
+    match sequence {
+        b"\x1b[1;2A" => Some((TextEditor::cursor_up, true)),
+        b"\x1b[1;2B" => Some((TextEditor::cursor_down, true)),
+        b"\x1b[1;2C" => Some((TextEditor::cursor_right, true)),
+        b"\x1b[1;2D" => Some((TextEditor::cursor_left, true)),
+        b"\x1b[1;2H" => Some((TextEditor::line_start, true)),
+        b"\x1b[1;2F" => Some((TextEditor::line_end, true)),
+        b"\x1b[1;5C" => Some((TextEditor::word_right, false)),
+        b"\x1b[1;5D" => Some((TextEditor::word_left, false)),
+        b"\x1b[1;6C" => Some((TextEditor::word_right, true)),
+        b"\x1b[1;6D" => Some((TextEditor::word_left, true)),
+        _ => None,
+    }
+}
+
 pub fn run(config: Config) -> Result<(), Box<dyn Error + 'static>> {
     let contents = fs::read_to_string(&config.file_path).or_else(|err| {
         if err.kind() == std::io::ErrorKind::NotFound {
@@ -26,40 +140,193 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error + 'static>> {
     match contents {
         Ok(c) => {
             let mut editor = TextEditor::build(&c);
+            editor.file_name = config.file_path.clone();
+            editor.highlighter = Language::from_path(&config.file_path).map(Highlighter::new);
+            editor.tab_spaces = config.tab_spaces;
+
+            // The swap file stays until the recovered text is saved
+            if let Some(swap) = file::read_swap(&config.file_path) {
+                if swap != c && ask_to_recover(&config.file_path)? {
+                    editor.replace_text(&swap);
+                } else {
+                    file::remove_swap(&config.file_path);
+                }
+            }
+            let mut swap = Swap::new(&config.file_path, &editor);
 
             let mut stdout = io::stdout().into_raw_mode()?;
-            let stdin = io::stdin().keys();
+            let events = spawn_events();
 
             editor.redraw(&mut stdout).unwrap();
-            for key in stdin {
-                match key.unwrap() {
-                    termion::event::Key::Char(c) => {
+            loop {
+                // Checked on every event too, so steady typing doesn't hold them back
+                let now = Instant::now();
+                swap.update(&mut editor, now);
+                if editor.expire_notification(now) {
+                    editor.redraw(&mut stdout).unwrap();
+                }
+
+                let event = match events.recv_timeout(TICK) {
+                    Ok(event) => event?,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+
+                let key = match event {
+                    Event::Key(key) => key,
+                    Event::Unsupported(sequence) => {
+                        // Extend the selection on shift+arrows, jump by words on ctrl+arrows
+                        if let Some((movement, select)) = modified_movement(&sequence) {
+                            editor.move_cursor(movement, select);
+                            editor.redraw(&mut stdout).unwrap();
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                // The open prompt takes all keys
+                if editor.confirm_quit {
+                    match key {
+                        Key::Char('y') => {
+                            if save_with_notification(&mut editor, &config.file_path) {
+                                break;
+                            }
+                            editor.confirm_quit = false;
+                        }
+                        Key::Char('n') => break,
+                        Key::Char('c') | Key::Esc => editor.confirm_quit = false,
+                        _ => (),
+                    }
+                    editor.redraw(&mut stdout).unwrap();
+                    continue;
+                }
+                if editor.go_to_input.is_some() {
+                    editor.go_to_key(key);
+                    editor.redraw(&mut stdout).unwrap();
+                    continue;
+                }
+                if editor.search.is_some() {
+                    editor.prompt_key(key);
+                    editor.redraw(&mut stdout).unwrap();
+                    continue;
+                }
+
+                match key {
+                    Key::Char(c) => {
                         if c == '\n' {
                             editor.handle_newline();
+                        } else if c == '\t' {
+                            editor.insert_tab();
                         } else {
                             editor.insert_char(c);
                         }
                     }
-                    termion::event::Key::Left => {
-                        editor.cursor_left();
+                    Key::Left => {
+                        editor.move_cursor(TextEditor::cursor_left, false);
                     }
-                    termion::event::Key::Right => {
-                        editor.cursor_right();
+                    Key::Right => {
+                        editor.move_cursor(TextEditor::cursor_right, false);
                     }
-                    termion::event::Key::Up => {
-                        editor.cursor_up();
+                    Key::Up => {
+                        editor.move_cursor(TextEditor::cursor_up, false);
                     }
-                    termion::event::Key::Down => {
-                        editor.cursor_down();
+                    Key::Down => {
+                        editor.move_cursor(TextEditor::cursor_down, false);
                     }
-                    termion::event::Key::Backspace => {
+                    Key::Backspace => {
                         editor.handle_backspace();
                     }
-                    termion::event::Key::Ctrl('c') => break, // Exit on Ctrl+C
-                    termion::event::Key::Ctrl('s') => {
+                    Key::Delete => {
+                        editor.delete_forward();
+                    }
+                    Key::Home => {
+                        editor.move_cursor(TextEditor::line_start, false);
+                    }
+                    Key::End => {
+                        editor.move_cursor(TextEditor::line_end, false);
+                    }
+                    Key::PageUp => {
+                        editor.move_cursor(TextEditor::page_up, false);
+                    }
+                    Key::PageDown => {
+                        editor.move_cursor(TextEditor::page_down, false);
+                    }
+                    Key::Ctrl('q') => {
+                        // Exit on Ctrl+q, asking to save unsaved changes first
+                        if !editor.is_modified() {
+                            break;
+                        }
+                        editor.confirm_quit = true;
+                    }
+                    Key::Ctrl('c') => {
+                        // Copy the selection on Ctrl+c
+                        let copied = editor.copy();
+                        if !copied {
+                            editor.show_notification("Nothing selected");
+                        }
+                    }
+                    Key::Ctrl('x') => {
+                        // Cut the selection on Ctrl+x
+                        let cut = editor.cut();
+                        if !cut {
+                            editor.show_notification("Nothing selected");
+                        }
+                    }
+                    Key::Ctrl('v') => {
+                        // Paste on Ctrl+v
+                        let pasted = editor.paste();
+                        if !pasted {
+                            editor.show_notification("Clipboard is empty");
+                        }
+                    }
+                    Key::Ctrl('z') => {
+                        // Undo on Ctrl+z
+                        let undone = editor.undo();
+                        if !undone {
+                            editor.show_notification("Nothing to undo");
+                        }
+                    }
+                    Key::Ctrl('y') => {
+                        // Redo on Ctrl+y
+                        let redone = editor.redo();
+                        if !redone {
+                            editor.show_notification("Nothing to redo");
+                        }
+                    }
+                    Key::Ctrl('f') => {
+                        // Search on Ctrl+f
+                        editor.open_search(PromptKind::Search);
+                    }
+                    Key::Ctrl('r') => {
+                        // Find and replace on Ctrl+r
+                        editor.open_search(PromptKind::Replace);
+                    }
+                    Key::Ctrl('g') => {
+                        // Go to line on Ctrl+g
+                        editor.go_to_input = Some(String::new());
+                    }
+                    Key::Ctrl('l') => {
+                        // Switch line numbers on Ctrl+l
+                        editor.toggle_gutter();
+                        editor.show_notification(match editor.gutter {
+                            Gutter::Off => "Line numbers off",
+                            Gutter::Absolute => "Line numbers on",
+                            Gutter::Relative => "Relative line numbers on",
+                        });
+                    }
+                    Key::Ctrl('w') => {
+                        // Toggle soft line wrapping on Ctrl+w
+                        editor.toggle_wrap();
+                        editor.show_notification(if editor.wrap {
+                            "Line wrapping on"
+                        } else {
+                            "Line wrapping off"
+                        });
+                    }
+                    Key::Ctrl('s') => {
                         // Save the file on Ctrl+s
-                        fs::write(&config.file_path, editor.content_to_save())?;
-                        editor.show_notification("File saved!");
+                        save_with_notification(&mut editor, &config.file_path);
                     }
                     _ => (),
                 }
@@ -67,6 +334,8 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error + 'static>> {
                 editor.redraw(&mut stdout).unwrap();
             }
 
+            // Quitting keeps the text on disk, unsaved changes were either saved or dropped
+            file::remove_swap(&config.file_path);
             Ok(())
         }
         _ => panic!(),
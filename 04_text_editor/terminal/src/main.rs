@@ -2,8 +2,6 @@ use std::env;
 use std::process;
 use texteditor::config::Config;
 
-pub mod text_editor;
-
 fn main() -> Result<(), std::io::Error> {
     let config = Config::build(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {err}");
@@ -0,0 +1,83 @@
+// Text storage behind the editor, addressed by line and character column.
+// Columns count chars, a line never contains the '\n' separating it from the next one.
+pub trait TextBuffer {
+    fn line_count(&self) -> usize;
+
+    fn line(&self, index: usize) -> String;
+
+    fn line_length(&self, index: usize) -> usize;
+
+    // Function to insert text at the position, '\n' in the text starts new lines
+    fn insert(&mut self, pos: (usize, usize), text: &str);
+
+    // Function to delete `count` chars starting at the position, line ends count as one char
+    fn delete(&mut self, pos: (usize, usize), count: usize);
+
+    fn text(&self) -> String;
+}
+
+// Vector of strings, every edit shifts the rest of the line or the rest of the lines
+pub struct LineBuffer {
+    lines: Vec<String>,
+}
+
+impl LineBuffer {
+    pub fn build(initial: &str) -> Self {
+        LineBuffer {
+            lines: initial.split('\n').map(String::from).collect(),
+        }
+    }
+
+    fn byte_index(&self, line: usize, column: usize) -> usize {
+        self.lines[line]
+            .char_indices()
+            .nth(column)
+            .map_or(self.lines[line].len(), |(index, _)| index)
+    }
+}
+
+impl TextBuffer for LineBuffer {
+    fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    fn line(&self, index: usize) -> String {
+        self.lines[index].clone()
+    }
+
+    fn line_length(&self, index: usize) -> usize {
+        self.lines[index].chars().count()
+    }
+
+    fn insert(&mut self, (line_idx, col_idx): (usize, usize), text: &str) {
+        let index = self.byte_index(line_idx, col_idx);
+        let rest_of_line = self.lines[line_idx].split_off(index);
+
+        let mut new_lines = text.split('\n');
+        if let Some(first) = new_lines.next() {
+            self.lines[line_idx].push_str(first);
+        }
+        let mut last_idx = line_idx;
+        for new_line in new_lines {
+            last_idx += 1;
+            self.lines.insert(last_idx, String::from(new_line));
+        }
+        self.lines[last_idx].push_str(&rest_of_line);
+    }
+
+    fn delete(&mut self, (line_idx, col_idx): (usize, usize), count: usize) {
+        for _ in 0..count {
+            if col_idx < self.line_length(line_idx) {
+                let index = self.byte_index(line_idx, col_idx);
+                self.lines[line_idx].remove(index);
+            } else if line_idx + 1 < self.lines.len() {
+                let next_line = self.lines.remove(line_idx + 1);
+                self.lines[line_idx].push_str(&next_line);
+            }
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
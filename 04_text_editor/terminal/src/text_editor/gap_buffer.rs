@@ -1,7 +1,18 @@
+use crate::text_editor::buffer::TextBuffer;
+
+const MIN_GAP_SIZE: usize = 64;
+
+// Chars of the whole text with a gap at the cursor, so edits in one place only touch the gap.
+// Moving the cursor moves the gap, copying just the chars between the old and new position.
 pub struct GapBuffer {
     buffer: Vec<char>,
     gap_start: usize,
     gap_end: usize,
+    // Line starts before the gap as offsets from the text start, the rest as offsets
+    // from the text end, so edits at the gap never shift them
+    line_starts: Vec<usize>,
+    // Number of line starts before the gap
+    gap_line: usize,
 }
 
 impl GapBuffer {
@@ -13,57 +24,167 @@ impl GapBuffer {
             buffer,
             gap_start: 0,
             gap_end: size,
+            line_starts: vec![0],
+            gap_line: 1,
         }
     }
 
     pub fn build(initial: &str) -> Self {
-        let size = initial.len();
+        // Leave room for edits, so the first key press doesn't copy the whole text
+        let mut buffer = GapBuffer::new(initial.len() + initial.len() / 16 + MIN_GAP_SIZE);
+        buffer.insert_str(initial);
+        buffer.move_cursor(0);
+        buffer
+    }
 
-        let mut buffer = Vec::with_capacity(size);
-        buffer.extend(initial.chars());
+    fn gap_size(&self) -> usize {
+        self.gap_end - self.gap_start
+    }
 
-        GapBuffer {
-            buffer,
-            gap_start: size,
-            gap_end: size,
-        }
+    pub fn len(&self) -> usize {
+        self.buffer.len() - self.gap_size()
     }
 
-    pub fn insert(&mut self, c: char) {
-        if self.gap_start == self.gap_end {
-            self.resize_buffer();
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-        self.buffer[self.gap_start] = c;
-        self.gap_start += 1;
+    // Function to get the char at a text offset, skipping the gap
+    fn char_at(&self, pos: usize) -> char {
+        if pos < self.gap_start {
+            self.buffer[pos]
+        } else {
+            self.buffer[pos + self.gap_size()]
+        }
     }
 
-    pub fn delete(&mut self) {
-        if self.gap_start > 0 {
-            self.gap_start -= 1;
-            self.buffer[self.gap_start] = ' ';
+    fn line_start(&self, index: usize) -> usize {
+        if index < self.gap_line {
+            self.line_starts[index]
+        } else {
+            self.len() - self.line_starts[index]
         }
     }
 
-    fn _move_cursor(&mut self, offset: isize) {
-        let new_pos = (self.gap_start as isize + offset) as usize;
-        if new_pos <= self.gap_end {
-            self.gap_start = new_pos;
+    // Function to move the gap to a text offset
+    pub fn move_cursor(&mut self, pos: usize) {
+        let pos = std::cmp::min(pos, self.len());
+
+        // Line starts passed by the gap switch between counting from the start and the end
+        while self.gap_line > 1 && self.line_starts[self.gap_line - 1] > pos {
+            self.gap_line -= 1;
+            self.line_starts[self.gap_line] = self.len() - self.line_starts[self.gap_line];
+        }
+        while self.gap_line < self.line_starts.len() && self.line_start(self.gap_line) <= pos {
+            self.line_starts[self.gap_line] = self.line_start(self.gap_line);
+            self.gap_line += 1;
+        }
+
+        if pos < self.gap_start {
+            let count = self.gap_start - pos;
+            self.buffer
+                .copy_within(pos..self.gap_start, self.gap_end - count);
+            self.gap_start = pos;
+            self.gap_end -= count;
+        } else if pos > self.gap_start {
+            let count = pos - self.gap_start;
+            self.buffer
+                .copy_within(self.gap_end..self.gap_end + count, self.gap_start);
+            self.gap_start += count;
+            self.gap_end += count;
         }
     }
 
-    fn resize_buffer(&mut self) {
+    // Function to grow the buffer so the gap fits at least `required` chars
+    fn resize_buffer(&mut self, required: usize) {
         let old_len = self.buffer.len();
-        let new_len = old_len * 2;
+        let new_len =
+            std::cmp::max(old_len * 2, old_len - self.gap_size() + required).max(MIN_GAP_SIZE);
+        let tail_len = old_len - self.gap_end;
+
         self.buffer.resize(new_len, ' ');
-        let gap_size = self.gap_end - self.gap_start;
         self.buffer
-            .copy_within(self.gap_end..old_len, old_len + gap_size);
-        self.gap_end = new_len;
+            .copy_within(self.gap_end..old_len, new_len - tail_len);
+        self.gap_end = new_len - tail_len;
+    }
+
+    // Function to insert text at the cursor, leaving the cursor after it
+    pub fn insert_str(&mut self, text: &str) {
+        let count = text.chars().count();
+        if self.gap_size() < count {
+            self.resize_buffer(count);
+        }
+
+        let pos = self.gap_start;
+        let mut new_starts = vec![];
+        for (offset, c) in text.chars().enumerate() {
+            self.buffer[self.gap_start] = c;
+            self.gap_start += 1;
+            if c == '\n' {
+                new_starts.push(pos + offset + 1);
+            }
+        }
+        self.gap_line += new_starts.len();
+        self.line_starts.splice(
+            self.gap_line - new_starts.len()..self.gap_line - new_starts.len(),
+            new_starts,
+        );
+    }
+
+    // Function to delete chars after the cursor
+    pub fn delete_forward(&mut self, count: usize) {
+        let pos = self.gap_start;
+        let count = std::cmp::min(count, self.len() - pos);
+
+        // Lines whose '\n' is deleted merge with the previous one
+        let mut last_removed = self.gap_line;
+        while last_removed < self.line_starts.len() && self.line_start(last_removed) <= pos + count
+        {
+            last_removed += 1;
+        }
+        self.line_starts.drain(self.gap_line..last_removed);
+
+        self.gap_end += count;
+    }
+
+    // Function to turn a (line, column) position into a text offset
+    fn offset(&self, (line_idx, col_idx): (usize, usize)) -> usize {
+        self.line_start(line_idx) + std::cmp::min(col_idx, self.line_length(line_idx))
+    }
+}
+
+impl TextBuffer for GapBuffer {
+    fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    fn line(&self, index: usize) -> String {
+        let start = self.line_start(index);
+        (start..start + self.line_length(index))
+            .map(|pos| self.char_at(pos))
+            .collect()
+    }
+
+    fn line_length(&self, index: usize) -> usize {
+        if index + 1 < self.line_starts.len() {
+            self.line_start(index + 1) - self.line_start(index) - 1
+        } else {
+            self.len() - self.line_start(index)
+        }
+    }
+
+    fn insert(&mut self, pos: (usize, usize), text: &str) {
+        self.move_cursor(self.offset(pos));
+        self.insert_str(text);
+    }
+
+    fn delete(&mut self, pos: (usize, usize), count: usize) {
+        self.move_cursor(self.offset(pos));
+        self.delete_forward(count);
     }
 
-    pub fn to_string(&self) -> String {
-        let mut result = String::with_capacity(self.buffer.len() - (self.gap_end - self.gap_start));
+    fn text(&self) -> String {
+        let mut result = String::with_capacity(self.len());
         result.extend(&self.buffer[..self.gap_start]);
         result.extend(&self.buffer[self.gap_end..]);
         result
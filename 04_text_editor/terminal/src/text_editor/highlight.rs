@@ -0,0 +1,578 @@
+use std::cell::RefCell;
+use std::path::Path;
+
+use crate::text_editor::buffer::TextBuffer;
+use crate::text_editor::unicode::{self, Span};
+
+const KEYWORD: &str = "\x1b[35m"; // magenta
+const TYPE: &str = "\x1b[36m"; // cyan
+const STRING: &str = "\x1b[32m"; // green
+const NUMBER: &str = "\x1b[33m"; // yellow
+const COMMENT: &str = "\x1b[90m"; // gray
+const NAME: &str = "\x1b[34m"; // blue: macros, attributes and keys
+const HEADING: &str = "\x1b[1;34m"; // bold blue
+const BOLD: &str = "\x1b[1m";
+const ITALIC: &str = "\x1b[3m";
+const LINK: &str = "\x1b[4;34m"; // underlined blue
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Rust,
+    Toml,
+    Markdown,
+    Json,
+}
+
+impl Language {
+    // Function to pick the language by the file extension
+    pub fn from_path(path: &str) -> Option<Language> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Language::Rust),
+            "toml" => Some(Language::Toml),
+            "md" | "markdown" => Some(Language::Markdown),
+            "json" => Some(Language::Json),
+            _ => None,
+        }
+    }
+}
+
+// What a line leaves open for the next one
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Normal,
+    BlockComment(usize), // nesting depth
+    String,
+    RawString(usize),      // number of '#'
+    MultilineString(char), // quote of the """ or ''' string
+    CodeBlock,
+}
+
+#[derive(Clone)]
+struct LineHighlight {
+    start: State,
+    end: State,
+    spans: Vec<Span>,
+}
+
+#[derive(Default)]
+struct Cache {
+    lines: Vec<Option<LineHighlight>>, // `None` for lines changed since they were highlighted
+    checked: usize,                    // number of leading lines known to be up to date
+}
+
+// Highlighting of the buffer lines, kept between redraws. Edits only drop the changed lines,
+// the lines below them are tokenized again only if the state they start with changed.
+pub struct Highlighter {
+    language: Language,
+    cache: RefCell<Cache>,
+}
+
+impl Highlighter {
+    pub fn new(language: Language) -> Self {
+        Highlighter {
+            language,
+            cache: RefCell::new(Cache::default()),
+        }
+    }
+
+    // Function to drop the highlighting of a changed line, `removed` lines after it were
+    // joined into it and `added` new lines follow it now
+    pub fn edited(&self, line: usize, removed: usize, added: usize) {
+        let mut cache = self.cache.borrow_mut();
+        cache.checked = std::cmp::min(cache.checked, line);
+
+        let lines = &mut cache.lines;
+        if line + removed < lines.len() {
+            lines.splice(line..=line + removed, std::iter::repeat_n(None, added + 1));
+        } else {
+            lines.truncate(line);
+        }
+    }
+
+    // Function to get the spans of a line, tokenizing the lines above it first when needed
+    pub fn spans(&self, buffer: &dyn TextBuffer, index: usize) -> Vec<Span> {
+        let mut cache = self.cache.borrow_mut();
+
+        while cache.checked <= index {
+            let line_idx = cache.checked;
+            let start = match line_idx {
+                0 => State::Normal,
+                _ => cache.lines[line_idx - 1]
+                    .as_ref()
+                    .map_or(State::Normal, |line| line.end),
+            };
+
+            let up_to_date =
+                matches!(cache.lines.get(line_idx), Some(Some(line)) if line.start == start);
+            if !up_to_date {
+                let highlight = self.tokenize(&buffer.line(line_idx), start);
+                if line_idx < cache.lines.len() {
+                    cache.lines[line_idx] = Some(highlight);
+                } else {
+                    cache.lines.push(Some(highlight));
+                }
+            }
+            cache.checked += 1;
+        }
+
+        cache.lines[index]
+            .as_ref()
+            .map_or(vec![], |line| line.spans.clone())
+    }
+
+    // Function to find the spans of a line in grapheme columns
+    fn tokenize(&self, line: &str, start: State) -> LineHighlight {
+        let chars: Vec<char> = line.chars().collect();
+        let mut spans = vec![];
+        let end = match self.language {
+            Language::Rust => rust_line(&chars, start, &mut spans),
+            Language::Toml => toml_line(&chars, start, &mut spans),
+            Language::Markdown => markdown_line(&chars, start, &mut spans),
+            Language::Json => json_line(&chars, &mut spans),
+        };
+
+        // Tokenizers count chars, a grapheme gets the style of its chars
+        if !line.is_ascii() {
+            let mut columns = Vec::with_capacity(chars.len());
+            for (column, grapheme) in unicode::graphemes(line).enumerate() {
+                columns.extend(std::iter::repeat_n(column, grapheme.chars().count()));
+            }
+            for span in &mut spans {
+                span.start = columns[span.start];
+                span.end = columns[span.end - 1] + 1;
+            }
+        }
+
+        LineHighlight { start, end, spans }
+    }
+}
+
+fn push(spans: &mut Vec<Span>, start: usize, end: usize, style: &'static str) {
+    if start < end {
+        spans.push(Span { start, end, style });
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn word_end(chars: &[char], start: usize) -> usize {
+    start
+        + chars[start..]
+            .iter()
+            .take_while(|c| is_word_char(**c))
+            .count()
+}
+
+// Function to find where a pattern starts, looking from `start`
+fn find(chars: &[char], start: usize, pattern: &[char]) -> Option<usize> {
+    (start..chars.len()).find(|index| chars[*index..].starts_with(pattern))
+}
+
+fn next_non_space(chars: &[char], start: usize) -> Option<char> {
+    chars[start..].iter().copied().find(|c| !c.is_whitespace())
+}
+
+// Function to find the end of a quoted string, looking after its opening quote.
+// Returns the index after the closing quote, or the line end with `false` when it isn't closed.
+fn quoted(chars: &[char], start: usize, quote: char, escapes: bool) -> (usize, bool) {
+    let mut index = start;
+    while index < chars.len() {
+        if escapes && chars[index] == '\\' {
+            index += 2;
+        } else if chars[index] == quote {
+            return (index + 1, true);
+        } else {
+            index += 1;
+        }
+    }
+    (chars.len(), false)
+}
+
+// Function to find the end of a nested /* */ comment, returns the depth still open at the line end
+fn block_comment(chars: &[char], start: usize, mut depth: usize) -> (usize, usize) {
+    let mut index = start;
+    while index < chars.len() {
+        if chars[index..].starts_with(&['/', '*']) {
+            depth += 1;
+            index += 2;
+        } else if chars[index..].starts_with(&['*', '/']) {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return (index, 0);
+            }
+        } else {
+            index += 1;
+        }
+    }
+    (chars.len(), depth)
+}
+
+// Function to find the end of a r#"..."# string, looking after its opening quote
+fn raw_string_end(chars: &[char], start: usize, hashes: usize) -> (usize, bool) {
+    let mut closing = vec!['"'];
+    closing.extend(std::iter::repeat_n('#', hashes));
+    match find(chars, start, &closing) {
+        Some(index) => (index + closing.len(), true),
+        None => (chars.len(), false),
+    }
+}
+
+// Function to recognize r"", r#""#, br"" openings, returns the length of the opening and the '#' count
+fn raw_string_start(chars: &[char], start: usize) -> Option<(usize, usize)> {
+    let prefix = if chars[start..].starts_with(&['b', 'r']) {
+        2
+    } else if chars[start] == 'r' {
+        1
+    } else {
+        return None;
+    };
+    let hashes = chars[start + prefix..]
+        .iter()
+        .take_while(|c| **c == '#')
+        .count();
+    (chars.get(start + prefix + hashes) == Some(&'"')).then_some((prefix + hashes + 1, hashes))
+}
+
+// Function to find the end of a 'c' or '\n' literal, `None` for lifetimes like 'a
+fn char_literal_end(chars: &[char], start: usize) -> Option<usize> {
+    if chars.get(start + 1) == Some(&'\\') {
+        let (end, closed) = quoted(chars, start + 1, '\'', true);
+        return closed.then_some(end);
+    }
+    (chars.get(start + 2) == Some(&'\'')).then_some(start + 3)
+}
+
+fn number_end(chars: &[char], start: usize) -> usize {
+    let mut index = start;
+    while index < chars.len() {
+        let c = chars[index];
+        let is_fraction = c == '.'
+            && chars
+                .get(index + 1)
+                .is_some_and(|next| next.is_ascii_digit());
+        if !is_word_char(c) && !is_fraction {
+            break;
+        }
+        index += 1;
+    }
+    index
+}
+
+fn rust_line(chars: &[char], start: State, spans: &mut Vec<Span>) -> State {
+    // Finish what the previous line left open
+    let mut index = match start {
+        State::BlockComment(depth) => {
+            let (end, depth) = block_comment(chars, 0, depth);
+            push(spans, 0, end, COMMENT);
+            if depth > 0 {
+                return State::BlockComment(depth);
+            }
+            end
+        }
+        State::String => {
+            let (end, closed) = quoted(chars, 0, '"', true);
+            push(spans, 0, end, STRING);
+            if !closed {
+                return State::String;
+            }
+            end
+        }
+        State::RawString(hashes) => {
+            let (end, closed) = raw_string_end(chars, 0, hashes);
+            push(spans, 0, end, STRING);
+            if !closed {
+                return State::RawString(hashes);
+            }
+            end
+        }
+        _ => 0,
+    };
+
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+
+        if c == '/' && next == Some('/') {
+            push(spans, index, chars.len(), COMMENT);
+            break;
+        }
+        if c == '/' && next == Some('*') {
+            let (end, depth) = block_comment(chars, index + 2, 1);
+            push(spans, index, end, COMMENT);
+            if depth > 0 {
+                return State::BlockComment(depth);
+            }
+            index = end;
+        } else if c == '"' || (c == 'b' && next == Some('"')) {
+            let opening = if c == 'b' { 2 } else { 1 };
+            let (end, closed) = quoted(chars, index + opening, '"', true);
+            push(spans, index, end, STRING);
+            if !closed {
+                return State::String;
+            }
+            index = end;
+        } else if let Some((opening, hashes)) = raw_string_start(chars, index) {
+            let (end, closed) = raw_string_end(chars, index + opening, hashes);
+            push(spans, index, end, STRING);
+            if !closed {
+                return State::RawString(hashes);
+            }
+            index = end;
+        } else if c == '\'' || (c == 'b' && next == Some('\'')) {
+            let quote = if c == 'b' { index + 1 } else { index };
+            match char_literal_end(chars, quote) {
+                Some(end) => {
+                    push(spans, index, end, STRING);
+                    index = end;
+                }
+                // Lifetime
+                None => index = word_end(chars, quote + 1),
+            }
+        } else if c.is_ascii_digit() {
+            let end = number_end(chars, index);
+            push(spans, index, end, NUMBER);
+            index = end;
+        } else if c == '#' && matches!(next, Some('[') | Some('!')) {
+            let end = find(chars, index, &[']']).map_or(chars.len(), |end| end + 1);
+            push(spans, index, end, NAME);
+            index = end;
+        } else if is_word_char(c) {
+            let end = word_end(chars, index);
+            let word: String = chars[index..end].iter().collect();
+            if RUST_KEYWORDS.contains(&word.as_str()) {
+                push(spans, index, end, KEYWORD);
+            } else if chars.get(end) == Some(&'!') {
+                push(spans, index, end + 1, NAME);
+            } else if c.is_uppercase() {
+                push(spans, index, end, TYPE);
+            }
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    State::Normal
+}
+
+// Function to find the end of a """ or ''' string, looking after its opening quotes
+fn multiline_string_end(chars: &[char], start: usize, quote: char) -> (usize, bool) {
+    let mut index = start;
+    while index < chars.len() {
+        if quote == '"' && chars[index] == '\\' {
+            index += 2;
+        } else if chars[index..].starts_with(&[quote; 3]) {
+            return (index + 3, true);
+        } else {
+            index += 1;
+        }
+    }
+    (chars.len(), false)
+}
+
+fn toml_line(chars: &[char], start: State, spans: &mut Vec<Span>) -> State {
+    let mut index = 0;
+    if let State::MultilineString(quote) = start {
+        let (end, closed) = multiline_string_end(chars, 0, quote);
+        push(spans, 0, end, STRING);
+        if !closed {
+            return start;
+        }
+        index = end;
+    }
+
+    // [table] and [[array]] headers
+    let first = chars.iter().position(|c| !c.is_whitespace());
+    if let Some(first) = first.filter(|first| index == 0 && chars[*first] == '[') {
+        let end = find(chars, first, &[']']).map_or(chars.len(), |end| end + 1);
+        let end = if chars.get(end) == Some(&']') {
+            end + 1
+        } else {
+            end
+        };
+        push(spans, first, end, TYPE);
+        index = end;
+    }
+
+    while index < chars.len() {
+        let c = chars[index];
+        if c == '#' {
+            push(spans, index, chars.len(), COMMENT);
+            break;
+        }
+
+        if c == '"' || c == '\'' {
+            if chars[index..].starts_with(&[c; 3]) {
+                let (end, closed) = multiline_string_end(chars, index + 3, c);
+                push(spans, index, end, STRING);
+                if !closed {
+                    return State::MultilineString(c);
+                }
+                index = end;
+                continue;
+            }
+
+            let (end, _) = quoted(chars, index + 1, c, c == '"');
+            let style = if next_non_space(chars, end) == Some('=') {
+                NAME
+            } else {
+                STRING
+            };
+            push(spans, index, end, style);
+            index = end;
+        } else if is_word_char(c) || c == '-' || c == '+' {
+            // Bare keys, booleans, numbers and dates
+            let length = chars[index..]
+                .iter()
+                .take_while(|c| is_word_char(**c) || matches!(c, '-' | '+' | '.' | ':'))
+                .count();
+            let end = index + length;
+            let word: String = chars[index..end].iter().collect();
+            let is_number = word
+                .trim_start_matches(['+', '-'])
+                .starts_with(|c: char| c.is_ascii_digit())
+                || matches!(word.trim_start_matches(['+', '-']), "inf" | "nan");
+
+            if next_non_space(chars, end) == Some('=') {
+                push(spans, index, end, NAME);
+            } else if word == "true" || word == "false" {
+                push(spans, index, end, KEYWORD);
+            } else if is_number {
+                push(spans, index, end, NUMBER);
+            }
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    State::Normal
+}
+
+fn json_line(chars: &[char], spans: &mut Vec<Span>) -> State {
+    let mut index = 0;
+    while index < chars.len() {
+        let c = chars[index];
+        if c == '"' {
+            let (end, _) = quoted(chars, index + 1, '"', true);
+            let style = if next_non_space(chars, end) == Some(':') {
+                NAME
+            } else {
+                STRING
+            };
+            push(spans, index, end, style);
+            index = end;
+        } else if c == '-' || c.is_ascii_digit() {
+            let length = chars[index..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                .count();
+            push(spans, index, index + length, NUMBER);
+            index += length;
+        } else if c.is_alphabetic() {
+            let end = word_end(chars, index);
+            let word: String = chars[index..end].iter().collect();
+            if matches!(word.as_str(), "true" | "false" | "null") {
+                push(spans, index, end, KEYWORD);
+            }
+            index = end;
+        } else {
+            index += 1;
+        }
+    }
+    State::Normal
+}
+
+fn markdown_line(chars: &[char], start: State, spans: &mut Vec<Span>) -> State {
+    let first = chars
+        .iter()
+        .position(|c| !c.is_whitespace())
+        .unwrap_or(chars.len());
+    let rest = &chars[first..];
+    let is_fence = rest.starts_with(&['`'; 3]) || rest.starts_with(&['~'; 3]);
+
+    // Code blocks are drawn as one string, fences included
+    if start == State::CodeBlock || is_fence {
+        push(spans, 0, chars.len(), STRING);
+        return match (start, is_fence) {
+            (State::CodeBlock, false) | (State::Normal, true) => State::CodeBlock,
+            _ => State::Normal,
+        };
+    }
+
+    let hashes = rest.iter().take_while(|c| **c == '#').count();
+    if (1..=6).contains(&hashes) && rest.get(hashes).is_none_or(|c| *c == ' ') {
+        push(spans, 0, chars.len(), HEADING);
+        return State::Normal;
+    }
+    if rest.first() == Some(&'>') {
+        push(spans, 0, chars.len(), COMMENT);
+        return State::Normal;
+    }
+
+    // List markers
+    let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+    let marker = if matches!(rest, ['-' | '*' | '+', ' ', ..]) {
+        1
+    } else if digits > 0 && rest[digits..].starts_with(&['.', ' ']) {
+        digits + 1
+    } else {
+        0
+    };
+    push(spans, first, first + marker, KEYWORD);
+
+    markdown_inline(chars, first + marker, spans);
+    State::Normal
+}
+
+// Function to find `code`, **bold**, *italic* and [links](url) in a line
+fn markdown_inline(chars: &[char], start: usize, spans: &mut Vec<Span>) {
+    let mut index = start;
+    while index < chars.len() {
+        let c = chars[index];
+        let next = chars.get(index + 1).copied();
+        let after_word = index > 0 && is_word_char(chars[index - 1]);
+
+        let found = if c == '`' {
+            find(chars, index + 1, &['`']).map(|end| (end + 1, STRING))
+        } else if (c == '*' || c == '_') && next == Some(c) && !after_word {
+            find(chars, index + 2, &[c, c]).map(|end| (end + 2, BOLD))
+        } else if (c == '*' || c == '_')
+            && next.is_some_and(|next| !next.is_whitespace())
+            && !after_word
+        {
+            find(chars, index + 1, &[c]).map(|end| (end + 1, ITALIC))
+        } else if c == '[' {
+            let link = find(chars, index, &[']', '(']).and_then(|close| {
+                let end = find(chars, close, &[')'])?;
+                Some((close, end + 1))
+            });
+            if let Some((close, end)) = link {
+                push(spans, index, close + 1, LINK);
+                push(spans, close + 1, end, COMMENT);
+                index = end;
+                continue;
+            }
+            None
+        } else {
+            None
+        };
+
+        match found {
+            Some((end, style)) => {
+                push(spans, index, end, style);
+                index = end;
+            }
+            None => index += 1,
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::text_editor::buffer::TextBuffer;
+
+// Single change of the buffer, positions are (line, char column) like in `TextBuffer`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Insert { pos: (usize, usize), text: String },
+    Delete { pos: (usize, usize), text: String },
+}
+
+impl Edit {
+    pub fn apply(&self, buffer: &mut dyn TextBuffer) {
+        match self {
+            Edit::Insert { pos, text } => buffer.insert(*pos, text),
+            Edit::Delete { pos, text } => buffer.delete(*pos, text.chars().count()),
+        }
+    }
+
+    fn revert(&self, buffer: &mut dyn TextBuffer) {
+        match self {
+            Edit::Insert { pos, text } => buffer.delete(*pos, text.chars().count()),
+            Edit::Delete { pos, text } => buffer.insert(*pos, text),
+        }
+    }
+
+    // Function to get (changed line, lines joined into it, lines added after it) when the edit
+    // is applied or reverted
+    pub fn line_changes(&self, reverted: bool) -> (usize, usize, usize) {
+        let (Edit::Insert { pos, text } | Edit::Delete { pos, text }) = self;
+        let new_lines = text.matches('\n').count();
+
+        if matches!(self, Edit::Insert { .. }) != reverted {
+            (pos.0, 0, new_lines)
+        } else {
+            (pos.0, new_lines, 0)
+        }
+    }
+}
+
+// Kind of consecutive edits merged into one undo step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Group {
+    Typing,
+    Deleting,
+}
+
+// Edits undone together, with the editor cursor around them
+#[derive(Debug)]
+pub struct Step {
+    id: usize,
+    edits: Vec<Edit>,
+    group: Option<Group>,
+    pub cursor_before: (usize, usize),
+    pub cursor_after: (usize, usize),
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    next_id: usize,
+    saved: Option<usize>, // last step when the text was saved, `None` before any step
+}
+
+impl Step {
+    pub fn edits(&self) -> &[Edit] {
+        &self.edits
+    }
+}
+
+impl History {
+    // Function to remember an edit already applied to the buffer.
+    // It joins the last step when both are of the same group and the cursor hasn't moved since.
+    pub fn record(
+        &mut self,
+        edit: Edit,
+        group: Option<Group>,
+        cursor_before: (usize, usize),
+        cursor_after: (usize, usize),
+    ) {
+        self.redo.clear();
+
+        if let Some(last) = self.undo.last_mut() {
+            if group.is_some() && last.group == group && last.cursor_after == cursor_before {
+                last.edits.push(edit);
+                last.cursor_after = cursor_after;
+                return;
+            }
+        }
+
+        self.undo.push(Step {
+            id: self.next_id,
+            edits: vec![edit],
+            group,
+            cursor_before,
+            cursor_after,
+        });
+        self.next_id += 1;
+    }
+
+    // Function to remember edits already applied to the buffer as one step of their own
+    pub fn record_step(
+        &mut self,
+        edits: Vec<Edit>,
+        cursor_before: (usize, usize),
+        cursor_after: (usize, usize),
+    ) {
+        if edits.is_empty() {
+            return;
+        }
+
+        self.redo.clear();
+        self.undo.push(Step {
+            id: self.next_id,
+            edits,
+            group: None,
+            cursor_before,
+            cursor_after,
+        });
+        self.next_id += 1;
+    }
+
+    // Function to get the id of the last applied step, it identifies the current text
+    pub fn current(&self) -> Option<usize> {
+        self.undo.last().map(|step| step.id)
+    }
+
+    // Function to remember the current text as the one on disk
+    pub fn mark_saved(&mut self) {
+        self.saved = self.current();
+        // Typing after saving doesn't extend the saved step
+        if let Some(last) = self.undo.last_mut() {
+            last.group = None;
+        }
+    }
+
+    // Function to check whether undo or redo or new edits moved the text away from the saved one
+    pub fn is_modified(&self) -> bool {
+        self.current() != self.saved
+    }
+
+    // Function to revert the last step, returns it to restore the cursor from
+    pub fn undo(&mut self, buffer: &mut dyn TextBuffer) -> Option<&Step> {
+        let step = self.undo.pop()?;
+        for edit in step.edits.iter().rev() {
+            edit.revert(buffer);
+        }
+
+        self.redo.push(step);
+        // Typing after undo doesn't extend the step before the undone one
+        if let Some(last) = self.undo.last_mut() {
+            last.group = None;
+        }
+        self.redo.last()
+    }
+
+    // Function to apply the last undone step again, returns it to restore the cursor from
+    pub fn redo(&mut self, buffer: &mut dyn TextBuffer) -> Option<&Step> {
+        let mut step = self.redo.pop()?;
+        for edit in &step.edits {
+            edit.apply(buffer);
+        }
+
+        // Typing after redo starts a new step
+        step.group = None;
+        self.undo.push(step);
+        self.undo.last()
+    }
+}
@@ -0,0 +1,101 @@
+use termion::event::Key;
+
+use crate::text_editor::TextEditor;
+
+// Gray
+const GUTTER_STYLE: &str = "\x1b[90m";
+// Columns always taken by numbers, so the text doesn't move while the first lines are added
+const MIN_NUMBER_WIDTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gutter {
+    Off,
+    Absolute,
+    Relative, // distance to the cursor line, which keeps its own number
+}
+
+impl TextEditor {
+    // Function to switch between no gutter, absolute and relative line numbers
+    pub fn toggle_gutter(&mut self) {
+        self.gutter = match self.gutter {
+            Gutter::Off => Gutter::Absolute,
+            Gutter::Absolute => Gutter::Relative,
+            Gutter::Relative => Gutter::Off,
+        };
+    }
+
+    // Function to get the number of columns the gutter takes, numbers are followed by a space
+    pub fn gutter_width(&self) -> usize {
+        if self.gutter == Gutter::Off {
+            return 0;
+        }
+        let digits = self.buffer.line_count().to_string().len();
+        digits.max(MIN_NUMBER_WIDTH) + 1
+    }
+
+    // Function to render the gutter next to a screen row, wrapped rows get no number
+    pub(super) fn gutter_text(&self, line_idx: usize, first_row: bool) -> String {
+        let width = self.gutter_width();
+        if width == 0 {
+            return String::new();
+        }
+        if !first_row {
+            return " ".repeat(width);
+        }
+
+        let number = match self.gutter {
+            Gutter::Relative if line_idx != self.cursor_pos.0 => {
+                line_idx.abs_diff(self.cursor_pos.0)
+            }
+            _ => line_idx + 1,
+        };
+        format!("{}{:>2$} \x1b[0m", GUTTER_STYLE, number, width - 1)
+    }
+
+    // Function to move the cursor to "line" or "line:column", both counted from 1 like in compiler errors
+    pub fn go_to(&mut self, target: &str) -> Result<(), &'static str> {
+        let mut parts = target.trim().splitn(2, ':');
+        let line = parse_position(parts.next())?.ok_or("Enter a line number")?;
+        let column = parse_position(parts.next())?.unwrap_or(1);
+
+        let line_idx = std::cmp::min(line, self.buffer.line_count()) - 1;
+        let col_idx = std::cmp::min(column - 1, self.get_line_length(line_idx));
+        self.selection = None;
+        self.cursor_pos = (line_idx, col_idx);
+        Ok(())
+    }
+
+    // Function to handle a key while the go to line prompt is open
+    pub fn go_to_key(&mut self, key: Key) {
+        let Some(input) = self.go_to_input.as_mut() else {
+            return;
+        };
+
+        match key {
+            Key::Char('\n') => {
+                let target = input.clone();
+                self.go_to_input = None;
+                if let Err(err) = self.go_to(&target) {
+                    self.show_notification(err);
+                }
+            }
+            Key::Char(c) if c.is_ascii_digit() || c == ':' => input.push(c),
+            Key::Backspace => {
+                input.pop();
+            }
+            Key::Esc => self.go_to_input = None,
+            _ => (),
+        }
+    }
+}
+
+// Function to parse a 1-based position, `None` when it is missing
+fn parse_position(part: Option<&str>) -> Result<Option<usize>, &'static str> {
+    match part.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(part) => match part.parse::<usize>() {
+            Ok(0) | Err(_) => Err("Invalid line:column"),
+            Ok(position) => Ok(Some(position)),
+        },
+    }
+}
@@ -1,13 +1,59 @@
 use std::io::{self, Stdout, Write};
-use termion::cursor::{DetectCursorPos, Hide};
+use termion::cursor::{DetectCursorPos, Goto, Hide};
 use termion::raw::RawTerminal;
 
+pub mod buffer;
+pub mod gap_buffer;
+pub mod highlight;
+pub mod history;
+pub mod line_numbers;
+mod motion;
+pub mod regex;
+mod rows;
+pub mod search;
+mod selection;
+pub mod status;
+pub mod unicode;
+
+use buffer::TextBuffer;
+use gap_buffer::GapBuffer;
+use highlight::Highlighter;
+use history::{Edit, Group, History};
+use line_numbers::Gutter;
+use rows::RowCache;
+use search::Search;
+use status::{LineEnding, Notification};
+
 const EDITOR_WIDTH: usize = 20;
+const EDITOR_HEIGHT: usize = 10;
+
 pub struct TextEditor {
     pub width: usize,
-    pub lines: Vec<String>,
-    pub notifications: Vec<String>,
-    pub cursor_pos: (usize, usize), // (line, column)
+    pub height: usize,
+    pub buffer: Box<dyn TextBuffer>,
+    pub notification: Option<Notification>, // transient message in the status bar
+    pub cursor_pos: (usize, usize),         // (line, column)
+    pub scroll: (usize, usize),             // first visible (screen row, column)
+    pub wrap: bool,
+    pub history: History,
+    pub changes: usize, // bumped by every edit applied to the text, undo and redo included
+    pub selection: Option<(usize, usize)>, // where the selection started, it ends at the cursor
+    pub clipboard: String,
+    pub search: Option<Search>, // open search or replace prompt
+    pub file_name: String,
+    pub confirm_quit: bool, // asking whether to save before quitting
+    pub line_ending: LineEnding,
+    pub gutter: Gutter,
+    pub go_to_input: Option<String>, // open go to line prompt
+    pub highlighter: Option<Highlighter>,
+    pub tab_spaces: Option<usize>, // Tab inserts spaces up to the next multiple of it instead of '\t'
+    rows: RowCache,                // screen rows of wrapped lines
+}
+
+impl Default for TextEditor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TextEditor {
@@ -15,116 +61,309 @@ impl TextEditor {
     pub fn new() -> Self {
         TextEditor {
             width: EDITOR_WIDTH,
-            lines: vec![String::new()],
-            notifications: vec![],
+            height: EDITOR_HEIGHT,
+            buffer: Box::new(GapBuffer::new(0)),
+            notification: None,
             cursor_pos: (0, 0),
+            scroll: (0, 0),
+            wrap: true,
+            history: History::default(),
+            changes: 0,
+            selection: None,
+            clipboard: String::new(),
+            search: None,
+            file_name: String::new(),
+            confirm_quit: false,
+            line_ending: LineEnding::Lf,
+            gutter: Gutter::Off,
+            go_to_input: None,
+            highlighter: None,
+            tab_spaces: None,
+            rows: RowCache::default(),
         }
     }
 
     pub fn build(initial: &str) -> Self {
-        let mut lines: Vec<String> = vec![];
-        let mut cursor_pos = (0, 0);
-
-        if initial.lines().count() > 0 {
-            for line in initial.lines() {
-                lines.push(String::from(line));
-                cursor_pos.1 = line.len();
-            }
-            cursor_pos.0 = initial.lines().count() - 1;
+        let mut editor = Self::with_buffer(Box::new(GapBuffer::build(&Self::normalize(initial))));
+        editor.line_ending = LineEnding::detect(initial);
+        editor
+    }
 
-            if initial.ends_with("\n") {
-                lines.push(String::new());
-                cursor_pos.0 += 1;
-                cursor_pos.1 = 0;
-            }
-        } else {
-            lines.push(String::new());
-        }
+    // Function to create a TextEditor on top of any buffer, with the cursor at the end of the text
+    pub fn with_buffer(buffer: Box<dyn TextBuffer>) -> Self {
+        let last_line = buffer.line_count() - 1;
+        let cursor_pos = (last_line, unicode::grapheme_count(&buffer.line(last_line)));
 
         TextEditor {
             width: EDITOR_WIDTH,
-            lines,
+            height: EDITOR_HEIGHT,
+            buffer,
             cursor_pos,
-            notifications: vec![],
+            notification: None,
+            scroll: (0, 0),
+            wrap: true,
+            history: History::default(),
+            changes: 0,
+            selection: None,
+            clipboard: String::new(),
+            search: None,
+            file_name: String::new(),
+            confirm_quit: false,
+            line_ending: LineEnding::Lf,
+            gutter: Gutter::Off,
+            go_to_input: None,
+            highlighter: None,
+            tab_spaces: None,
+            rows: RowCache::default(),
+        }
+    }
+
+    // Function to replace the whole text as one undo step, e.g. with a recovered swap file
+    pub fn replace_text(&mut self, text: &str) {
+        let cursor_before = self.cursor_pos;
+        let last_line = self.buffer.line_count() - 1;
+        let end = (last_line, self.get_line_length(last_line));
+        let edits = self.replace_edits((0, 0), end, &Self::normalize(text));
+        self.history
+            .record_step(edits, cursor_before, self.cursor_pos);
+    }
+
+    pub fn is_modified(&self) -> bool {
+        self.history.is_modified()
+    }
+
+    // Function to drop '\r' of Windows line endings, lines are always separated by '\n'
+    fn normalize(initial: &str) -> String {
+        let mut text = initial.lines().collect::<Vec<&str>>().join("\n");
+        if initial.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+
+    // Function to read `count` chars starting at a (line, char column) position, line ends included
+    fn text_at(&self, (mut line_idx, mut char_idx): (usize, usize), count: usize) -> String {
+        let mut result = String::new();
+        let mut remaining = count;
+
+        while remaining > 0 {
+            let line = self.buffer.line(line_idx);
+            let taken: String = line.chars().skip(char_idx).take(remaining).collect();
+            remaining -= taken.chars().count();
+            result.push_str(&taken);
+
+            if remaining == 0 || line_idx + 1 == self.buffer.line_count() {
+                break;
+            }
+            result.push('\n');
+            remaining -= 1;
+            line_idx += 1;
+            char_idx = 0;
+        }
+        result
+    }
+
+    // Function to change the buffer, dropping the highlighting and rows of the changed lines
+    fn apply(&mut self, edit: &Edit) {
+        edit.apply(self.buffer.as_mut());
+        self.lines_changed(edit.line_changes(false));
+    }
+
+    // Function to drop what is kept about changed lines, `removed` lines after `line` were
+    // joined into it and `added` new lines follow it now
+    fn lines_changed(&mut self, (line, removed, added): (usize, usize, usize)) {
+        self.changes += 1;
+        self.rows.edited(line, removed, added);
+        if let Some(highlighter) = &self.highlighter {
+            highlighter.edited(line, removed, added);
+        }
+    }
+
+    // Function to create an edit deleting `count` chars, keeping them for undo
+    fn delete_edit(&self, pos: (usize, usize), count: usize) -> Edit {
+        Edit::Delete {
+            pos,
+            text: self.text_at(pos, count),
         }
     }
 
     pub fn handle_backspace(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
+
+        let cursor_before = self.cursor_pos;
         let (line_idx, col_idx) = self.cursor_pos;
-        if col_idx > 0 {
-            let line = &mut self.lines[line_idx];
+        let edit = if col_idx > 0 {
+            // Remove the whole grapheme at the left of the cursor
+            let line = self.buffer.line(line_idx);
+            let start = unicode::char_index(&line, col_idx - 1);
+            let count = unicode::char_index(&line, col_idx) - start;
             self.cursor_pos.1 -= 1;
-            line.remove(col_idx - 1); // Remove the character at the left of the cursor
+            self.delete_edit((line_idx, start), count)
+        } else if line_idx > 0 {
+            // move rest of line up
+            self.cursor_pos = (line_idx - 1, self.get_line_length(line_idx - 1));
+            self.delete_edit((line_idx - 1, self.buffer.line_length(line_idx - 1)), 1)
         } else {
-            if line_idx > 0 {
-                let prev_line_len = self.lines[line_idx - 1].len();
+            return;
+        };
 
-                self.cursor_pos.0 -= 1;
-                self.cursor_pos.1 = prev_line_len;
+        self.apply(&edit);
+        self.history
+            .record(edit, Some(Group::Deleting), cursor_before, self.cursor_pos);
+    }
 
-                // move rest of line up
-                let rest_of_line = &self.lines[line_idx][col_idx..];
-                let mut new_line = String::from(&self.lines[line_idx - 1]);
-                new_line.push_str(rest_of_line);
-                self.lines[line_idx - 1] = new_line;
+    // Function to delete the grapheme under the cursor, joining the next line at the line end
+    pub fn delete_forward(&mut self) {
+        if self.delete_selection() {
+            return;
+        }
 
-                self.lines.remove(line_idx);
-            } else {
-                // empty file
-            }
+        let (line_idx, col_idx) = self.cursor_pos;
+        let line = self.buffer.line(line_idx);
+        let start = unicode::char_index(&line, col_idx);
+        let edit = if col_idx < self.get_line_length(line_idx) {
+            let count = unicode::char_index(&line, col_idx + 1) - start;
+            self.delete_edit((line_idx, start), count)
+        } else if line_idx + 1 < self.buffer.line_count() {
+            self.delete_edit((line_idx, start), 1)
+        } else {
+            return;
+        };
+
+        self.apply(&edit);
+        self.history.record(
+            edit,
+            Some(Group::Deleting),
+            self.cursor_pos,
+            self.cursor_pos,
+        );
+    }
+
+    // Function to insert a tab, or spaces up to the next tab stop when `tab_spaces` is set
+    pub fn insert_tab(&mut self) {
+        let Some(tab_spaces) = self.tab_spaces.filter(|spaces| *spaces > 0) else {
+            self.insert_char('\t');
+            return;
+        };
+
+        self.delete_selection();
+        let (line_idx, col_idx) = self.cursor_pos;
+        let (_, x) = unicode::layout(&self.buffer.line(line_idx), None)[col_idx];
+        for _ in 0..tab_spaces - x % tab_spaces {
+            self.insert_char(' ');
         }
     }
 
     pub fn insert_char(&mut self, c: char) {
+        self.delete_selection();
+        let cursor_before = self.cursor_pos;
         let (line_idx, col_idx) = self.cursor_pos;
-        let line = &mut self.lines[line_idx];
-        line.insert(col_idx, c); // Insert the character at the cursor position
-        self.cursor_pos.1 += 1; // Move cursor to the right after insertion
+        let char_idx = unicode::char_index(&self.buffer.line(line_idx), col_idx);
+        let edit = Edit::Insert {
+            pos: (line_idx, char_idx),
+            text: c.to_string(),
+        };
+        self.apply(&edit); // Insert the character at the cursor position
+
+        // Move cursor to the right after insertion, combining marks join the grapheme on the left
+        self.cursor_pos.1 = unicode::column_at_char(&self.buffer.line(line_idx), char_idx + 1);
+        self.history
+            .record(edit, Some(Group::Typing), cursor_before, self.cursor_pos);
     }
 
     // Function to handle newline character
     pub fn handle_newline(&mut self) {
+        self.delete_selection();
+        let cursor_before = self.cursor_pos;
         let (line_idx, col_idx) = self.cursor_pos;
+        let char_idx = unicode::char_index(&self.buffer.line(line_idx), col_idx);
+        let edit = Edit::Insert {
+            pos: (line_idx, char_idx),
+            text: String::from("\n"),
+        };
+        self.apply(&edit);
+
+        self.cursor_pos = (line_idx + 1, 0);
+        self.history
+            .record(edit, None, cursor_before, self.cursor_pos);
+    }
 
-        // create new line
-        let rest_of_line = &self.lines[line_idx][col_idx..];
-        let new_line = String::from(rest_of_line);
+    // Function to revert the last edit step, returns `false` when there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        self.selection = None;
+        let Some(step) = self.history.undo(self.buffer.as_mut()) else {
+            return false;
+        };
+
+        self.cursor_pos = step.cursor_before;
+        let changes: Vec<_> = step
+            .edits()
+            .iter()
+            .rev()
+            .map(|edit| edit.line_changes(true))
+            .collect();
+        for change in changes {
+            self.lines_changed(change);
+        }
+        true
+    }
 
-        // trim current line
-        self.lines[line_idx] = String::from(&self.lines[line_idx][..col_idx]);
+    // Function to apply the last undone step again, returns `false` when there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        self.selection = None;
+        let Some(step) = self.history.redo(self.buffer.as_mut()) else {
+            return false;
+        };
+
+        self.cursor_pos = step.cursor_after;
+        let changes: Vec<_> = step
+            .edits()
+            .iter()
+            .map(|edit| edit.line_changes(false))
+            .collect();
+        for change in changes {
+            self.lines_changed(change);
+        }
+        true
+    }
 
-        self.lines.insert(line_idx + 1, new_line);
-        self.cursor_pos.0 += 1;
-        self.cursor_pos.1 = 0;
+    // Function to get (row, x) of every grapheme of a line and of its end
+    fn line_layout(&self, index: usize) -> Vec<(usize, usize)> {
+        let width = if self.wrap { Some(self.width) } else { None };
+        unicode::layout(&self.buffer.line(index), width)
     }
 
     // Function to move the cursor up
     pub fn cursor_up(&mut self) {
-        if self.cursor_pos.0 > 0 {
-            self.cursor_pos.0 -= 1;
-            let prev_line_len = self.get_line_length(self.cursor_pos.0);
-            if self.cursor_pos.1 >= prev_line_len {
-                if prev_line_len > 0 {
-                    self.cursor_pos.1 = prev_line_len;
-                } else {
-                    self.cursor_pos.1 = 0;
-                }
-            }
+        let (line_idx, col_idx) = self.cursor_pos;
+        let positions = self.line_layout(line_idx);
+        let (row_idx, x) = positions[col_idx];
+
+        if row_idx > 0 {
+            self.cursor_pos.1 = unicode::column_in_row(&positions, row_idx - 1, x);
+        } else if line_idx > 0 {
+            let prev_positions = self.line_layout(line_idx - 1);
+            let last_row = prev_positions[prev_positions.len() - 1].0;
+            self.cursor_pos = (
+                line_idx - 1,
+                unicode::column_in_row(&prev_positions, last_row, x),
+            );
         }
     }
 
     // Function to move the cursor down
     pub fn cursor_down(&mut self) {
         let (line_idx, col_idx) = self.cursor_pos;
-
-        if line_idx < self.lines.len() - 1 {
-            let next_line_len = self.get_line_length(line_idx + 1);
-
-            if col_idx > next_line_len {
-                self.cursor_pos.1 = next_line_len
-            }
-
-            self.cursor_pos.0 += 1;
+        let positions = self.line_layout(line_idx);
+        let (row_idx, x) = positions[col_idx];
+
+        if row_idx < positions[positions.len() - 1].0 {
+            self.cursor_pos.1 = unicode::column_in_row(&positions, row_idx + 1, x);
+        } else if line_idx < self.buffer.line_count() - 1 {
+            let next_positions = self.line_layout(line_idx + 1);
+            self.cursor_pos = (line_idx + 1, unicode::column_in_row(&next_positions, 0, x));
         }
     }
 
@@ -134,11 +373,9 @@ impl TextEditor {
 
         if col_idx > 0 {
             self.cursor_pos.1 -= 1;
-        } else {
-            if line_idx > 0 {
-                self.cursor_pos.0 -= 1;
-                self.cursor_pos.1 = self.get_line_length(line_idx - 1);
-            }
+        } else if line_idx > 0 {
+            self.cursor_pos.0 -= 1;
+            self.cursor_pos.1 = self.get_line_length(line_idx - 1);
         }
     }
 
@@ -148,58 +385,197 @@ impl TextEditor {
 
         if col_idx < self.get_line_length(line_idx) {
             self.cursor_pos.1 += 1;
-        } else {
-            if line_idx < self.lines.len() - 1 {
-                self.cursor_pos = (line_idx + 1, 0)
-            }
+        } else if line_idx < self.buffer.line_count() - 1 {
+            self.cursor_pos = (line_idx + 1, 0)
         }
     }
 
-    // Function to get the length of a specific line
+    // Function to get the length of a specific line in graphemes
     fn get_line_length(&self, line: usize) -> usize {
-        self.lines[line].to_string().len()
+        unicode::grapheme_count(&self.buffer.line(line))
+    }
+
+    // Function to render a line into screen rows, replacing the grapheme under the cursor with a block
+    fn line_to_rows(&self, index: usize, with_cursor: bool) -> Vec<String> {
+        let cursor = if with_cursor && self.cursor_pos.0 == index {
+            Some(self.cursor_pos.1)
+        } else {
+            None
+        };
+        let width = if self.wrap { Some(self.width) } else { None };
+        let mut spans = match &self.highlighter {
+            Some(highlighter) => highlighter.spans(self.buffer.as_ref(), index),
+            None => vec![],
+        };
+        spans.extend(self.search_spans(index));
+        spans.extend(self.selection_spans(index));
+        unicode::render_rows(&self.buffer.line(index), cursor, width, &spans)
+    }
+
+    // Function to count screen rows taken by a line, including the cursor block at its end
+    fn rows_in_line(&self, index: usize) -> usize {
+        if !self.wrap {
+            return 1;
+        }
+
+        let line_rows = self.rows.line_rows(self.buffer.as_ref(), self.width, index);
+        line_rows.rows + usize::from(index == self.cursor_pos.0 && self.cursor_adds_row())
+    }
+
+    // Function to check whether the cursor block at the end of its line wraps to a row of its own
+    fn cursor_adds_row(&self) -> bool {
+        let (line_idx, col_idx) = self.cursor_pos;
+        self.wrap
+            && self
+                .rows
+                .line_rows(self.buffer.as_ref(), self.width, line_idx)
+                .end_wraps
+            && col_idx == self.get_line_length(line_idx)
+    }
+
+    // Function to count the screen rows of the whole text
+    fn total_rows(&self) -> usize {
+        if !self.wrap {
+            return self.buffer.line_count();
+        }
+
+        let line_count = self.buffer.line_count();
+        self.rows
+            .rows_above(self.buffer.as_ref(), self.width, line_count)
+            + usize::from(self.cursor_adds_row())
+    }
+
+    // Function to get the screen row and cell of the cursor, counting from the first line
+    fn cursor_screen_pos(&self) -> (usize, usize) {
+        let (line_idx, col_idx) = self.cursor_pos;
+        let (row_idx, x) = self.line_layout(line_idx)[col_idx];
+        if !self.wrap {
+            return (line_idx, x);
+        }
+
+        let rows_above = self
+            .rows
+            .rows_above(self.buffer.as_ref(), self.width, line_idx);
+        (rows_above + row_idx, x)
+    }
+
+    // Function to find the line shown at a screen row, with the first row of that line
+    fn line_at_row(&self, row: usize) -> (usize, usize) {
+        if !self.wrap {
+            return (row, row);
+        }
+
+        let cursor_line = self.cursor_pos.0;
+        let (line_idx, first_row) = self.rows.line_at_row(self.buffer.as_ref(), self.width, row);
+        if line_idx <= cursor_line || !self.cursor_adds_row() {
+            return (line_idx, first_row);
+        }
+
+        // Lines below the cursor are one row lower than the cached offsets
+        let (line_idx, first_row) =
+            self.rows
+                .line_at_row(self.buffer.as_ref(), self.width, row - 1);
+        if line_idx > cursor_line {
+            (line_idx, first_row + 1)
+        } else {
+            (line_idx, first_row)
+        }
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap = !self.wrap;
+        self.scroll = (0, 0);
+        self.scroll_to_cursor();
     }
 
     pub fn content_to_string(&self, with_cursor: bool) -> String {
         let mut result = vec![];
-        for (index, line) in self.lines.iter().enumerate() {
-            if with_cursor {
-                let s = line.to_string();
-                if self.cursor_pos.0 == index {
-                    let col = self.cursor_pos.1;
-                    if col < s.len() {
-                        result.push(format!("{}█{}", &s[..col], &s[col + 1..]));
-                    } else {
-                        result.push(format!("{}█", s));
-                    }
-                } else {
-                    result.push(line.to_string());
-                }
-            } else {
-                result.push(line.to_string());
+        for index in 0..self.buffer.line_count() {
+            result.extend(self.line_to_rows(index, with_cursor));
+        }
+        result.join("\n\r")
+    }
+
+    // Function to fit the editor into the terminal, the gutter takes its columns from the text
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width.saturating_sub(self.gutter_width()).max(2);
+        self.height = height.max(1);
+        self.scroll_to_cursor();
+    }
+
+    // Function to scroll the least amount needed for the cursor to be visible
+    pub fn scroll_to_cursor(&mut self) {
+        let (row_idx, x) = self.cursor_screen_pos();
+        let (top, left) = &mut self.scroll;
+
+        if row_idx < *top {
+            *top = row_idx;
+        } else if row_idx >= *top + self.height {
+            *top = row_idx + 1 - self.height;
+        }
+
+        if x < *left {
+            *left = x;
+        } else if x >= *left + self.width {
+            *left = x + 1 - self.width;
+        }
+    }
+
+    // Function to render only the screen rows and columns inside the viewport
+    pub fn visible_content_to_string(&self) -> String {
+        let (top, left) = self.scroll;
+        let mut result = vec![];
+        // Lines above the viewport are skipped, with wrapping their rows come from the cache
+        let (first_line, mut row_idx) = self.line_at_row(top);
+
+        for index in first_line..self.buffer.line_count() {
+            let rows_count = self.rows_in_line(index);
+            if row_idx + rows_count > top {
+                let rows = self.line_to_rows(index, true);
+                let skipped = top.saturating_sub(row_idx);
+                result.extend(
+                    rows.iter()
+                        .enumerate()
+                        .skip(skipped)
+                        .map(|(row_in_line, row)| {
+                            format!(
+                                "{}{}",
+                                self.gutter_text(index, row_in_line == 0),
+                                unicode::slice_columns(row, left, self.width)
+                            )
+                        }),
+                );
             }
 
-            // if temp_line.len() <= self.width {
-            //     result.push(temp_line);
-            // } else {
-            //     let mut start = 0;
-            //     while start < temp_line.len() {
-            //         let end = std::cmp::min(start + self.width - 2, temp_line.len());
-            //         println!("{}, {}:{}", temp_line.len(), start, end);
-            //         result.push(temp_line[start..end].to_string());
-            //         start = end
-            //     }
-            // }
+            row_idx += rows_count;
+            if row_idx >= top + self.height {
+                break;
+            }
         }
+
+        result.truncate(self.height);
         result.join("\n\r")
     }
 
+    // Function to get the text to write to the file, with the line endings it was read with
     pub fn content_to_save(&self) -> String {
-        self.lines.join("\n")
+        match self.line_ending {
+            LineEnding::Lf => self.buffer.text(),
+            LineEnding::Crlf => self.buffer.text().replace('\n', "\r\n"),
+        }
     }
 
-    pub fn show_notification(&mut self, notification: &str) {
-        self.notifications.push(notification.to_string());
+    // Function to get the text of the open prompt
+    pub fn prompt_text(&self) -> Option<String> {
+        if self.confirm_quit {
+            return Some(String::from(
+                "Save changes before quitting? (y)es (n)o (c)ancel",
+            ));
+        }
+        if let Some(input) = &self.go_to_input {
+            return Some(format!("Go to line[:column]: {}", input));
+        }
+        self.search_prompt_text()
     }
 
     pub fn redraw(&mut self, stdout: &mut RawTerminal<Stdout>) -> io::Result<()> {
@@ -208,21 +584,41 @@ impl TextEditor {
         // Clear terminal
         write!(stdout, "\x1B[2J\x1B[1;1H")?;
 
-        while !&self.notifications.is_empty() {
-            let message = &self.notifications.remove(0);
-            write!(stdout, "{}\n", message)?;
+        // The status bar takes the bottom row and the prompt the one above it,
+        // the text gets the rest of the terminal
+        let (columns, rows) = termion::terminal_size()?;
+        let prompt = self.prompt_text();
+        self.resize(
+            columns as usize,
+            (rows as usize).saturating_sub(1 + usize::from(prompt.is_some())),
+        );
+
+        write!(stdout, "{}", self.visible_content_to_string())?;
+        if let Some(prompt) = prompt {
+            write!(
+                stdout,
+                "{}{}",
+                Goto(1, rows.saturating_sub(1)),
+                unicode::slice_columns(&prompt, 0, columns as usize)
+            )?;
         }
-        write!(stdout, "{}", self.content_to_string(true)).unwrap();
+        write!(
+            stdout,
+            "{}\x1b[7m{}\x1b[0m",
+            Goto(1, rows),
+            self.status_bar_text(columns as usize)
+        )?;
 
         // self.debug(stdout);
         stdout.flush()?;
         Ok(())
     }
 
+    #[allow(dead_code)]
     fn debug(&self, stdout: &mut RawTerminal<Stdout>) {
         // Print content
         write!(stdout, "\n\r==== DEBUG ====\n\r").unwrap();
-        write!(stdout, "{:?}", self.lines).unwrap();
+        write!(stdout, "{:?}", self.buffer.text()).unwrap();
 
         if let Ok(pos) = stdout.cursor_pos() {
             write!(stdout, "\n\r{:?}", pos).unwrap()
@@ -230,5 +626,6 @@ impl TextEditor {
     }
 }
 
+#[cfg(test)]
 #[path = "tests.rs"]
 mod tests;
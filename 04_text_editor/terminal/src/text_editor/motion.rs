@@ -0,0 +1,116 @@
+use crate::text_editor::unicode;
+use crate::text_editor::TextEditor;
+
+#[derive(PartialEq)]
+enum CharClass {
+    Space,
+    Word,
+    Punctuation,
+}
+
+fn char_class(grapheme: &str) -> CharClass {
+    match grapheme.chars().next() {
+        Some(c) if c.is_whitespace() => CharClass::Space,
+        Some(c) if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        _ => CharClass::Punctuation,
+    }
+}
+
+impl TextEditor {
+    // Function to move to the first non-blank grapheme of the line, or to the line start when already there
+    pub fn line_start(&mut self) {
+        let line = self.buffer.line(self.cursor_pos.0);
+        let indent = unicode::graphemes(&line)
+            .take_while(|grapheme| char_class(grapheme) == CharClass::Space)
+            .count();
+        self.cursor_pos.1 = if self.cursor_pos.1 == indent {
+            0
+        } else {
+            indent
+        };
+    }
+
+    pub fn line_end(&mut self) {
+        self.cursor_pos.1 = self.get_line_length(self.cursor_pos.0);
+    }
+
+    // Function to move to the start of the word on the left, going to the previous line at the line start
+    pub fn word_left(&mut self) {
+        let (line_idx, col_idx) = self.cursor_pos;
+        if col_idx == 0 {
+            self.cursor_left();
+            return;
+        }
+
+        let line = self.buffer.line(line_idx);
+        let classes: Vec<CharClass> = unicode::graphemes(&line)
+            .take(col_idx)
+            .map(char_class)
+            .collect();
+
+        let mut column = col_idx;
+        while column > 0 && classes[column - 1] == CharClass::Space {
+            column -= 1;
+        }
+        if column > 0 {
+            let class = &classes[column - 1];
+            while column > 0 && classes[column - 1] == *class {
+                column -= 1;
+            }
+        }
+        self.cursor_pos.1 = column;
+    }
+
+    // Function to move to the end of the word on the right, going to the next line at the line end
+    pub fn word_right(&mut self) {
+        let (line_idx, col_idx) = self.cursor_pos;
+        let line = self.buffer.line(line_idx);
+        let classes: Vec<CharClass> = unicode::graphemes(&line)
+            .skip(col_idx)
+            .map(char_class)
+            .collect();
+        if classes.is_empty() {
+            self.cursor_right();
+            return;
+        }
+
+        let mut offset = 0;
+        while offset < classes.len() && classes[offset] == CharClass::Space {
+            offset += 1;
+        }
+        if offset < classes.len() {
+            let class = &classes[offset];
+            while offset < classes.len() && classes[offset] == *class {
+                offset += 1;
+            }
+        }
+        self.cursor_pos.1 = col_idx + offset;
+    }
+
+    // Function to get the number of screen rows a page moves, one row stays visible
+    fn page_rows(&self) -> usize {
+        self.height.saturating_sub(1).max(1)
+    }
+
+    // Function to move the cursor and the view a page up
+    pub fn page_up(&mut self) {
+        let rows = self.page_rows();
+        for _ in 0..rows {
+            self.cursor_up();
+        }
+        self.scroll.0 = self.scroll.0.saturating_sub(rows);
+        self.scroll_to_cursor();
+    }
+
+    // Function to move the cursor and the view a page down
+    pub fn page_down(&mut self) {
+        let rows = self.page_rows();
+        for _ in 0..rows {
+            self.cursor_down();
+        }
+        // The last page is kept full
+        let last_top = self.total_rows().saturating_sub(self.height);
+        self.scroll.0 = std::cmp::min(self.scroll.0 + rows, last_top);
+        self.scroll_to_cursor();
+    }
+}
@@ -0,0 +1,256 @@
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+// Small regex for searching within a line. It knows literals, `.`, classes like `[a-z_]` or
+// `[^0-9]`, the `\d` `\w` `\s` escapes, `*` `+` `?` repetitions and `^` `$` anchors. Matching
+// runs every alternative at once over the text, so it takes time linear in the line length.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regex {
+    nodes: Vec<Node>,
+    start_anchor: bool,
+    end_anchor: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Node {
+    atom: Atom,
+    repeat: Repeat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Atom {
+    Char(char),
+    Any,
+    Class {
+        items: Vec<ClassItem>,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit,
+    Word,
+    Space,
+}
+
+// `+` is kept as the atom once followed by the atom repeated
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Repeat {
+    One,
+    ZeroOrMore,
+    ZeroOrOne,
+}
+
+// A match in progress, waiting at a node or done
+#[derive(Debug, Clone, Copy)]
+enum Thread {
+    At { node: usize, start: usize },
+    Matched { start: usize },
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Range(from, to) => *from <= c && c <= *to,
+            ClassItem::Digit => c.is_ascii_digit(),
+            ClassItem::Word => c.is_alphanumeric() || c == '_',
+            ClassItem::Space => c.is_whitespace(),
+        }
+    }
+}
+
+impl Atom {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Atom::Char(expected) => *expected == c,
+            Atom::Any => true,
+            Atom::Class { items, negated } => items.iter().any(|item| item.matches(c)) != *negated,
+        }
+    }
+
+    // Function to turn the char after a backslash into an atom
+    fn escaped(c: char) -> Atom {
+        let (item, negated) = match c {
+            'd' | 'D' => (ClassItem::Digit, c == 'D'),
+            'w' | 'W' => (ClassItem::Word, c == 'W'),
+            's' | 'S' => (ClassItem::Space, c == 'S'),
+            't' => return Atom::Char('\t'),
+            _ => return Atom::Char(c),
+        };
+        Atom::Class {
+            items: vec![item],
+            negated,
+        }
+    }
+
+    // Function to parse a class after its opening '['
+    fn class(chars: &mut Peekable<IntoIter<char>>) -> Result<Atom, &'static str> {
+        let negated = chars.next_if_eq(&'^').is_some();
+        let mut items = vec![];
+
+        loop {
+            let from = match chars.next().ok_or("Unclosed [")? {
+                ']' => break,
+                '\\' => match Atom::escaped(chars.next().ok_or("Unclosed [")?) {
+                    Atom::Char(c) => c,
+                    Atom::Class { items: escaped, .. } => {
+                        items.extend(escaped);
+                        continue;
+                    }
+                    Atom::Any => unreachable!(),
+                },
+                c => c,
+            };
+
+            if chars.next_if_eq(&'-').is_none() {
+                items.push(ClassItem::Range(from, from));
+            } else if chars.peek() == Some(&']') {
+                // A trailing '-' is just a char
+                items.push(ClassItem::Range(from, from));
+                items.push(ClassItem::Range('-', '-'));
+            } else {
+                let to = chars.next().ok_or("Unclosed [")?;
+                if to < from {
+                    return Err("Invalid range in []");
+                }
+                items.push(ClassItem::Range(from, to));
+            }
+        }
+
+        Ok(Atom::Class { items, negated })
+    }
+}
+
+impl Regex {
+    pub fn build(pattern: &str) -> Result<Regex, &'static str> {
+        let mut chars: Vec<char> = pattern.chars().collect();
+
+        let start_anchor = chars.first() == Some(&'^');
+        if start_anchor {
+            chars.remove(0);
+        }
+        // "\$" at the end is a literal '$'
+        let end_anchor = chars.last() == Some(&'$')
+            && chars[..chars.len() - 1]
+                .iter()
+                .rev()
+                .take_while(|c| **c == '\\')
+                .count()
+                % 2
+                == 0;
+        if end_anchor {
+            chars.pop();
+        }
+
+        let mut nodes: Vec<Node> = vec![];
+        let mut chars = chars.into_iter().peekable();
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '.' => Atom::Any,
+                '\\' => Atom::escaped(chars.next().ok_or("Pattern ends with \\")?),
+                '[' => Atom::class(&mut chars)?,
+                '*' | '+' | '?' => {
+                    let node = nodes
+                        .last_mut()
+                        .filter(|node| node.repeat == Repeat::One)
+                        .ok_or("Nothing to repeat")?;
+                    match c {
+                        '*' => node.repeat = Repeat::ZeroOrMore,
+                        '?' => node.repeat = Repeat::ZeroOrOne,
+                        _ => {
+                            let atom = node.atom.clone();
+                            nodes.push(Node {
+                                atom,
+                                repeat: Repeat::ZeroOrMore,
+                            });
+                        }
+                    }
+                    continue;
+                }
+                c => Atom::Char(c),
+            };
+            nodes.push(Node {
+                atom,
+                repeat: Repeat::One,
+            });
+        }
+
+        Ok(Regex {
+            nodes,
+            start_anchor,
+            end_anchor,
+        })
+    }
+
+    // Function to find the leftmost match starting at `start` or later, as a char range.
+    // Threads are kept in the order a backtracking matcher would try them, so repetitions
+    // still take as many chars as they can.
+    pub fn find_at(&self, text: &[char], start: usize) -> Option<(usize, usize)> {
+        let mut found = None;
+        let mut threads = vec![];
+        let mut seen = vec![false; self.nodes.len() + 1];
+
+        for pos in start..=text.len() {
+            if found.is_none() && (!self.start_anchor || pos == 0) {
+                self.add_thread(&mut threads, &mut seen, text, pos, 0, pos);
+            }
+            if threads.is_empty() && (found.is_some() || self.start_anchor) {
+                break;
+            }
+
+            let mut next = vec![];
+            let mut next_seen = vec![false; self.nodes.len() + 1];
+            for thread in threads {
+                match thread {
+                    // Threads after a match would only give less preferred ones
+                    Thread::Matched { start } => {
+                        found = Some((start, pos));
+                        break;
+                    }
+                    Thread::At { node, start } => {
+                        let Node { atom, repeat } = &self.nodes[node];
+                        if text.get(pos).is_some_and(|c| atom.matches(*c)) {
+                            let node = match repeat {
+                                Repeat::ZeroOrMore => node,
+                                _ => node + 1,
+                            };
+                            self.add_thread(&mut next, &mut next_seen, text, pos + 1, node, start);
+                        }
+                    }
+                }
+            }
+            threads = next;
+            seen = next_seen;
+        }
+        found
+    }
+
+    // Function to add the thread waiting at a node, followed by the threads that skip it
+    fn add_thread(
+        &self,
+        threads: &mut Vec<Thread>,
+        seen: &mut [bool],
+        text: &[char],
+        pos: usize,
+        node: usize,
+        start: usize,
+    ) {
+        if seen[node] {
+            return;
+        }
+        seen[node] = true;
+
+        let Some(Node { repeat, .. }) = self.nodes.get(node) else {
+            if !self.end_anchor || pos == text.len() {
+                threads.push(Thread::Matched { start });
+            }
+            return;
+        };
+        threads.push(Thread::At { node, start });
+        if *repeat != Repeat::One {
+            self.add_thread(threads, seen, text, pos, node + 1, start);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+
+use crate::text_editor::buffer::TextBuffer;
+use crate::text_editor::unicode;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LineRows {
+    pub rows: usize,     // screen rows taken by the text of the line
+    pub end_wraps: bool, // a cursor at the end of the line goes to one more row
+}
+
+#[derive(Default)]
+struct Cache {
+    width: usize,
+    lines: Vec<Option<LineRows>>, // `None` for lines changed since they were laid out
+    offsets: Vec<usize>,          // rows above each of the leading lines, known up to its length
+}
+
+// Screen rows of wrapped lines, kept between redraws so finding the row of a line doesn't
+// lay out every line above it again. Edits drop the rows of the changed lines and the
+// offsets below them, a different width drops everything.
+#[derive(Default)]
+pub struct RowCache {
+    cache: RefCell<Cache>,
+}
+
+impl RowCache {
+    // Function to drop the rows of a changed line, `removed` lines after it were
+    // joined into it and `added` new lines follow it now
+    pub fn edited(&self, line: usize, removed: usize, added: usize) {
+        let mut cache = self.cache.borrow_mut();
+        cache.offsets.truncate(line + 1);
+
+        let lines = &mut cache.lines;
+        if line + removed < lines.len() {
+            lines.splice(line..=line + removed, std::iter::repeat_n(None, added + 1));
+        } else {
+            lines.truncate(line);
+        }
+    }
+
+    // Function to get the rows of a line, laying it out when needed
+    pub fn line_rows(&self, buffer: &dyn TextBuffer, width: usize, index: usize) -> LineRows {
+        let mut cache = self.cache.borrow_mut();
+        Self::check_width(&mut cache, width);
+        Self::cached_line_rows(&mut cache, buffer, index)
+    }
+
+    // Function to count the rows of the lines above `index`
+    pub fn rows_above(&self, buffer: &dyn TextBuffer, width: usize, index: usize) -> usize {
+        let mut cache = self.cache.borrow_mut();
+        Self::check_width(&mut cache, width);
+        Self::extend_offsets(&mut cache, buffer, |offsets| offsets.len() > index);
+        cache.offsets[index]
+    }
+
+    // Function to find the line the screen row falls into, with the first row of that line
+    pub fn line_at_row(&self, buffer: &dyn TextBuffer, width: usize, row: usize) -> (usize, usize) {
+        let mut cache = self.cache.borrow_mut();
+        Self::check_width(&mut cache, width);
+        Self::extend_offsets(&mut cache, buffer, |offsets| {
+            offsets.last().is_some_and(|offset| *offset > row)
+        });
+
+        let line = cache.offsets.partition_point(|offset| *offset <= row) - 1;
+        (line, cache.offsets[line])
+    }
+
+    fn check_width(cache: &mut Cache, width: usize) {
+        if cache.width != width {
+            *cache = Cache {
+                width,
+                ..Cache::default()
+            };
+        }
+    }
+
+    // Function to add offsets of the following lines until `done` or the end of the text,
+    // the offset after the last line is the row count of the whole text
+    fn extend_offsets(cache: &mut Cache, buffer: &dyn TextBuffer, done: impl Fn(&[usize]) -> bool) {
+        if cache.offsets.is_empty() {
+            cache.offsets.push(0);
+        }
+        while !done(&cache.offsets) && cache.offsets.len() <= buffer.line_count() {
+            let index = cache.offsets.len() - 1;
+            let rows = Self::cached_line_rows(cache, buffer, index).rows;
+            let offset = cache.offsets[index] + rows;
+            cache.offsets.push(offset);
+        }
+    }
+
+    fn cached_line_rows(cache: &mut Cache, buffer: &dyn TextBuffer, index: usize) -> LineRows {
+        if let Some(Some(rows)) = cache.lines.get(index) {
+            return *rows;
+        }
+
+        let rows = Self::layout_rows(buffer, cache.width, index);
+        if index >= cache.lines.len() {
+            cache.lines.resize(index + 1, None);
+        }
+        cache.lines[index] = Some(rows);
+        rows
+    }
+
+    fn layout_rows(buffer: &dyn TextBuffer, width: usize, index: usize) -> LineRows {
+        // Graphemes are at most two cells wide, so short lines can't wrap
+        if 2 * buffer.line_length(index) < width {
+            return LineRows {
+                rows: 1,
+                end_wraps: false,
+            };
+        }
+
+        let positions = unicode::layout(&buffer.line(index), Some(width));
+        let end = positions.len() - 1;
+        let rows = positions[end.saturating_sub(1)].0 + 1;
+        LineRows {
+            rows,
+            end_wraps: positions[end].0 + 1 > rows,
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::ops::Range;
+
+use termion::event::Key;
+
+use crate::text_editor::regex::Regex;
+use crate::text_editor::unicode::{self, Span};
+use crate::text_editor::TextEditor;
+
+// Yellow background
+const MATCH_STYLE: &str = "\x1b[43m";
+
+type Match = ((usize, usize), (usize, usize));
+
+pub enum Pattern {
+    Text(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn build(query: &str, regex: bool) -> Result<Pattern, &'static str> {
+        if regex {
+            Regex::build(query).map(Pattern::Regex)
+        } else {
+            Ok(Pattern::Text(query.to_string()))
+        }
+    }
+
+    // Function to find non-overlapping, non-empty matches in a line as char ranges
+    pub fn find_all(&self, line: &str) -> Vec<(usize, usize)> {
+        match self {
+            Pattern::Text(text) if text.is_empty() => vec![],
+            Pattern::Text(text) => line
+                .match_indices(text.as_str())
+                .map(|(index, found)| {
+                    let start = line[..index].chars().count();
+                    (start, start + found.chars().count())
+                })
+                .collect(),
+            Pattern::Regex(regex) => {
+                let chars: Vec<char> = line.chars().collect();
+                let mut matches = vec![];
+                let mut pos = 0;
+                while let Some((start, end)) = regex.find_at(&chars, pos) {
+                    if start == end {
+                        pos = start + 1;
+                    } else {
+                        matches.push((start, end));
+                        pos = end;
+                    }
+                }
+                matches
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromptKind {
+    Search,      // typing what to find
+    Replace,     // typing what to replace
+    ReplaceWith, // typing the replacement
+    Confirm,     // asking whether to replace the selected match
+}
+
+pub struct Search {
+    pub kind: PromptKind,
+    pub query: String,
+    pub replacement: String,
+    pub regex: bool,
+    pattern: Option<Pattern>, // None while the query is empty or not a valid regex
+    origin: (usize, usize),   // cursor when the prompt was opened
+    replaced: usize,
+}
+
+impl TextEditor {
+    pub fn open_search(&mut self, kind: PromptKind) {
+        self.selection = None;
+        self.search = Some(Search {
+            kind,
+            query: String::new(),
+            replacement: String::new(),
+            regex: false,
+            pattern: None,
+            origin: self.cursor_pos,
+            replaced: 0,
+        });
+    }
+
+    pub(super) fn search_prompt_text(&self) -> Option<String> {
+        let search = self.search.as_ref()?;
+        let mode = match (search.regex, &search.pattern) {
+            (false, _) => "",
+            (true, None) if !search.query.is_empty() => " (invalid regex)",
+            (true, _) => " (regex)",
+        };
+
+        Some(match search.kind {
+            PromptKind::Search => format!("Search{}: {}", mode, search.query),
+            PromptKind::Replace => format!("Replace{}: {}", mode, search.query),
+            PromptKind::ReplaceWith => {
+                format!("Replace {} with: {}", search.query, search.replacement)
+            }
+            PromptKind::Confirm => String::from("Replace this match? (y)es (n)o (a)ll (q)uit"),
+        })
+    }
+
+    // Function to handle a key while the prompt is open
+    pub fn prompt_key(&mut self, key: Key) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+
+        match (search.kind, key) {
+            (_, Key::Esc) => self.close_search(),
+            (PromptKind::Confirm, Key::Char('y')) => {
+                self.replace_match();
+                self.next_replacement(self.cursor_pos);
+            }
+            (PromptKind::Confirm, Key::Char('n')) => {
+                let (line_idx, col_idx) = self.current_match_start();
+                self.next_replacement((line_idx, col_idx + 1));
+            }
+            (PromptKind::Confirm, Key::Char('a')) => {
+                self.replace_all();
+                self.close_search();
+            }
+            (PromptKind::Confirm, Key::Char('q')) => self.close_search(),
+            (PromptKind::Confirm, _) => (),
+            (PromptKind::ReplaceWith, Key::Char('\n')) => {
+                search.kind = PromptKind::Confirm;
+                let origin = search.origin;
+                self.next_replacement(origin);
+            }
+            (PromptKind::ReplaceWith, Key::Char(c)) => search.replacement.push(c),
+            (PromptKind::ReplaceWith, Key::Backspace) => {
+                search.replacement.pop();
+            }
+            (PromptKind::Replace, Key::Char('\n')) if search.pattern.is_some() => {
+                search.kind = PromptKind::ReplaceWith;
+            }
+            (PromptKind::Replace, Key::Char('\n')) => (),
+            (PromptKind::Search, Key::Char('\n') | Key::Down) => self.jump_to_match(true),
+            (PromptKind::Search, Key::Up) => self.jump_to_match(false),
+            (_, Key::Char(c)) => {
+                search.query.push(c);
+                self.update_search();
+            }
+            (_, Key::Backspace) => {
+                search.query.pop();
+                self.update_search();
+            }
+            (_, Key::Ctrl('e')) => {
+                // Toggle regex on Ctrl+e
+                search.regex = !search.regex;
+                self.update_search();
+            }
+            _ => (),
+        }
+    }
+
+    // Function to close the prompt, the current match stays selected
+    fn close_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            if search.kind == PromptKind::Confirm {
+                self.show_notification(&format!("Replaced {} occurrences", search.replaced));
+            }
+        }
+    }
+
+    // Function to select the first match after where the search started, as the query changes.
+    // Only the lines on screen are searched while typing, Enter looks through the whole text.
+    fn update_search(&mut self) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        search.pattern = if search.query.is_empty() {
+            None
+        } else {
+            Pattern::build(&search.query, search.regex).ok()
+        };
+
+        let origin = search.origin;
+        let lines = self.visible_lines();
+        let from = if lines.contains(&origin.0) {
+            origin
+        } else {
+            (lines.start, 0)
+        };
+        match self.find_match_in(from, true, true, lines) {
+            Some(found) => self.select_match(found),
+            None => {
+                self.selection = None;
+                self.cursor_pos = origin;
+            }
+        }
+    }
+
+    fn select_match(&mut self, (start, end): Match) {
+        self.selection = Some(start);
+        self.cursor_pos = end;
+    }
+
+    // Function to get the lines on screen
+    fn visible_lines(&self) -> Range<usize> {
+        let last_line = self.buffer.line_count() - 1;
+        let (first, _) = self.line_at_row(self.scroll.0);
+        let (last, _) = self.line_at_row(self.scroll.0 + self.height - 1);
+        first.min(last_line)..last.min(last_line) + 1
+    }
+
+    fn current_match_start(&self) -> (usize, usize) {
+        self.selection_range()
+            .map_or(self.cursor_pos, |(start, _)| start)
+    }
+
+    // Function to select the next or previous match, going around the text. Without a
+    // selected match, the search goes on from the cursor itself.
+    fn jump_to_match(&mut self, forward: bool) {
+        let (line_idx, col_idx) = self.current_match_start();
+        let from = if forward && self.selection.is_some() {
+            (line_idx, col_idx + 1)
+        } else {
+            (line_idx, col_idx)
+        };
+
+        if let Some(found) = self.find_match(from, forward, true) {
+            self.select_match(found);
+        }
+    }
+
+    // Function to select the next match to confirm, the replacing ends at the end of the text
+    fn next_replacement(&mut self, from: (usize, usize)) {
+        match self.find_match(from, true, false) {
+            Some(found) => self.select_match(found),
+            None => {
+                self.selection = None;
+                self.close_search();
+            }
+        }
+    }
+
+    // Function to find matches of the search in a line as grapheme column ranges
+    fn line_matches(&self, index: usize) -> Vec<(usize, usize)> {
+        let Some(pattern) = self
+            .search
+            .as_ref()
+            .and_then(|search| search.pattern.as_ref())
+        else {
+            return vec![];
+        };
+
+        let line = self.buffer.line(index);
+        pattern
+            .find_all(&line)
+            .into_iter()
+            .map(|(start, end)| {
+                (
+                    unicode::column_at_char(&line, start),
+                    unicode::column_at_char(&line, end),
+                )
+            })
+            .filter(|(start, end)| start < end)
+            .collect()
+    }
+
+    // Function to find the first match starting at `from` or later, or the last one before it
+    fn find_match(&self, from: (usize, usize), forward: bool, wrap: bool) -> Option<Match> {
+        self.find_match_in(from, forward, wrap, 0..self.buffer.line_count())
+    }
+
+    // Function to find a match like `find_match`, looking only at the given lines
+    fn find_match_in(
+        &self,
+        from: (usize, usize),
+        forward: bool,
+        wrap: bool,
+        range: Range<usize>,
+    ) -> Option<Match> {
+        let (from_line, from_col) = from;
+
+        // Lines from `from` to the end of the range in the search direction, then around from the other end
+        let lines: Vec<usize> = if forward {
+            (from_line..range.end)
+                .chain(range.start..from_line + 1)
+                .collect()
+        } else {
+            (range.start..from_line + 1)
+                .rev()
+                .chain((from_line..range.end).rev())
+                .collect()
+        };
+        let first_pass = if forward {
+            range.end - from_line
+        } else {
+            from_line + 1 - range.start
+        };
+
+        for (step, index) in lines.into_iter().enumerate() {
+            if step >= first_pass && !wrap {
+                break;
+            }
+
+            let matches = self.line_matches(index);
+            let found = if forward {
+                matches
+                    .into_iter()
+                    .find(|(start, _)| step > 0 || *start >= from_col)
+            } else {
+                matches
+                    .into_iter()
+                    .rev()
+                    .find(|(start, _)| step > 0 || *start < from_col)
+            };
+            if let Some((start, end)) = found {
+                return Some(((index, start), (index, end)));
+            }
+        }
+        None
+    }
+
+    // Function to replace the selected match as one undo step
+    fn replace_match(&mut self) {
+        let Some((start, end)) = self.selection_range() else {
+            return;
+        };
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        search.replaced += 1;
+        let replacement = search.replacement.clone();
+
+        let cursor_before = self.cursor_pos;
+        let edits = self.replace_edits(start, end, &replacement);
+        self.history
+            .record_step(edits, cursor_before, self.cursor_pos);
+    }
+
+    // Function to replace every match in the text as one undo step
+    fn replace_all(&mut self) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let replacement = search.replacement.clone();
+
+        let cursor_before = self.cursor_pos;
+        let mut edits = vec![];
+        for index in 0..self.buffer.line_count() {
+            // From the line end, so replacing doesn't move the matches still to replace
+            for (start, end) in self.line_matches(index).into_iter().rev() {
+                edits.extend(self.replace_edits((index, start), (index, end), &replacement));
+                if let Some(search) = self.search.as_mut() {
+                    search.replaced += 1;
+                }
+            }
+        }
+
+        let line_idx = cursor_before.0;
+        self.cursor_pos = (
+            line_idx,
+            std::cmp::min(cursor_before.1, self.get_line_length(line_idx)),
+        );
+        self.history
+            .record_step(edits, cursor_before, self.cursor_pos);
+    }
+
+    // Function to get the matches of a line as spans to highlight
+    pub(super) fn search_spans(&self, index: usize) -> Vec<Span> {
+        self.line_matches(index)
+            .into_iter()
+            .map(|(start, end)| Span {
+                start,
+                end,
+                style: MATCH_STYLE,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,160 @@
+use std::cmp::Ordering;
+
+use crate::text_editor::history::Edit;
+use crate::text_editor::unicode::{self, Span};
+use crate::text_editor::TextEditor;
+
+// Inverted colors
+const SELECTION_STYLE: &str = "\x1b[7m";
+
+impl TextEditor {
+    // Function to move the cursor, either extending the selection from where it was or dropping it
+    pub fn move_cursor(&mut self, movement: fn(&mut Self), select: bool) {
+        if !select {
+            self.selection = None;
+        } else if self.selection.is_none() {
+            self.selection = Some(self.cursor_pos);
+        }
+        movement(self);
+    }
+
+    // Function to get the selected (start, end) positions in order, `None` when nothing is selected
+    pub fn selection_range(&self) -> Option<((usize, usize), (usize, usize))> {
+        let anchor = self.selection?;
+        match anchor.cmp(&self.cursor_pos) {
+            Ordering::Less => Some((anchor, self.cursor_pos)),
+            Ordering::Greater => Some((self.cursor_pos, anchor)),
+            Ordering::Equal => None,
+        }
+    }
+
+    // Function to turn a (line, grapheme column) position into a (line, char column) one
+    fn char_pos(&self, (line_idx, col_idx): (usize, usize)) -> (usize, usize) {
+        (
+            line_idx,
+            unicode::char_index(&self.buffer.line(line_idx), col_idx),
+        )
+    }
+
+    // Function to count chars between two positions, line ends count as one
+    fn chars_between(&self, start: (usize, usize), end: (usize, usize)) -> usize {
+        let (start, end) = (self.char_pos(start), self.char_pos(end));
+        let full_lines: usize = (start.0..end.0)
+            .map(|index| self.buffer.line_length(index) + 1)
+            .sum();
+        full_lines + end.1 - start.1
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection_range()?;
+        Some(self.text_at(self.char_pos(start), self.chars_between(start, end)))
+    }
+
+    // Function to delete the selected text as one undo step, returns `false` when nothing is selected
+    pub fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection_range() else {
+            self.selection = None;
+            return false;
+        };
+
+        let cursor_before = self.cursor_pos;
+        let edit = self.delete_edit(self.char_pos(start), self.chars_between(start, end));
+        self.apply(&edit);
+
+        self.selection = None;
+        self.cursor_pos = start;
+        self.history
+            .record(edit, None, cursor_before, self.cursor_pos);
+        true
+    }
+
+    pub fn copy(&mut self) -> bool {
+        match self.selected_text() {
+            Some(text) => {
+                self.clipboard = text;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cut(&mut self) -> bool {
+        self.copy() && self.delete_selection()
+    }
+
+    // Function to replace the text between two positions, leaving the cursor after the new text.
+    // Returns the applied edits, so several replacements can be undone as one step.
+    pub(super) fn replace_edits(
+        &mut self,
+        start: (usize, usize),
+        end: (usize, usize),
+        text: &str,
+    ) -> Vec<Edit> {
+        let (line_idx, char_idx) = self.char_pos(start);
+        let mut edits = vec![];
+        if start != end {
+            edits.push(self.delete_edit((line_idx, char_idx), self.chars_between(start, end)));
+        }
+        if !text.is_empty() {
+            edits.push(Edit::Insert {
+                pos: (line_idx, char_idx),
+                text: text.to_string(),
+            });
+        }
+        for edit in &edits {
+            self.apply(edit);
+        }
+
+        // The cursor ends on the last inserted line
+        let new_lines = text.matches('\n').count();
+        let last_line_chars = text.rsplit('\n').next().unwrap_or("").chars().count();
+        let end_char = if new_lines == 0 {
+            char_idx + last_line_chars
+        } else {
+            last_line_chars
+        };
+        let end_line = line_idx + new_lines;
+        self.selection = None;
+        self.cursor_pos = (
+            end_line,
+            unicode::column_at_char(&self.buffer.line(end_line), end_char),
+        );
+        edits
+    }
+
+    // Function to insert the clipboard at the cursor as one undo step, replacing the selection
+    pub fn paste(&mut self) -> bool {
+        if self.clipboard.is_empty() {
+            return false;
+        }
+
+        let cursor_before = self.cursor_pos;
+        let (start, end) = self
+            .selection_range()
+            .unwrap_or((self.cursor_pos, self.cursor_pos));
+        let edits = self.replace_edits(start, end, &self.clipboard.clone());
+        self.history
+            .record_step(edits, cursor_before, self.cursor_pos);
+        true
+    }
+
+    // Function to get the selected graphemes of a line as a span to highlight
+    pub(super) fn selection_spans(&self, index: usize) -> Vec<Span> {
+        match self.selection_range() {
+            Some((start, end)) if start.0 <= index && index <= end.0 => {
+                let from = if index == start.0 { start.1 } else { 0 };
+                let to = if index == end.0 {
+                    end.1
+                } else {
+                    self.get_line_length(index)
+                };
+                vec![Span {
+                    start: from,
+                    end: to,
+                    style: SELECTION_STYLE,
+                }]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+use crate::text_editor::unicode;
+use crate::text_editor::TextEditor;
+
+// How long a notification stays in the status bar
+pub const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+
+// Line separator of the opened file, the buffer always uses '\n'
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn detect(text: &str) -> LineEnding {
+        if text.contains("\r\n") {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+        }
+    }
+}
+
+pub struct Notification {
+    pub text: String,
+    pub shown_at: Instant,
+}
+
+impl TextEditor {
+    pub fn show_notification(&mut self, notification: &str) {
+        self.notification = Some(Notification {
+            text: notification.to_string(),
+            shown_at: Instant::now(),
+        });
+    }
+
+    // Function to drop the notification once it has been shown long enough, returns `true` if it was dropped
+    pub fn expire_notification(&mut self, now: Instant) -> bool {
+        let expired = self.notification.as_ref().is_some_and(|notification| {
+            now.duration_since(notification.shown_at) >= NOTIFICATION_DURATION
+        });
+        if expired {
+            self.notification = None;
+        }
+        expired
+    }
+
+    // Function to render the status bar: the notification or file name on the left,
+    // cursor position, total lines, encoding and line ending on the right
+    pub fn status_bar_text(&self, width: usize) -> String {
+        let left = match &self.notification {
+            Some(notification) => notification.text.clone(),
+            None => {
+                let file_name = if self.file_name.is_empty() {
+                    "[No Name]"
+                } else {
+                    &self.file_name
+                };
+                let modified = if self.is_modified() { " [+]" } else { "" };
+                format!(" {}{}", file_name, modified)
+            }
+        };
+        let right = format!(
+            "Ln {}, Col {} | {} lines | UTF-8 | {} ",
+            self.cursor_pos.0 + 1,
+            self.cursor_pos.1 + 1,
+            self.buffer.line_count(),
+            self.line_ending.name()
+        );
+
+        // The right side is kept whole, the left one is cut to the space that is left
+        let left = unicode::slice_columns(&left, 0, width.saturating_sub(right.width() + 1));
+        let padding = width.saturating_sub(left.width() + right.width());
+        let text = format!("{}{}{}", left, " ".repeat(padding), right);
+        unicode::slice_columns(&text, 0, width)
+    }
+}
@@ -256,3 +256,1523 @@ mod new_lines {
         assert_eq!(prep("12345\n67890\n12345"), editor.content_to_string(false));
     }
 }
+
+#[cfg(test)]
+mod viewport {
+    use super::*;
+
+    fn numbered_lines(count: usize) -> String {
+        (0..count)
+            .map(|index| index.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_only_lines_that_fit_the_height() {
+        let mut editor = TextEditor::build(&numbered_lines(10));
+        editor.cursor_pos = (0, 0);
+        editor.resize(10, 3);
+
+        assert_eq!(prep("█\n1\n2"), editor.visible_content_to_string());
+    }
+
+    #[test]
+    fn scrolls_down_to_keep_cursor_visible() {
+        let mut editor = TextEditor::build(&numbered_lines(10));
+        editor.resize(10, 3);
+
+        assert_eq!(editor.scroll, (7, 0));
+        assert_eq!(prep("7\n8\n9█"), editor.visible_content_to_string());
+    }
+
+    #[test]
+    fn scrolls_up_only_when_cursor_leaves_the_viewport() {
+        let mut editor = TextEditor::build(&numbered_lines(10));
+        editor.resize(10, 3);
+
+        editor.cursor_up();
+        editor.cursor_up();
+        editor.scroll_to_cursor();
+        assert_eq!(editor.scroll, (7, 0));
+
+        editor.cursor_up();
+        editor.scroll_to_cursor();
+        assert_eq!(editor.scroll, (6, 0));
+    }
+
+    #[test]
+    fn scrolls_horizontally_on_long_lines() {
+        let mut editor = TextEditor::build("abcdefghij\nxyz");
+        editor.wrap = false;
+        editor.cursor_pos = (0, 8);
+        editor.resize(4, 5);
+
+        assert_eq!(editor.scroll, (0, 5));
+        assert_eq!(prep("fgh█\n"), editor.visible_content_to_string());
+
+        editor.cursor_pos = (1, 1);
+        editor.scroll_to_cursor();
+        assert_eq!(editor.scroll, (0, 1));
+        assert_eq!(prep("bcde\n█z"), editor.visible_content_to_string());
+    }
+
+    #[test]
+    fn keeps_cursor_at_the_line_end_visible() {
+        let mut editor = TextEditor::build("abcd");
+        editor.wrap = false;
+        editor.resize(4, 1);
+
+        assert_eq!(editor.scroll, (0, 1));
+        assert_eq!("bcd█", editor.visible_content_to_string());
+    }
+}
+
+#[cfg(test)]
+mod wrapping {
+    use super::*;
+
+    #[test]
+    fn does_not_wrap_when_turned_off() {
+        let mut editor = TextEditor::build("123456789012345");
+        editor.width = 5;
+        editor.toggle_wrap();
+
+        assert_eq!("123456789012345", editor.content_to_string(false));
+    }
+
+    #[test]
+    fn moves_cursor_to_the_next_row_when_it_fills_the_line() {
+        let mut editor = TextEditor::build("12345");
+        editor.width = 5;
+
+        assert_eq!(prep("12345\n█"), editor.content_to_string(true));
+    }
+
+    #[test]
+    fn moves_cursor_down_by_screen_rows() {
+        let mut editor = TextEditor::build("123456789012\nab");
+        editor.width = 5;
+        editor.cursor_pos = (0, 1);
+
+        editor.cursor_down();
+        assert_eq!(editor.cursor_pos, (0, 6));
+        editor.cursor_down();
+        assert_eq!(editor.cursor_pos, (0, 11));
+        editor.cursor_down();
+        assert_eq!(editor.cursor_pos, (1, 1));
+    }
+
+    #[test]
+    fn moves_cursor_up_by_screen_rows() {
+        let mut editor = TextEditor::build("123456789012\nabcd");
+        editor.width = 5;
+        editor.cursor_pos = (1, 3);
+
+        editor.cursor_up();
+        assert_eq!(editor.cursor_pos, (0, 12));
+        editor.cursor_up();
+        assert_eq!(editor.cursor_pos, (0, 7));
+        editor.cursor_up();
+        assert_eq!(editor.cursor_pos, (0, 2));
+        editor.cursor_up();
+        assert_eq!(editor.cursor_pos, (0, 2));
+    }
+
+    #[test]
+    fn scrolls_by_screen_rows() {
+        let mut editor = TextEditor::build("1234567890\nab\n1234567890");
+        editor.resize(5, 2);
+
+        assert_eq!(editor.scroll, (4, 0));
+        assert_eq!(prep("67890\n█"), editor.visible_content_to_string());
+
+        editor.cursor_pos = (0, 7);
+        editor.scroll_to_cursor();
+        assert_eq!(editor.scroll, (1, 0));
+        assert_eq!(prep("67█90\nab"), editor.visible_content_to_string());
+    }
+
+    // Function to render the same text and cursor in a new editor, without any kept rows
+    fn rendered_fresh(editor: &TextEditor) -> String {
+        let mut fresh = TextEditor::build(&editor.buffer.text());
+        fresh.cursor_pos = editor.cursor_pos;
+        fresh.resize(editor.width, editor.height);
+        fresh.scroll = editor.scroll;
+        fresh.visible_content_to_string()
+    }
+
+    #[test]
+    fn recounts_rows_of_edited_lines() {
+        let mut editor = TextEditor::build("1234567890\nab\n1234567890\nx\ny");
+        editor.resize(5, 3);
+
+        editor.cursor_pos = (1, 2);
+        for c in "cdefgh".chars() {
+            editor.insert_char(c);
+        }
+        editor.cursor_pos = (4, 1);
+        editor.scroll_to_cursor();
+        assert_eq!(editor.scroll, (5, 0));
+        assert_eq!(prep("67890\nx\ny█"), editor.visible_content_to_string());
+        assert_eq!(rendered_fresh(&editor), editor.visible_content_to_string());
+
+        editor.undo();
+        editor.cursor_pos = (4, 1);
+        editor.scroll_to_cursor();
+        assert_eq!(prep("x\ny█"), editor.visible_content_to_string());
+        assert_eq!(rendered_fresh(&editor), editor.visible_content_to_string());
+
+        editor.cursor_pos = (0, 0);
+        editor.handle_newline();
+        editor.cursor_pos = (5, 1);
+        editor.scroll_to_cursor();
+        assert_eq!(rendered_fresh(&editor), editor.visible_content_to_string());
+    }
+
+    #[test]
+    fn recounts_rows_after_resize() {
+        let mut editor = TextEditor::build("1234567890\n1234567890\nab");
+        editor.resize(5, 2);
+        assert_eq!(editor.scroll, (3, 0));
+
+        editor.resize(10, 2);
+        assert_eq!(editor.scroll, (2, 0));
+        assert_eq!(prep("ab█"), editor.visible_content_to_string());
+    }
+
+    #[test]
+    fn counts_the_cursor_row_above_the_viewport() {
+        let mut editor = TextEditor::build("12345\na\nb\nc");
+        editor.resize(5, 2);
+        editor.cursor_pos = (0, 5);
+        editor.scroll = (2, 0);
+
+        assert_eq!(prep("a\nb"), editor.visible_content_to_string());
+    }
+}
+
+#[cfg(test)]
+mod buffers {
+    use crate::text_editor::buffer::{LineBuffer, TextBuffer};
+    use crate::text_editor::gap_buffer::GapBuffer;
+
+    fn lines(buffer: &dyn TextBuffer) -> Vec<String> {
+        (0..buffer.line_count())
+            .map(|index| buffer.line(index))
+            .collect()
+    }
+
+    fn both(initial: &str) -> Vec<Box<dyn TextBuffer>> {
+        vec![
+            Box::new(GapBuffer::build(initial)),
+            Box::new(LineBuffer::build(initial)),
+        ]
+    }
+
+    #[test]
+    fn splits_text_into_lines() {
+        for buffer in both("abc\n\ndef\n") {
+            assert_eq!(lines(buffer.as_ref()), vec!["abc", "", "def", ""]);
+            assert_eq!(buffer.line_length(2), 3);
+            assert_eq!(buffer.text(), "abc\n\ndef\n");
+        }
+    }
+
+    #[test]
+    fn inserts_text_with_new_lines() {
+        for mut buffer in both("abc\ndef") {
+            buffer.insert((0, 1), "x\ny\nz");
+
+            assert_eq!(lines(buffer.as_ref()), vec!["ax", "y", "zbc", "def"]);
+        }
+    }
+
+    #[test]
+    fn deletes_across_line_ends() {
+        for mut buffer in both("abc\ndef\nghi") {
+            buffer.delete((0, 2), 6);
+
+            assert_eq!(lines(buffer.as_ref()), vec!["abghi"]);
+        }
+    }
+
+    #[test]
+    fn grows_when_the_gap_is_filled() {
+        let mut buffer = GapBuffer::build("end");
+        for index in 0..200 {
+            buffer.insert((0, index), "a");
+        }
+
+        assert_eq!(buffer.line_length(0), 203);
+        assert!(buffer.line(0).ends_with("aend"));
+    }
+
+    #[test]
+    fn moves_the_gap_back_and_forth() {
+        let mut buffer = GapBuffer::build("0123456789");
+
+        buffer.insert((0, 8), "b");
+        buffer.insert((0, 2), "a");
+        buffer.delete((0, 10), 1);
+        buffer.insert((0, 11), "c");
+
+        assert_eq!(buffer.text(), "01a234567b9c");
+    }
+
+    #[test]
+    fn matches_line_buffer_after_many_edits() {
+        let initial = "fn main() {\n    println!(\"hi\");\n}\n";
+        let mut gap = GapBuffer::build(initial);
+        let mut reference = LineBuffer::build(initial);
+
+        for step in 0..300 {
+            let line_idx = step * 7 % reference.line_count();
+            let col_idx = step * 13 % (reference.line_length(line_idx) + 1);
+            match step % 4 {
+                0 => {
+                    gap.insert((line_idx, col_idx), "ж\n");
+                    reference.insert((line_idx, col_idx), "ж\n");
+                }
+                1 | 2 => {
+                    gap.insert((line_idx, col_idx), "ab");
+                    reference.insert((line_idx, col_idx), "ab");
+                }
+                _ => {
+                    gap.delete((line_idx, col_idx), 3);
+                    reference.delete((line_idx, col_idx), 3);
+                }
+            }
+
+            assert_eq!(lines(&gap), lines(&reference), "step {}", step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod unicode {
+    use super::*;
+
+    #[test]
+    fn types_and_moves_over_multibyte_chars() {
+        let mut editor = TextEditor::build("");
+
+        for c in "привет é".chars() {
+            editor.insert_char(c);
+        }
+        editor.cursor_left();
+        editor.cursor_left();
+        editor.insert_char('ж');
+
+        assert_eq!(editor.content_to_save(), "приветж é");
+        assert_eq!(editor.cursor_pos, (0, 7));
+        assert_eq!("приветж█é", editor.content_to_string(true));
+    }
+
+    #[test]
+    fn deletes_whole_grapheme_on_backspace() {
+        let mut editor = TextEditor::build("ae\u{301}b");
+        editor.cursor_pos = (0, 2);
+
+        editor.handle_backspace();
+
+        assert_eq!(editor.content_to_save(), "ab");
+        assert_eq!(editor.cursor_pos, (0, 1));
+    }
+
+    #[test]
+    fn keeps_combining_mark_in_the_same_column() {
+        let mut editor = TextEditor::build("e");
+
+        editor.insert_char('\u{301}');
+
+        assert_eq!(editor.cursor_pos, (0, 1));
+        assert_eq!(editor.content_to_save(), "e\u{301}");
+    }
+
+    #[test]
+    fn steps_over_emoji_as_one_character() {
+        let mut editor = TextEditor::build("a👍🏽b");
+        editor.cursor_pos = (0, 0);
+
+        editor.cursor_right();
+        editor.cursor_right();
+
+        assert_eq!(editor.cursor_pos, (0, 2));
+        assert_eq!("a👍🏽█", editor.content_to_string(true));
+    }
+
+    #[test]
+    fn covers_wide_character_with_a_wide_cursor() {
+        let mut editor = TextEditor::build("日本");
+        editor.cursor_pos = (0, 0);
+
+        assert_eq!("██本", editor.content_to_string(true));
+    }
+
+    #[test]
+    fn splits_new_line_after_multibyte_chars() {
+        let mut editor = TextEditor::build("ёжик");
+        editor.cursor_pos = (0, 2);
+
+        editor.handle_newline();
+
+        assert_eq!(prep("ёж\nик"), editor.content_to_string(false));
+    }
+
+    #[test]
+    fn wraps_wide_characters_by_display_width() {
+        let mut editor = TextEditor::build("日本語です");
+        editor.width = 5;
+
+        assert_eq!(prep("日本\n語で\nす"), editor.content_to_string(false));
+    }
+
+    #[test]
+    fn keeps_screen_column_when_moving_between_wide_and_narrow_lines() {
+        let mut editor = TextEditor::build("日本語\nabcdef");
+        editor.cursor_pos = (1, 4);
+
+        editor.cursor_up();
+        assert_eq!(editor.cursor_pos, (0, 2));
+        editor.cursor_down();
+        assert_eq!(editor.cursor_pos, (1, 4));
+    }
+
+    #[test]
+    fn expands_tabs_to_the_next_tab_stop() {
+        let mut editor = TextEditor::build("a\tb\n\tc");
+        assert_eq!(
+            prep("a       b\n        c"),
+            editor.content_to_string(false)
+        );
+
+        editor.cursor_pos = (0, 1);
+        assert_eq!(prep("a███████b\n        c"), editor.content_to_string(true));
+
+        editor.cursor_pos = (0, 2);
+        editor.cursor_down();
+        assert_eq!(editor.cursor_pos, (1, 1));
+    }
+
+    #[test]
+    fn wraps_tabs_by_display_width() {
+        let mut editor = TextEditor::build("abcdef\tg");
+        editor.width = 10;
+
+        assert_eq!(prep("abcdef  g"), editor.content_to_string(false));
+        editor.width = 7;
+        assert_eq!(prep("abcdef \ng"), editor.content_to_string(false));
+    }
+
+    #[test]
+    fn scrolls_horizontally_by_display_width() {
+        let mut editor = TextEditor::build("日本語です");
+        editor.wrap = false;
+        editor.resize(5, 1);
+
+        assert_eq!(editor.scroll, (0, 6));
+        assert_eq!("です█", editor.visible_content_to_string());
+
+        editor.scroll = (0, 1);
+        editor.cursor_pos = (0, 1);
+        assert_eq!(" ██語", editor.visible_content_to_string());
+    }
+}
+
+#[cfg(test)]
+mod history {
+    use super::*;
+
+    #[test]
+    fn undoes_consecutive_typing_at_once() {
+        let mut editor = TextEditor::build("ab");
+        for c in "cde".chars() {
+            editor.insert_char(c);
+        }
+
+        assert!(editor.undo());
+        assert_eq!(editor.content_to_save(), "ab");
+        assert_eq!(editor.cursor_pos, (0, 2));
+        assert!(!editor.undo());
+    }
+
+    #[test]
+    fn starts_a_new_step_after_moving_the_cursor() {
+        let mut editor = TextEditor::build("ab");
+        editor.insert_char('c');
+        editor.cursor_left();
+        editor.insert_char('d');
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "abc");
+        assert_eq!(editor.cursor_pos, (0, 2));
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "ab");
+    }
+
+    #[test]
+    fn undoes_backspace_with_cursor() {
+        let mut editor = TextEditor::build("ab\ncd");
+        editor.cursor_pos = (1, 1);
+        editor.handle_backspace();
+        editor.handle_backspace();
+        editor.handle_backspace();
+        assert_eq!(editor.content_to_save(), "ad");
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "ab\ncd");
+        assert_eq!(editor.cursor_pos, (1, 1));
+    }
+
+    #[test]
+    fn undoes_newline_as_own_step() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        editor.handle_newline();
+        editor.insert_char('b');
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "a\n");
+        assert_eq!(editor.cursor_pos, (1, 0));
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "a");
+        assert_eq!(editor.cursor_pos, (0, 1));
+    }
+
+    #[test]
+    fn redoes_undone_steps() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        editor.handle_newline();
+        editor.undo();
+        editor.undo();
+
+        assert!(editor.redo());
+        assert!(editor.redo());
+        assert!(!editor.redo());
+        assert_eq!(editor.content_to_save(), "a\n");
+        assert_eq!(editor.cursor_pos, (1, 0));
+    }
+
+    #[test]
+    fn clears_redo_on_new_edit() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        editor.undo();
+        editor.insert_char('b');
+
+        assert!(!editor.redo());
+        assert_eq!(editor.content_to_save(), "b");
+    }
+
+    #[test]
+    fn undoes_multibyte_graphemes() {
+        let mut editor = TextEditor::build("e");
+        editor.insert_char('\u{301}');
+        editor.insert_char('日');
+        editor.handle_backspace();
+        editor.handle_backspace();
+        assert_eq!(editor.content_to_save(), "");
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "e\u{301}日");
+        assert_eq!(editor.cursor_pos, (0, 2));
+    }
+}
+
+#[cfg(test)]
+mod selection {
+    use super::*;
+
+    fn select(editor: &mut TextEditor, movement: fn(&mut TextEditor), times: usize) {
+        for _ in 0..times {
+            editor.move_cursor(movement, true);
+        }
+    }
+
+    #[test]
+    fn selects_with_shift_arrows_in_both_directions() {
+        let mut editor = TextEditor::build("hello\nworld");
+        editor.cursor_pos = (0, 1);
+        select(&mut editor, TextEditor::cursor_right, 3);
+        assert_eq!(editor.selected_text(), Some(String::from("ell")));
+
+        select(&mut editor, TextEditor::cursor_left, 4);
+        assert_eq!(editor.selection_range(), Some(((0, 0), (0, 1))));
+        assert_eq!(editor.selected_text(), Some(String::from("h")));
+    }
+
+    #[test]
+    fn selects_across_lines() {
+        let mut editor = TextEditor::build("hello\nworld");
+        editor.cursor_pos = (0, 3);
+        select(&mut editor, TextEditor::cursor_down, 1);
+
+        assert_eq!(editor.selected_text(), Some(String::from("lo\nwor")));
+    }
+
+    #[test]
+    fn drops_selection_when_moving_without_shift() {
+        let mut editor = TextEditor::build("hello");
+        select(&mut editor, TextEditor::cursor_left, 2);
+        editor.move_cursor(TextEditor::cursor_left, false);
+
+        assert_eq!(editor.selection, None);
+        assert_eq!(editor.selected_text(), None);
+    }
+
+    #[test]
+    fn highlights_selection() {
+        let mut editor = TextEditor::build("hello\nworld");
+        editor.cursor_pos = (0, 3);
+        select(&mut editor, TextEditor::cursor_down, 1);
+
+        assert_eq!(
+            editor.content_to_string(true),
+            "hel\x1b[7mlo\x1b[0m\n\r\x1b[7mwor\x1b[0m█d"
+        );
+    }
+
+    #[test]
+    fn keeps_highlight_when_scrolled_horizontally() {
+        let mut editor = TextEditor::build("abcdefgh");
+        editor.wrap = false;
+        editor.resize(4, 1);
+        select(&mut editor, TextEditor::cursor_left, 3);
+
+        assert_eq!(editor.scroll, (0, 5));
+        assert_eq!(editor.visible_content_to_string(), "█\x1b[7mgh\x1b[0m");
+    }
+
+    #[test]
+    fn copies_and_pastes_multiple_lines() {
+        let mut editor = TextEditor::build("ab\ncd\n");
+        editor.cursor_pos = (0, 1);
+        select(&mut editor, TextEditor::cursor_down, 1);
+        assert!(editor.copy());
+
+        editor.move_cursor(TextEditor::cursor_down, false);
+        assert!(editor.paste());
+        assert_eq!(editor.content_to_save(), "ab\ncd\nb\nc");
+        assert_eq!(editor.buffer.line_count(), 4);
+        assert_eq!(editor.cursor_pos, (3, 1));
+    }
+
+    #[test]
+    fn cuts_selection() {
+        let mut editor = TextEditor::build("hello\nworld");
+        editor.cursor_pos = (0, 2);
+        select(&mut editor, TextEditor::cursor_down, 1);
+
+        assert!(editor.cut());
+        assert_eq!(editor.clipboard, "llo\nwo");
+        assert_eq!(editor.content_to_save(), "herld");
+        assert_eq!(editor.cursor_pos, (0, 2));
+        assert_eq!(editor.selection, None);
+    }
+
+    #[test]
+    fn replaces_selection_when_typing_and_pasting() {
+        let mut editor = TextEditor::build("hello");
+        select(&mut editor, TextEditor::cursor_left, 4);
+        editor.insert_char('a');
+        assert_eq!(editor.content_to_save(), "ha");
+
+        editor.clipboard = String::from("ey");
+        select(&mut editor, TextEditor::cursor_left, 1);
+        editor.paste();
+        assert_eq!(editor.content_to_save(), "hey");
+        assert_eq!(editor.cursor_pos, (0, 3));
+    }
+
+    #[test]
+    fn undoes_cut() {
+        let mut editor = TextEditor::build("hello");
+        select(&mut editor, TextEditor::cursor_left, 2);
+        editor.cut();
+        editor.undo();
+
+        assert_eq!(editor.content_to_save(), "hello");
+        assert_eq!(editor.cursor_pos, (0, 3));
+    }
+
+    #[test]
+    fn does_nothing_without_selection_or_clipboard() {
+        let mut editor = TextEditor::build("hello");
+
+        assert!(!editor.copy());
+        assert!(!editor.cut());
+        assert!(!editor.paste());
+        assert_eq!(editor.content_to_save(), "hello");
+    }
+}
+
+#[cfg(test)]
+mod regex {
+    use crate::text_editor::regex::Regex;
+
+    fn find(pattern: &str, text: &str) -> Option<(usize, usize)> {
+        let chars: Vec<char> = text.chars().collect();
+        Regex::build(pattern).unwrap().find_at(&chars, 0)
+    }
+
+    #[test]
+    fn matches_literals_and_any_char() {
+        assert_eq!(find("b.d", "abcde"), Some((1, 4)));
+        assert_eq!(find("x", "abc"), None);
+    }
+
+    #[test]
+    fn repeats_greedily_with_backtracking() {
+        assert_eq!(find("a*b", "caaab"), Some((1, 5)));
+        assert_eq!(find("a.*b", "a1b2b3"), Some((0, 5)));
+        assert_eq!(find("colou?r", "color"), Some((0, 5)));
+        assert_eq!(find("x+", "abc"), None);
+        assert_eq!(find("a+a", "aaa"), Some((0, 3)));
+        assert_eq!(find("a?ab", "xab"), Some((1, 3)));
+    }
+
+    #[test]
+    fn matches_long_lines_in_linear_time() {
+        let text = "a".repeat(100_000);
+        assert_eq!(find("a*a*a*a*a*b", &text), None);
+        assert_eq!(find("a*a*a*a*a*$", &text), Some((0, 100_000)));
+    }
+
+    #[test]
+    fn matches_classes_and_escapes() {
+        assert_eq!(find("[a-c_]+", "xy_ab-c"), Some((2, 5)));
+        assert_eq!(find("[^0-9 ]+", "12 ab3"), Some((3, 5)));
+        assert_eq!(find(r"\d+\s\w+", "v 12 apples"), Some((2, 11)));
+        assert_eq!(find(r"a\.b", "axb a.b"), Some((4, 7)));
+    }
+
+    #[test]
+    fn anchors_to_line_start_and_end() {
+        assert_eq!(find("^ab", "abab"), Some((0, 2)));
+        assert_eq!(find("ab$", "abab"), Some((2, 4)));
+        assert_eq!(find("^b", "ab"), None);
+        assert_eq!(find(r"a\$", "a$"), Some((0, 2)));
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(Regex::build("*a").is_err());
+        assert!(Regex::build("a**").is_err());
+        assert!(Regex::build("[ab").is_err());
+        assert!(Regex::build("[z-a]").is_err());
+        assert!(Regex::build("a\\").is_err());
+    }
+}
+
+#[cfg(test)]
+mod search {
+    use super::*;
+    use crate::text_editor::search::PromptKind;
+    use termion::event::Key;
+
+    fn type_text(editor: &mut TextEditor, text: &str) {
+        for c in text.chars() {
+            editor.prompt_key(Key::Char(c));
+        }
+    }
+
+    #[test]
+    fn selects_first_match_after_cursor_while_typing() {
+        let mut editor = TextEditor::build("one two\ntwo one\none");
+        editor.cursor_pos = (0, 2);
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "on");
+
+        assert_eq!(editor.selection_range(), Some(((1, 4), (1, 6))));
+        type_text(&mut editor, "e");
+        assert_eq!(editor.selection_range(), Some(((1, 4), (1, 7))));
+        assert_eq!(editor.prompt_text(), Some(String::from("Search: one")));
+    }
+
+    #[test]
+    fn jumps_between_matches_around_the_text() {
+        let mut editor = TextEditor::build("ab\nab ab");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "ab");
+        assert_eq!(editor.selection_range(), Some(((0, 0), (0, 2))));
+
+        editor.prompt_key(Key::Down);
+        assert_eq!(editor.selection_range(), Some(((1, 0), (1, 2))));
+        editor.prompt_key(Key::Char('\n'));
+        assert_eq!(editor.selection_range(), Some(((1, 3), (1, 5))));
+        editor.prompt_key(Key::Down);
+        assert_eq!(editor.selection_range(), Some(((0, 0), (0, 2))));
+        editor.prompt_key(Key::Up);
+        assert_eq!(editor.selection_range(), Some(((1, 3), (1, 5))));
+    }
+
+    #[test]
+    fn returns_to_start_without_matches() {
+        let mut editor = TextEditor::build("abc");
+        editor.cursor_pos = (0, 1);
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "x");
+
+        assert_eq!(editor.selection, None);
+        assert_eq!(editor.cursor_pos, (0, 1));
+    }
+
+    #[test]
+    fn searches_the_whole_text_only_on_enter() {
+        let mut editor = TextEditor::build("one\ntwo\nthree\nfour\ntwo");
+        editor.cursor_pos = (0, 0);
+        editor.resize(20, 2);
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "f");
+
+        assert_eq!(editor.selection, None);
+        assert_eq!(editor.cursor_pos, (0, 0));
+        type_text(&mut editor, "ou");
+        editor.prompt_key(Key::Char('\n'));
+        assert_eq!(editor.selection_range(), Some(((3, 0), (3, 3))));
+    }
+
+    #[test]
+    fn highlights_matches() {
+        let mut editor = TextEditor::build("a-a-a");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "a");
+        editor.prompt_key(Key::Esc);
+
+        assert!(editor.search.is_none());
+        assert_eq!(editor.selection_range(), Some(((0, 0), (0, 1))));
+
+        editor.open_search(PromptKind::Search);
+        type_text(&mut editor, "-");
+        assert_eq!(
+            editor.content_to_string(false),
+            "a\x1b[7m-\x1b[0ma\x1b[43m-\x1b[0ma"
+        );
+    }
+
+    #[test]
+    fn searches_with_regex() {
+        let mut editor = TextEditor::build("x1 y22");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Search);
+        editor.prompt_key(Key::Ctrl('e'));
+        type_text(&mut editor, "y\\d+");
+
+        assert_eq!(editor.selection_range(), Some(((0, 3), (0, 6))));
+        assert_eq!(
+            editor.prompt_text(),
+            Some(String::from("Search (regex): y\\d+"))
+        );
+
+        type_text(&mut editor, "[");
+        assert_eq!(editor.selection, None);
+        assert_eq!(
+            editor.prompt_text(),
+            Some(String::from("Search (invalid regex): y\\d+["))
+        );
+    }
+
+    #[test]
+    fn confirms_each_replacement() {
+        let mut editor = TextEditor::build("cat cat\ncat");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Replace);
+        type_text(&mut editor, "cat\ndog\n");
+        assert_eq!(editor.search.as_ref().unwrap().kind, PromptKind::Confirm);
+        assert_eq!(editor.selection_range(), Some(((0, 0), (0, 3))));
+
+        editor.prompt_key(Key::Char('y'));
+        assert_eq!(editor.selection_range(), Some(((0, 4), (0, 7))));
+        editor.prompt_key(Key::Char('n'));
+        assert_eq!(editor.selection_range(), Some(((1, 0), (1, 3))));
+        editor.prompt_key(Key::Char('y'));
+
+        assert!(editor.search.is_none());
+        assert_eq!(editor.content_to_save(), "dog cat\ndog");
+        assert_eq!(
+            editor.notification.as_ref().unwrap().text,
+            "Replaced 2 occurrences"
+        );
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "dog cat\ncat");
+    }
+
+    #[test]
+    fn replaces_all_as_one_step() {
+        let mut editor = TextEditor::build("a1 b22\nc333");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Replace);
+        editor.prompt_key(Key::Ctrl('e'));
+        type_text(&mut editor, "\\d+\n#\n");
+        editor.prompt_key(Key::Char('a'));
+
+        assert_eq!(editor.content_to_save(), "a# b#\nc#");
+        assert_eq!(
+            editor.notification.as_ref().unwrap().text,
+            "Replaced 3 occurrences"
+        );
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "a1 b22\nc333");
+    }
+
+    #[test]
+    fn doesnt_match_inside_replacement() {
+        let mut editor = TextEditor::build("a a");
+        editor.cursor_pos = (0, 0);
+        editor.open_search(PromptKind::Replace);
+        type_text(&mut editor, "a\naa\n");
+        editor.prompt_key(Key::Char('y'));
+        editor.prompt_key(Key::Char('y'));
+
+        assert!(editor.search.is_none());
+        assert_eq!(editor.content_to_save(), "aa aa");
+    }
+}
+
+#[cfg(test)]
+mod status {
+    use super::*;
+    use crate::text_editor::status::{LineEnding, NOTIFICATION_DURATION};
+
+    #[test]
+    fn shows_file_and_cursor_info() {
+        let mut editor = TextEditor::build("ab\ncd");
+        editor.file_name = String::from("notes.txt");
+        editor.cursor_pos = (1, 1);
+
+        assert_eq!(
+            editor.status_bar_text(50),
+            " notes.txt     Ln 2, Col 2 | 2 lines | UTF-8 | LF "
+        );
+    }
+
+    #[test]
+    fn marks_modified_buffer() {
+        let mut editor = TextEditor::build("ab");
+        editor.file_name = String::from("a.txt");
+        editor.insert_char('c');
+
+        assert!(editor.is_modified());
+        assert!(editor.status_bar_text(60).starts_with(" a.txt [+] "));
+    }
+
+    #[test]
+    fn cuts_file_name_to_fit() {
+        let mut editor = TextEditor::build("");
+        editor.file_name = String::from("some/very/long/path.txt");
+
+        assert_eq!(
+            editor.status_bar_text(40),
+            " som Ln 1, Col 1 | 1 lines | UTF-8 | LF "
+        );
+        assert_eq!(editor.status_bar_text(5), "Ln 1,");
+    }
+
+    #[test]
+    fn keeps_windows_line_endings() {
+        let editor = TextEditor::build("ab\r\ncd\r\n");
+
+        assert_eq!(editor.line_ending, LineEnding::Crlf);
+        assert_eq!(editor.buffer.line(0), "ab");
+        assert_eq!(editor.content_to_save(), "ab\r\ncd\r\n");
+        assert!(editor.status_bar_text(40).ends_with("| CRLF "));
+    }
+
+    #[test]
+    fn shows_notification_until_it_expires() {
+        let mut editor = TextEditor::build("");
+        editor.show_notification("File saved!");
+        assert!(editor.status_bar_text(60).starts_with("File saved! "));
+
+        let shown_at = editor.notification.as_ref().unwrap().shown_at;
+        assert!(!editor.expire_notification(shown_at));
+        assert!(editor.expire_notification(shown_at + NOTIFICATION_DURATION));
+        assert!(editor.status_bar_text(60).starts_with(" [No Name] "));
+    }
+}
+
+#[cfg(test)]
+mod dirty_tracking {
+    use super::*;
+
+    #[test]
+    fn starts_clean_and_gets_dirty_on_edit() {
+        let mut editor = TextEditor::build("ab");
+        assert!(!editor.is_modified());
+
+        editor.insert_char('c');
+        assert!(editor.is_modified());
+    }
+
+    #[test]
+    fn gets_clean_when_undoing_back_to_saved_text() {
+        let mut editor = TextEditor::build("ab");
+        editor.insert_char('c');
+        editor.history.mark_saved();
+        assert!(!editor.is_modified());
+
+        editor.undo();
+        assert!(editor.is_modified());
+        editor.redo();
+        assert!(!editor.is_modified());
+    }
+
+    #[test]
+    fn counts_every_applied_edit() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        let step = editor.history.current();
+        let changes = editor.changes;
+
+        // Typing merges into the same undo step but still changes the text
+        editor.insert_char('b');
+        assert_eq!(editor.history.current(), step);
+        assert!(editor.changes > changes);
+
+        let changes = editor.changes;
+        editor.undo();
+        assert!(editor.changes > changes);
+        let changes = editor.changes;
+        editor.redo();
+        assert!(editor.changes > changes);
+    }
+
+    #[test]
+    fn typing_after_save_is_a_new_step() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        editor.history.mark_saved();
+        editor.insert_char('b');
+        assert!(editor.is_modified());
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "a");
+        assert!(!editor.is_modified());
+    }
+
+    #[test]
+    fn stays_dirty_when_saved_text_cant_be_reached() {
+        let mut editor = TextEditor::build("");
+        editor.insert_char('a');
+        editor.history.mark_saved();
+        editor.undo();
+        editor.insert_char('b');
+
+        assert!(editor.is_modified());
+        editor.undo();
+        assert!(editor.is_modified());
+    }
+
+    #[test]
+    fn recovers_text_as_undoable_change() {
+        let mut editor = TextEditor::build("on disk\n");
+        editor.replace_text("recovered\r\ntext");
+
+        assert_eq!(editor.content_to_save(), "recovered\ntext");
+        assert_eq!(editor.cursor_pos, (1, 4));
+        assert!(editor.is_modified());
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "on disk\n");
+        assert!(!editor.is_modified());
+    }
+
+    #[test]
+    fn asks_before_quitting_in_prompt() {
+        let mut editor = TextEditor::build("");
+        editor.confirm_quit = true;
+
+        assert_eq!(
+            editor.prompt_text(),
+            Some(String::from(
+                "Save changes before quitting? (y)es (n)o (c)ancel"
+            ))
+        );
+    }
+}
+
+#[cfg(test)]
+mod line_numbers {
+    use super::*;
+    use crate::text_editor::line_numbers::Gutter;
+    use termion::event::Key;
+
+    #[test]
+    fn renders_absolute_numbers_in_gutter() {
+        let mut editor = TextEditor::build("ab\ncd");
+        editor.gutter = Gutter::Absolute;
+        editor.resize(10, 5);
+
+        assert_eq!(editor.width, 6);
+        assert_eq!(
+            editor.visible_content_to_string(),
+            "\x1b[90m  1 \x1b[0mab\n\r\x1b[90m  2 \x1b[0mcd█"
+        );
+    }
+
+    #[test]
+    fn renders_relative_numbers_around_cursor_line() {
+        let mut editor = TextEditor::build("a\nb\nc");
+        editor.gutter = Gutter::Relative;
+        editor.cursor_pos = (1, 0);
+        editor.resize(10, 5);
+
+        assert_eq!(
+            editor.visible_content_to_string(),
+            "\x1b[90m  1 \x1b[0ma\n\r\x1b[90m  2 \x1b[0m█\n\r\x1b[90m  1 \x1b[0mc"
+        );
+    }
+
+    #[test]
+    fn leaves_wrapped_rows_without_number() {
+        let mut editor = TextEditor::build("abcdef");
+        editor.gutter = Gutter::Absolute;
+        editor.cursor_pos = (0, 0);
+        editor.resize(7, 5);
+
+        assert_eq!(
+            editor.visible_content_to_string(),
+            "\x1b[90m  1 \x1b[0m█bc\n\r    def"
+        );
+    }
+
+    #[test]
+    fn widens_gutter_for_many_lines() {
+        let mut editor = TextEditor::build(&"\n".repeat(1000));
+        assert_eq!(editor.gutter_width(), 0);
+
+        editor.toggle_gutter();
+        assert_eq!(editor.gutter, Gutter::Absolute);
+        assert_eq!(editor.gutter_width(), 5);
+        editor.toggle_gutter();
+        assert_eq!(editor.gutter, Gutter::Relative);
+        editor.toggle_gutter();
+        assert_eq!(editor.gutter, Gutter::Off);
+    }
+
+    #[test]
+    fn goes_to_line_and_column() {
+        let mut editor = TextEditor::build("fn main() {\n    let x = 1;\n}");
+
+        editor.go_to("2:9").unwrap();
+        assert_eq!(editor.cursor_pos, (1, 8));
+        editor.go_to("1").unwrap();
+        assert_eq!(editor.cursor_pos, (0, 0));
+    }
+
+    #[test]
+    fn clamps_position_past_the_end() {
+        let mut editor = TextEditor::build("ab\ncd");
+
+        editor.go_to("1:50").unwrap();
+        assert_eq!(editor.cursor_pos, (0, 2));
+        editor.go_to("99").unwrap();
+        assert_eq!(editor.cursor_pos, (1, 0));
+    }
+
+    #[test]
+    fn rejects_invalid_positions() {
+        let mut editor = TextEditor::build("ab");
+
+        assert!(editor.go_to("").is_err());
+        assert!(editor.go_to("0").is_err());
+        assert!(editor.go_to("1:x").is_err());
+        assert_eq!(editor.cursor_pos, (0, 2));
+    }
+
+    #[test]
+    fn goes_to_line_from_prompt() {
+        let mut editor = TextEditor::build("a\nb\nc");
+        editor.go_to_input = Some(String::new());
+        for c in "2a:1\n".chars() {
+            editor.go_to_key(Key::Char(c));
+        }
+
+        assert_eq!(editor.go_to_input, None);
+        assert_eq!(editor.cursor_pos, (1, 0));
+
+        editor.go_to_input = Some(String::new());
+        editor.go_to_key(Key::Char('\n'));
+        assert_eq!(
+            editor.notification.as_ref().unwrap().text,
+            "Enter a line number"
+        );
+    }
+}
+
+#[cfg(test)]
+mod highlight {
+    use super::*;
+    use crate::text_editor::highlight::{Highlighter, Language};
+    use crate::text_editor::unicode;
+
+    fn editor(language: Language, text: &str) -> TextEditor {
+        let mut editor = TextEditor::build(text);
+        editor.highlighter = Some(Highlighter::new(language));
+        editor
+    }
+
+    // Function to get the highlighted parts of a line with their styles
+    fn styled(editor: &TextEditor, index: usize) -> Vec<(String, &'static str)> {
+        let line: Vec<String> = unicode::graphemes(&editor.buffer.line(index))
+            .map(String::from)
+            .collect();
+        let spans = editor
+            .highlighter
+            .as_ref()
+            .unwrap()
+            .spans(editor.buffer.as_ref(), index);
+        spans
+            .iter()
+            .map(|span| (line[span.start..span.end].concat(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn picks_language_by_extension() {
+        assert_eq!(Language::from_path("src/main.rs"), Some(Language::Rust));
+        assert_eq!(Language::from_path("Cargo.toml"), Some(Language::Toml));
+        assert_eq!(Language::from_path("README.MD"), Some(Language::Markdown));
+        assert_eq!(Language::from_path("data.json"), Some(Language::Json));
+        assert_eq!(Language::from_path("notes.txt"), None);
+        assert_eq!(Language::from_path("Makefile"), None);
+    }
+
+    #[test]
+    fn highlights_rust_tokens() {
+        let editor = editor(
+            Language::Rust,
+            "pub fn main() -> Option<u8> { println!(\"{}\", 'a', 42); } // done",
+        );
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![
+                (String::from("pub"), "\x1b[35m"),
+                (String::from("fn"), "\x1b[35m"),
+                (String::from("Option"), "\x1b[36m"),
+                (String::from("println!"), "\x1b[34m"),
+                (String::from("\"{}\""), "\x1b[32m"),
+                (String::from("'a'"), "\x1b[32m"),
+                (String::from("42"), "\x1b[33m"),
+                (String::from("// done"), "\x1b[90m"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_lifetimes_and_handles_raw_strings() {
+        let editor = editor(Language::Rust, "fn f<'a>(s: &'a str) { r#\"a \"b\"\"#; }");
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![
+                (String::from("fn"), "\x1b[35m"),
+                (String::from("r#\"a \"b\"\"#"), "\x1b[32m"),
+            ]
+        );
+    }
+
+    #[test]
+    fn carries_block_comments_and_strings_over_lines() {
+        let editor = editor(
+            Language::Rust,
+            "let a = 1; /* one\n/* two */ still\nend */ let\n\"multi\nline\" x",
+        );
+
+        assert_eq!(
+            styled(&editor, 1),
+            vec![(String::from("/* two */ still"), "\x1b[90m")]
+        );
+        assert_eq!(
+            styled(&editor, 2),
+            vec![
+                (String::from("end */"), "\x1b[90m"),
+                (String::from("let"), "\x1b[35m"),
+            ]
+        );
+        assert_eq!(
+            styled(&editor, 4),
+            vec![(String::from("line\""), "\x1b[32m")]
+        );
+    }
+
+    #[test]
+    fn rehighlights_lines_below_when_an_edit_opens_a_comment() {
+        let mut editor = editor(Language::Rust, "let a;\nlet b;\nlet c;");
+        assert_eq!(styled(&editor, 2), vec![(String::from("let"), "\x1b[35m")]);
+
+        editor.cursor_pos = (0, 0);
+        editor.insert_char('/');
+        editor.insert_char('*');
+        assert_eq!(
+            styled(&editor, 2),
+            vec![(String::from("let c;"), "\x1b[90m")]
+        );
+
+        editor.undo();
+        assert_eq!(styled(&editor, 2), vec![(String::from("let"), "\x1b[35m")]);
+        editor.redo();
+        assert_eq!(
+            styled(&editor, 1),
+            vec![(String::from("let b;"), "\x1b[90m")]
+        );
+    }
+
+    #[test]
+    fn follows_added_and_removed_lines() {
+        let mut editor = editor(Language::Rust, "// a\nlet b;");
+        assert_eq!(styled(&editor, 1), vec![(String::from("let"), "\x1b[35m")]);
+
+        editor.cursor_pos = (0, 0);
+        editor.handle_newline();
+        editor.handle_newline();
+        assert_eq!(styled(&editor, 2), vec![(String::from("// a"), "\x1b[90m")]);
+        assert_eq!(styled(&editor, 3), vec![(String::from("let"), "\x1b[35m")]);
+
+        editor.handle_backspace();
+        assert_eq!(styled(&editor, 1), vec![(String::from("// a"), "\x1b[90m")]);
+        assert_eq!(styled(&editor, 2), vec![(String::from("let"), "\x1b[35m")]);
+    }
+
+    #[test]
+    fn highlights_toml() {
+        let editor = editor(
+            Language::Toml,
+            "[package]\nname = \"app\" # comment\nopt = true\ntext = \"\"\"\nmore\"\"\"",
+        );
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![(String::from("[package]"), "\x1b[36m")]
+        );
+        assert_eq!(
+            styled(&editor, 1),
+            vec![
+                (String::from("name"), "\x1b[34m"),
+                (String::from("\"app\""), "\x1b[32m"),
+                (String::from("# comment"), "\x1b[90m"),
+            ]
+        );
+        assert_eq!(
+            styled(&editor, 2),
+            vec![
+                (String::from("opt"), "\x1b[34m"),
+                (String::from("true"), "\x1b[35m"),
+            ]
+        );
+        assert_eq!(
+            styled(&editor, 4),
+            vec![(String::from("more\"\"\""), "\x1b[32m")]
+        );
+    }
+
+    #[test]
+    fn highlights_json() {
+        let editor = editor(
+            Language::Json,
+            "{\"name\": \"app\", \"size\": -1.5e3, \"ok\": null}",
+        );
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![
+                (String::from("\"name\""), "\x1b[34m"),
+                (String::from("\"app\""), "\x1b[32m"),
+                (String::from("\"size\""), "\x1b[34m"),
+                (String::from("-1.5e3"), "\x1b[33m"),
+                (String::from("\"ok\""), "\x1b[34m"),
+                (String::from("null"), "\x1b[35m"),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlights_markdown() {
+        let editor = editor(
+            Language::Markdown,
+            "# Title\n- use `cargo` and **bold** [docs](http://x)\n```\n# not a title\n```\nsnake_case",
+        );
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![(String::from("# Title"), "\x1b[1;34m")]
+        );
+        assert_eq!(
+            styled(&editor, 1),
+            vec![
+                (String::from("-"), "\x1b[35m"),
+                (String::from("`cargo`"), "\x1b[32m"),
+                (String::from("**bold**"), "\x1b[1m"),
+                (String::from("[docs]"), "\x1b[4;34m"),
+                (String::from("(http://x)"), "\x1b[90m"),
+            ]
+        );
+        assert_eq!(
+            styled(&editor, 3),
+            vec![(String::from("# not a title"), "\x1b[32m")]
+        );
+        assert_eq!(styled(&editor, 5), vec![]);
+    }
+
+    #[test]
+    fn maps_spans_to_graphemes() {
+        let editor = editor(Language::Rust, "\"e\u{301}日\" x");
+
+        assert_eq!(
+            styled(&editor, 0),
+            vec![(String::from("\"e\u{301}日\""), "\x1b[32m")]
+        );
+    }
+
+    #[test]
+    fn renders_colors() {
+        let mut editor = editor(Language::Json, "[1, true]");
+        editor.cursor_pos = (0, 0);
+
+        assert_eq!(
+            editor.content_to_string(false),
+            "[\x1b[33m1\x1b[0m, \x1b[35mtrue\x1b[0m]"
+        );
+    }
+}
+
+#[cfg(test)]
+mod motions {
+    use super::*;
+
+    #[test]
+    fn deletes_forward_and_joins_next_line() {
+        let mut editor = TextEditor::build("ab\ncd");
+        editor.cursor_pos = (0, 1);
+
+        editor.delete_forward();
+        assert_eq!(editor.content_to_save(), "a\ncd");
+        editor.delete_forward();
+        assert_eq!(editor.content_to_save(), "acd");
+        assert_eq!(editor.cursor_pos, (0, 1));
+
+        editor.cursor_pos = (0, 3);
+        editor.delete_forward();
+        assert_eq!(editor.content_to_save(), "acd");
+    }
+
+    #[test]
+    fn deletes_forward_whole_grapheme_and_undoes_at_once() {
+        let mut editor = TextEditor::build("e\u{301}x");
+        editor.cursor_pos = (0, 0);
+        editor.delete_forward();
+        assert_eq!(editor.content_to_save(), "x");
+        editor.delete_forward();
+        assert_eq!(editor.content_to_save(), "");
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "e\u{301}x");
+        assert_eq!(editor.cursor_pos, (0, 0));
+    }
+
+    #[test]
+    fn goes_to_indent_then_line_start() {
+        let mut editor = TextEditor::build("    let x;");
+        editor.line_start();
+        assert_eq!(editor.cursor_pos, (0, 4));
+        editor.line_start();
+        assert_eq!(editor.cursor_pos, (0, 0));
+        editor.line_start();
+        assert_eq!(editor.cursor_pos, (0, 4));
+
+        editor.line_end();
+        assert_eq!(editor.cursor_pos, (0, 10));
+    }
+
+    #[test]
+    fn jumps_over_words_and_punctuation() {
+        let mut editor = TextEditor::build("let foo_bar = a.b;");
+        editor.cursor_pos = (0, 0);
+
+        let mut stops = vec![];
+        for _ in 0..6 {
+            editor.word_right();
+            stops.push(editor.cursor_pos.1);
+        }
+        assert_eq!(stops, vec![3, 11, 13, 15, 16, 17]);
+
+        let mut stops = vec![];
+        for _ in 0..6 {
+            editor.word_left();
+            stops.push(editor.cursor_pos.1);
+        }
+        assert_eq!(stops, vec![16, 15, 14, 12, 4, 0]);
+    }
+
+    #[test]
+    fn jumps_over_words_across_lines() {
+        let mut editor = TextEditor::build("ab\n  cd");
+        editor.cursor_pos = (0, 2);
+
+        editor.word_right();
+        assert_eq!(editor.cursor_pos, (1, 0));
+        editor.word_right();
+        assert_eq!(editor.cursor_pos, (1, 4));
+
+        editor.cursor_pos = (1, 0);
+        editor.word_left();
+        assert_eq!(editor.cursor_pos, (0, 2));
+    }
+
+    #[test]
+    fn moves_by_pages() {
+        let text: Vec<String> = (0..20).map(|line| line.to_string()).collect();
+        let mut editor = TextEditor::build(&text.join("\n"));
+        editor.resize(10, 5);
+        editor.cursor_pos = (0, 0);
+        editor.scroll = (0, 0);
+
+        editor.page_down();
+        assert_eq!(editor.cursor_pos, (4, 0));
+        assert_eq!(editor.scroll, (4, 0));
+        editor.page_down();
+        editor.page_down();
+        editor.page_down();
+        editor.page_down();
+        assert_eq!(editor.cursor_pos, (19, 0));
+        assert_eq!(editor.scroll.0, 15);
+
+        editor.page_up();
+        assert_eq!(editor.cursor_pos, (15, 0));
+        assert_eq!(editor.scroll.0, 11);
+    }
+
+    #[test]
+    fn inserts_tab_or_spaces_to_next_stop() {
+        let mut editor = TextEditor::build("a");
+        editor.insert_tab();
+        assert_eq!(editor.content_to_save(), "a\t");
+
+        let mut editor = TextEditor::build("a");
+        editor.tab_spaces = Some(4);
+        editor.insert_tab();
+        assert_eq!(editor.content_to_save(), "a   ");
+        editor.insert_tab();
+        assert_eq!(editor.content_to_save(), "a       ");
+        assert_eq!(editor.cursor_pos, (0, 8));
+
+        editor.undo();
+        assert_eq!(editor.content_to_save(), "a");
+    }
+
+    #[test]
+    fn aligns_spaces_by_display_width() {
+        let mut editor = TextEditor::build("日");
+        editor.tab_spaces = Some(4);
+        editor.insert_tab();
+
+        assert_eq!(editor.content_to_save(), "日  ");
+    }
+}
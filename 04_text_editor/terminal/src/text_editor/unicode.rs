@@ -0,0 +1,241 @@
+use std::iter;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Tabs are drawn up to the next multiple of it, like terminals do
+const TAB_WIDTH: usize = 8;
+
+// Function to get the number of terminal cells a grapheme takes.
+// Zero-width ones still take a cell, so the cursor always has something to cover.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    if grapheme.len() == 1 {
+        return 1; // ASCII
+    }
+    std::cmp::max(1, grapheme.width())
+}
+
+// Function to get the cells a grapheme takes when it starts at cell `x` of a row `width` wide,
+// a tab reaches the next tab stop or the row end, whichever comes first
+fn width_at(grapheme: &str, x: usize, width: Option<usize>) -> usize {
+    if grapheme != "\t" {
+        return grapheme_width(grapheme);
+    }
+    let stop = TAB_WIDTH - x % TAB_WIDTH;
+    width.map_or(stop, |width| stop.min(width.saturating_sub(x)).max(1))
+}
+
+// Function to split text into graphemes, segmenting only around non-ASCII chars.
+// There is always a boundary between two ASCII chars, except inside "\r\n".
+pub fn graphemes(s: &str) -> impl Iterator<Item = &str> {
+    let bytes = s.as_bytes();
+    let mut start = 0;
+
+    iter::from_fn(move || {
+        if start >= bytes.len() {
+            return None;
+        }
+
+        let is_single = bytes[start].is_ascii()
+            && bytes.get(start + 1).is_none_or(|next| next.is_ascii())
+            && &bytes[start..std::cmp::min(start + 2, bytes.len())] != b"\r\n";
+        let grapheme = if is_single {
+            &s[start..start + 1]
+        } else {
+            s[start..].graphemes(true).next()?
+        };
+        start += grapheme.len();
+        Some(grapheme)
+    })
+}
+
+// ASCII lines are common and need no segmentation: every byte is a one cell grapheme.
+// ("\r\n" would be the only multi-char one, but lines never contain '\n')
+pub fn grapheme_count(s: &str) -> usize {
+    if s.is_ascii() {
+        return s.len();
+    }
+    graphemes(s).count()
+}
+
+// Function to count chars in the first `column` graphemes
+pub fn char_index(s: &str, column: usize) -> usize {
+    if s.is_ascii() {
+        return std::cmp::min(column, s.len());
+    }
+    graphemes(s)
+        .take(column)
+        .map(|grapheme| grapheme.chars().count())
+        .sum()
+}
+
+// Function to count graphemes starting within the first `chars` chars
+pub fn column_at_char(s: &str, chars: usize) -> usize {
+    if s.is_ascii() {
+        return std::cmp::min(chars, s.len());
+    }
+    let end = s
+        .char_indices()
+        .nth(chars)
+        .map_or(s.len(), |(index, _)| index);
+    grapheme_count(&s[..end])
+}
+
+// Function to place graphemes into rows no wider than `width`, a grapheme is never split.
+// Returns (row, x) of every grapheme, followed by the position of a cursor at the line end.
+pub fn layout(s: &str, width: Option<usize>) -> Vec<(usize, usize)> {
+    let mut positions = Vec::with_capacity(s.len() + 1);
+    let (mut row, mut x) = (0, 0);
+
+    for grapheme in graphemes(s).chain(iter::once(" ")) {
+        if let Some(width) = width {
+            if x > 0 && x + width_at(grapheme, x, Some(width)) > width {
+                row += 1;
+                x = 0;
+            }
+        }
+        positions.push((row, x));
+        x += width_at(grapheme, x, width);
+    }
+    positions
+}
+
+const RESET: &str = "\x1b[0m";
+
+// Graphemes [start, end) of a line drawn with an ANSI escape style
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub style: &'static str,
+}
+
+// Function to find the style of a grapheme, later spans are drawn over earlier ones
+fn style_at(spans: &[Span], index: usize) -> Option<&'static str> {
+    spans
+        .iter()
+        .rev()
+        .find(|span| span.start <= index && index < span.end)
+        .map(|span| span.style)
+}
+
+// Function to render a line into screen rows, covering the grapheme under the cursor with blocks.
+// Every row resets its styles at the end, so rows can be cut and printed on their own.
+pub fn render_rows(
+    s: &str,
+    cursor: Option<usize>,
+    width: Option<usize>,
+    spans: &[Span],
+) -> Vec<String> {
+    if cursor.is_none()
+        && spans.is_empty()
+        && s.is_ascii()
+        && !s.contains('\t')
+        && width.is_none_or(|width| s.len() <= width)
+    {
+        return vec![s.to_string()];
+    }
+
+    let positions = layout(s, width);
+    let mut rows = vec![String::new()];
+    let mut current_style = None;
+
+    let graphemes = graphemes(s).map(Some).chain(iter::once(None));
+    for (index, (grapheme, (row, x))) in graphemes.zip(positions).enumerate() {
+        let is_cursor = cursor == Some(index);
+        if grapheme.is_none() && !is_cursor {
+            break;
+        }
+        if row == rows.len() {
+            if current_style.take().is_some() {
+                rows[row - 1].push_str(RESET);
+            }
+            rows.push(String::new());
+        }
+
+        let text = &mut rows[row];
+        let style = if is_cursor {
+            None
+        } else {
+            style_at(spans, index)
+        };
+        if style != current_style {
+            if current_style.is_some() {
+                text.push_str(RESET);
+            }
+            if let Some(style) = style {
+                text.push_str(style);
+            }
+            current_style = style;
+        }
+
+        match grapheme {
+            Some(grapheme) if is_cursor => text.push_str(&"█".repeat(width_at(grapheme, x, width))),
+            Some("\t") => text.push_str(&" ".repeat(width_at("\t", x, width))),
+            Some(grapheme) => text.push_str(grapheme),
+            None => text.push('█'),
+        }
+    }
+    if current_style.is_some() {
+        if let Some(row) = rows.last_mut() {
+            row.push_str(RESET);
+        }
+    }
+    rows
+}
+
+// Function to cut `width` terminal cells starting at cell `left`.
+// Wide graphemes crossing the edges are replaced by spaces to keep the columns aligned.
+// Escape sequences take no cells and are always kept, so styles of the cut text stay the same.
+pub fn slice_columns(s: &str, left: usize, width: usize) -> String {
+    if s.is_ascii() && !s.contains('\x1b') {
+        let start = std::cmp::min(left, s.len());
+        return s[start..std::cmp::min(left + width, s.len())].to_string();
+    }
+
+    let mut result = String::new();
+    let mut x = 0;
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        if rest.starts_with('\x1b') {
+            let end = rest.find('m').map_or(rest.len(), |index| index + 1);
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = rest.find('\x1b').unwrap_or(rest.len());
+        for grapheme in graphemes(&rest[..end]) {
+            let grapheme_width = grapheme_width(grapheme);
+            let (start, end) = (x, x + grapheme_width);
+            x = end;
+
+            if end <= left || start >= left + width {
+                continue;
+            }
+            if start < left || end > left + width {
+                let visible = std::cmp::min(end, left + width) - std::cmp::max(start, left);
+                result.push_str(&" ".repeat(visible));
+            } else {
+                result.push_str(grapheme);
+            }
+        }
+        rest = &rest[end..];
+    }
+    result
+}
+
+// Function to find the grapheme closest to cell `x` of a screen row, without leaving the row
+pub fn column_in_row(positions: &[(usize, usize)], row: usize, x: usize) -> usize {
+    let mut column = None;
+    for (index, (grapheme_row, grapheme_x)) in positions.iter().enumerate() {
+        if *grapheme_row == row {
+            if column.is_none() || *grapheme_x <= x {
+                column = Some(index);
+            }
+        } else if *grapheme_row > row {
+            break;
+        }
+    }
+    column.unwrap_or(positions.len() - 1)
+}
@@ -0,0 +1,259 @@
+//! Heap used by the scanned tree per entry and cost of key presses in a large folder,
+//! for the current `FolderTree` and for the layout it replaced.
+//!
+//! ```text
+//! cargo bench --bench tree_memory
+//! ```
+//!
+//! On a tree of 202 200 entries and a folder of 50 000 files:
+//!
+//! | Tree                                      | Bytes per entry | Sort key press |
+//! |-------------------------------------------|-----------------|----------------|
+//! | `HashMap<String, Folder>`, owned names    | 137.6           | 7.8 ms         |
+//! | `FolderTree` with interned names          | 52.8            | 5.0 ms         |
+//!
+//! Entries keep size, modification time and owner as plain integers with sentinels
+//! for unknown values, and counts are read from the subfolder instead of copied.
+//! Subfolders are looked up by name in a child map, names no entry uses any more
+//! are dropped from the interner. Baseline sort runs on the bare entries, the tree
+//! one goes through `App::handle_event`.
+use crossterm::event::KeyCode;
+use space_inspector::events::key_press;
+use space_inspector::{App, MemoryStorage};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const PROJECTS: usize = 200;
+const MODULES: usize = 10;
+const FILES: usize = 100;
+const LARGE_FOLDER_FILES: usize = 50_000;
+const KEY_PRESSES: usize = 100;
+
+/// Keeps track of bytes currently allocated
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Layout before `FolderTree`: folders keyed by full path, every entry owning its name
+mod baseline {
+    use space_inspector::{FolderEntryType, Owner, Storage};
+    use std::collections::HashMap;
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    // Unread fields are kept for the space they took
+    #[allow(dead_code)]
+    pub struct Entry {
+        pub title: String,
+        pub size: Option<u64>,
+        pub kind: FolderEntryType,
+        pub owner: Option<Owner>,
+        pub modified: Option<SystemTime>,
+        /// Recursive (files, folders) of scanned folders
+        pub count: Option<(u64, u64)>,
+    }
+
+    #[allow(dead_code)]
+    pub struct Folder {
+        pub title: String,
+        pub cursor_index: usize,
+        pub entries: Vec<Entry>,
+        pub owners: HashMap<u32, u64>,
+        pub last_modified: Option<SystemTime>,
+        pub count: (u64, u64),
+    }
+
+    /// Scans `path` the way `App` did, returning the folder size
+    pub fn scan(storage: &dyn Storage, path: &Path, tree: &mut HashMap<String, Folder>) -> u64 {
+        let mut folder = Folder {
+            title: path.file_name().unwrap().to_string_lossy().into_owned(),
+            cursor_index: 0,
+            entries: vec![Entry {
+                title: String::from(".."),
+                size: None,
+                kind: FolderEntryType::Parent,
+                owner: None,
+                modified: None,
+                count: None,
+            }],
+            owners: HashMap::new(),
+            last_modified: None,
+            count: (0, 0),
+        };
+
+        for entry in storage.read_dir(path).unwrap() {
+            let mut entry = Entry {
+                title: entry.title.to_string(),
+                size: entry.size(),
+                owner: entry.owner(),
+                modified: entry.modified(),
+                kind: entry.kind,
+                count: None,
+            };
+            if entry.kind == FolderEntryType::Folder {
+                let subfolder_path = path.join(&entry.title);
+                entry.size = Some(scan(storage, &subfolder_path, tree));
+                let subfolder = &tree[subfolder_path.to_string_lossy().as_ref()];
+                for (uid, size) in subfolder.owners.iter() {
+                    *folder.owners.entry(*uid).or_insert(0) += size;
+                }
+                entry.count = Some(subfolder.count);
+                folder.count.0 += subfolder.count.0;
+                folder.count.1 += subfolder.count.1 + 1;
+            } else {
+                if let (Some(size), Some(owner)) = (entry.size, entry.owner) {
+                    *folder.owners.entry(owner.uid).or_insert(0) += size;
+                }
+                folder.count.0 += 1;
+            }
+            folder.entries.push(entry);
+        }
+        sort_by_title(&mut folder.entries);
+
+        let size = folder.entries.iter().filter_map(|entry| entry.size).sum();
+        tree.insert(path.to_string_lossy().into_owned(), folder);
+        size
+    }
+
+    /// What a sort key press did: toggle between size and title order
+    pub fn toggle_sort(folder: &mut Folder, by_size: bool) {
+        if by_size {
+            folder.entries.sort_by(|a, b| b.size.cmp(&a.size));
+        } else {
+            sort_by_title(&mut folder.entries);
+        }
+    }
+
+    fn sort_by_title(entries: &mut [Entry]) {
+        entries.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.title.cmp(&b.title)));
+    }
+}
+
+/// Project-like tree where the same file names repeat in every module
+fn project_storage() -> MemoryStorage {
+    let storage = MemoryStorage::new();
+    for project in 0..PROJECTS {
+        for module in 0..MODULES {
+            for file in 0..FILES {
+                storage.add_file(
+                    &format!("/bench/project_{project}/module_{module}/file_{file}.rs"),
+                    1000 + file as u64,
+                );
+            }
+        }
+    }
+    storage
+}
+
+fn print_memory(name: &str, used: usize, elapsed: Duration) {
+    let entries = PROJECTS * MODULES * (FILES + 1) + PROJECTS;
+    println!(
+        "{}: {} entries | {} bytes | {:.1} bytes per entry | scanned in {:.3} s",
+        name,
+        entries,
+        used,
+        used as f64 / entries as f64,
+        elapsed.as_secs_f64()
+    );
+}
+
+fn baseline_memory() {
+    let storage = project_storage();
+
+    let allocated_before = ALLOCATED.load(Ordering::Relaxed);
+    let started = Instant::now();
+    let mut tree = HashMap::new();
+    baseline::scan(&storage, Path::new("/bench"), &mut tree);
+    let elapsed = started.elapsed();
+    let used = ALLOCATED.load(Ordering::Relaxed) - allocated_before;
+
+    print_memory("baseline tree", used, elapsed);
+}
+
+fn tree_memory() {
+    let storage = project_storage();
+
+    let allocated_before = ALLOCATED.load(Ordering::Relaxed);
+    let started = Instant::now();
+    let mut app = App::with_storage(Box::new(storage.clone()));
+    app.init(Some(String::from("/bench")));
+    let elapsed = started.elapsed();
+    let used = ALLOCATED.load(Ordering::Relaxed) - allocated_before;
+
+    print_memory("tree", used, elapsed);
+}
+
+fn large_folder() -> MemoryStorage {
+    let storage = MemoryStorage::new();
+    for file in 0..LARGE_FOLDER_FILES {
+        storage.add_file(&format!("/large/file_{file}.bin"), file as u64);
+    }
+    storage
+}
+
+fn print_key_presses(name: &str, elapsed: Duration) {
+    println!(
+        "{}: {} entries | {:.3} ms per key press",
+        name,
+        LARGE_FOLDER_FILES,
+        elapsed.as_secs_f64() * 1000.0 / KEY_PRESSES as f64
+    );
+}
+
+fn baseline_key_presses() {
+    let storage = large_folder();
+    let mut tree = HashMap::new();
+    baseline::scan(&storage, Path::new("/large"), &mut tree);
+    let folder = tree.get_mut("/large").unwrap();
+
+    let started = Instant::now();
+    for press in 0..KEY_PRESSES {
+        baseline::toggle_sort(folder, press % 2 == 0);
+    }
+    print_key_presses("baseline sort", started.elapsed());
+}
+
+/// Sorting and cursor moves in a single folder with many files
+fn key_presses() {
+    let mut app = App::with_storage(Box::new(large_folder()));
+    app.init(Some(String::from("/large")));
+
+    for (name, code) in [("sort", KeyCode::Char('s')), ("cursor", KeyCode::Down)] {
+        let started = Instant::now();
+        for _ in 0..KEY_PRESSES {
+            app.handle_event(key_press(code));
+        }
+        print_key_presses(name, started.elapsed());
+    }
+}
+
+fn main() {
+    baseline_memory();
+    tree_memory();
+    baseline_key_presses();
+    key_presses();
+}
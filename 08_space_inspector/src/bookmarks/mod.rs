@@ -0,0 +1,78 @@
+use crate::config::config_dir;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const BOOKMARKS_FILE: &str = "bookmarks";
+
+/// Folders to jump to, saved as one absolute path per line
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    pub paths: Vec<PathBuf>,
+    /// Where changes are saved, nothing is persisted without it
+    pub file: Option<PathBuf>,
+}
+
+impl Bookmarks {
+    /// Reads bookmarks from `file`, missing file means no bookmarks yet
+    pub fn load(file: PathBuf) -> Result<Bookmarks, String> {
+        let content = match read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("Can't read {}: {}", file.to_string_lossy(), err)),
+        };
+        let paths = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(PathBuf::from)
+            .collect();
+
+        Ok(Bookmarks {
+            paths,
+            file: Some(file),
+        })
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.iter().any(|bookmark| bookmark == path)
+    }
+
+    /// Adds `path` or removes it if already there, returns `true` when added
+    pub fn toggle(&mut self, path: &Path) -> bool {
+        if self.contains(path) {
+            self.paths.retain(|bookmark| bookmark != path);
+            false
+        } else {
+            self.paths.push(path.to_path_buf());
+            true
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.paths.len() {
+            self.paths.remove(index);
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        let mut content = String::new();
+        for path in &self.paths {
+            content.push_str(&path.to_string_lossy());
+            content.push('\n');
+        }
+
+        file.parent()
+            .map_or(Ok(()), create_dir_all)
+            .and_then(|_| write(file, content))
+            .map_err(|err| format!("Can't save {}: {}", file.to_string_lossy(), err))
+    }
+}
+
+/// `bookmarks` in the config directory
+pub fn default_bookmarks_file() -> Option<PathBuf> {
+    Some(config_dir()?.join(BOOKMARKS_FILE))
+}
@@ -1,13 +1,130 @@
+use std::env;
+use std::path::PathBuf;
+
 pub struct Config {
     pub file_path: Option<String>,
+    pub read_only: bool,
+    pub protected_paths: Vec<PathBuf>,
+    pub confirm_size_threshold: Option<u64>,
+    /// Run as agent answering storage requests on stdin/stdout
+    pub serve: bool,
+    /// Agent to inspect instead of the local disk
+    pub remote: Option<RemoteAgent>,
+    pub theme: Option<String>,
+    pub theme_file: Option<PathBuf>,
+    /// Folder for HTML reports, current directory by default
+    pub export_dir: Option<PathBuf>,
+    /// `NO_COLOR` is set, start with monochrome theme
+    pub no_color: bool,
+    /// Scan without the UI and print scan statistics
+    pub report: bool,
+    pub bookmarks_file: Option<PathBuf>,
+}
+
+/// How to start the agent answering storage requests
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteAgent {
+    /// Shell command given with `--remote`
+    Command(String),
+    /// Host given with `--ssh`, the agent is started with `ssh <host> space_inspector --serve`
+    Ssh(String),
 }
 
 impl Config {
     pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
         args.next();
 
-        Ok(Config {
-            file_path: args.next(),
-        })
+        let mut config = Config {
+            file_path: None,
+            read_only: false,
+            protected_paths: vec![],
+            confirm_size_threshold: None,
+            serve: false,
+            remote: None,
+            theme: None,
+            theme_file: None,
+            export_dir: None,
+            no_color: false,
+            report: false,
+            bookmarks_file: None,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--read-only" | "--dry-run" => config.read_only = true,
+                "--protect" => {
+                    let path = args.next().ok_or("Missing path after --protect")?;
+                    config.protected_paths.push(PathBuf::from(path));
+                }
+                "--confirm-above" => {
+                    let size = args.next().ok_or("Missing size after --confirm-above")?;
+                    config.confirm_size_threshold = Some(parse_size(&size)?);
+                }
+                "--serve" => config.serve = true,
+                "--report" => config.report = true,
+                "--remote" => {
+                    let command = args.next().ok_or("Missing command after --remote")?;
+                    config.remote = Some(RemoteAgent::Command(command));
+                }
+                "--ssh" => {
+                    let host = args.next().ok_or("Missing host after --ssh")?;
+                    // Would be taken as an ssh option
+                    if host.starts_with('-') {
+                        return Err("Host after --ssh can't start with -");
+                    }
+                    config.remote = Some(RemoteAgent::Ssh(host));
+                }
+                "--theme" => {
+                    let theme = args.next().ok_or("Missing name after --theme")?;
+                    config.theme = Some(theme);
+                }
+                "--theme-file" => {
+                    let path = args.next().ok_or("Missing path after --theme-file")?;
+                    config.theme_file = Some(PathBuf::from(path));
+                }
+                "--export-dir" => {
+                    let path = args.next().ok_or("Missing path after --export-dir")?;
+                    config.export_dir = Some(PathBuf::from(path));
+                }
+                "--bookmarks-file" => {
+                    let path = args.next().ok_or("Missing path after --bookmarks-file")?;
+                    config.bookmarks_file = Some(PathBuf::from(path));
+                }
+                _ => {
+                    if config.file_path.is_some() {
+                        return Err("Only one path can be inspected");
+                    }
+                    config.file_path = Some(arg);
+                }
+            }
+        }
+
+        Ok(config)
     }
 }
+
+/// Parses sizes like "512", "10K", "1.5G"
+pub fn parse_size(value: &str) -> Result<u64, &'static str> {
+    let value = value.trim();
+    let (number, multiplier) = match value.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&value[..value.len() - 1], 1024u64),
+        Some('M') => (&value[..value.len() - 1], 1024u64.pow(2)),
+        Some('G') => (&value[..value.len() - 1], 1024u64.pow(3)),
+        Some('T') => (&value[..value.len() - 1], 1024u64.pow(4)),
+        _ => (value, 1),
+    };
+
+    match number.parse::<f64>() {
+        Ok(n) if n >= 0.0 => Ok((n * multiplier as f64) as u64),
+        _ => Err("Invalid size"),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/space_inspector`, falling back to `~/.config/space_inspector`
+pub fn config_dir() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("space_inspector"))
+}
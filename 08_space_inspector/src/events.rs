@@ -0,0 +1,58 @@
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use std::collections::VecDeque;
+use std::io;
+
+/// Source of input events driving `App::run_with_events`.
+/// Returning `None` ends the run loop.
+pub trait EventSource {
+    fn next_event(&mut self) -> io::Result<Option<Event>>;
+}
+
+/// Reads events from the terminal
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        Ok(Some(event::read()?))
+    }
+}
+
+/// Replays a fixed list of events
+#[derive(Debug, Default)]
+pub struct ScriptedEvents {
+    events: VecDeque<Event>,
+}
+
+impl ScriptedEvents {
+    pub fn new(keys: &[KeyCode]) -> Self {
+        ScriptedEvents {
+            events: keys.iter().map(|code| key_press(*code)).collect(),
+        }
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        Ok(self.events.pop_front())
+    }
+}
+
+pub fn key_press(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new_with_kind(
+        code,
+        KeyModifiers::NONE,
+        KeyEventKind::Press,
+    ))
+}
+
+pub fn mouse_click(column: u16, row: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
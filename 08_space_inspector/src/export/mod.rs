@@ -0,0 +1,275 @@
+use crate::fs::{FolderEntryType, FolderId, FolderTree, ItemCount};
+use crate::ui::format_file_size;
+use std::f64::consts::PI;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Largest files listed per folder, the rest is summarized in one row
+const MAX_FILES_PER_FOLDER: usize = 20;
+const SUNBURST_DEPTH: usize = 6;
+const SUNBURST_CENTER: f64 = 320.0;
+const SUNBURST_HOLE: f64 = 60.0;
+const SUNBURST_RING: f64 = 42.0;
+/// Segments narrower than this (radians) are not drawn
+const SUNBURST_MIN_ANGLE: f64 = 0.004;
+const TREE_OPEN_DEPTH: usize = 1;
+
+const STYLE: &str = "
+body { font: 14px/1.4 -apple-system, 'Segoe UI', sans-serif; margin: 0; color: #1e293b; background: #f8fafc; }
+header { padding: 16px 24px; background: #0f172a; color: #e2e8f0; }
+header h1 { margin: 0; font-size: 20px; }
+header p { margin: 4px 0 0; color: #94a3b8; }
+main { display: flex; flex-wrap: wrap; gap: 24px; padding: 24px; }
+section.chart { flex: 0 0 640px; }
+section.tree { flex: 1 1 480px; min-width: 0; }
+svg a path { stroke: #f8fafc; stroke-width: 1; }
+svg a:hover path { opacity: 0.75; }
+details { margin-left: 16px; }
+details:target > summary, .file:target { background: #fde68a; }
+summary, .file { display: grid; grid-template-columns: minmax(0, 1fr) 96px 80px 160px; gap: 8px; padding: 1px 4px; cursor: pointer; }
+.file { margin-left: 32px; cursor: default; }
+.name { overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.size, .count { text-align: right; font-variant-numeric: tabular-nums; }
+.bar { background: #e2e8f0; height: 10px; margin-top: 5px; }
+.bar i { display: block; height: 100%; background: #0ea5e9; }
+.more { color: #64748b; font-style: italic; }
+";
+
+/// Scanned folder or a file within the exported subtree
+#[derive(Debug)]
+struct ReportNode {
+    id: usize,
+    name: String,
+    size: u64,
+    count: Option<ItemCount>,
+    is_folder: bool,
+    children: Vec<ReportNode>,
+    /// Files left out to keep the report small: (amount, size)
+    hidden_files: (usize, u64),
+}
+
+/// Builds a self-contained HTML page with collapsible tree and sunburst chart
+/// of the subtree at `path`. Folders that were not scanned are left out.
+pub fn html_report(tree: &FolderTree, path: &Path) -> String {
+    let mut next_id = 0;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+    let root = build_node(tree, name, tree.find(path), &mut next_id);
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Space inspector | {}</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <header><h1>{}</h1><p>{} | {} files | {} folders</p></header>\n<main>\n",
+        escape(&root.name),
+        STYLE,
+        escape(&path.to_string_lossy()),
+        format_file_size(root.size),
+        root.count.unwrap_or_default().files,
+        root.count.unwrap_or_default().folders,
+    );
+
+    html.push_str("<section class=\"chart\">\n");
+    let size = SUNBURST_CENTER * 2.0;
+    let _ = writeln!(
+        html,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">"
+    );
+    render_sunburst(&mut html, &root, 0, -PI / 2.0, 2.0 * PI);
+    let _ = writeln!(
+        html,
+        "<text x=\"{c}\" y=\"{c}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>\n</svg>",
+        format_file_size(root.size),
+        c = SUNBURST_CENTER
+    );
+    html.push_str("</section>\n<section class=\"tree\">\n");
+    render_tree(&mut html, &root, root.size, 0);
+    html.push_str("</section>\n</main>\n</body>\n</html>\n");
+
+    html
+}
+
+fn build_node(
+    tree: &FolderTree,
+    name: String,
+    folder_id: Option<FolderId>,
+    next_id: &mut usize,
+) -> ReportNode {
+    let mut node = ReportNode {
+        id: *next_id,
+        name,
+        size: 0,
+        count: None,
+        is_folder: true,
+        children: vec![],
+        hidden_files: (0, 0),
+    };
+    *next_id += 1;
+
+    let Some(folder) = folder_id.and_then(|id| tree.get(id)) else {
+        return node;
+    };
+    node.size = folder.get_size();
+    node.count = Some(folder.count);
+
+    let mut files = vec![];
+    for entry in &folder.entries {
+        match entry.kind {
+            FolderEntryType::Parent => {}
+            FolderEntryType::Folder => {
+                let mut child = build_node(tree, entry.title.to_string(), entry.folder(), next_id);
+                child.size = entry.size().unwrap_or(child.size);
+                node.children.push(child);
+            }
+            FolderEntryType::File => files.push(entry),
+        }
+    }
+
+    files.sort_by(|a, b| b.size().cmp(&a.size()).then(a.title.cmp(&b.title)));
+    for (index, file) in files.into_iter().enumerate() {
+        let size = file.size().unwrap_or(0);
+        if index < MAX_FILES_PER_FOLDER {
+            node.children.push(ReportNode {
+                id: *next_id,
+                name: file.title.to_string(),
+                size,
+                count: None,
+                is_folder: false,
+                children: vec![],
+                hidden_files: (0, 0),
+            });
+            *next_id += 1;
+        } else {
+            node.hidden_files.0 += 1;
+            node.hidden_files.1 += size;
+        }
+    }
+    node.children
+        .sort_by(|a, b| b.size.cmp(&a.size).then(a.name.cmp(&b.name)));
+
+    node
+}
+
+fn render_tree(html: &mut String, node: &ReportNode, root_size: u64, depth: usize) {
+    let percent = if root_size == 0 {
+        0.0
+    } else {
+        node.size as f64 * 100.0 / root_size as f64
+    };
+    let count = node
+        .count
+        .map(|count| format!("{} files", count.files))
+        .unwrap_or_default();
+    let columns = format!(
+        "<span class=\"name\" title=\"{name}\">{name}</span><span class=\"size\">{}</span>\
+         <span class=\"count\">{}</span><span class=\"bar\"><i style=\"width:{:.1}%\"></i></span>",
+        format_file_size(node.size),
+        count,
+        percent,
+        name = escape(&node.name),
+    );
+
+    if !node.is_folder {
+        let _ = writeln!(
+            html,
+            "<div class=\"file\" id=\"n{}\">{}</div>",
+            node.id, columns
+        );
+        return;
+    }
+
+    let open = if depth <= TREE_OPEN_DEPTH {
+        " open"
+    } else {
+        ""
+    };
+    let _ = writeln!(
+        html,
+        "<details id=\"n{}\"{}><summary>{}</summary>",
+        node.id, open, columns
+    );
+    for child in &node.children {
+        render_tree(html, child, root_size, depth + 1);
+    }
+    if node.hidden_files.0 > 0 {
+        let _ = writeln!(
+            html,
+            "<div class=\"file more\"><span class=\"name\">{} more files</span><span class=\"size\">{}</span></div>",
+            node.hidden_files.0,
+            format_file_size(node.hidden_files.1)
+        );
+    }
+    html.push_str("</details>\n");
+}
+
+/// Draws `node` children as ring `depth + 1`, spanning `sweep` radians from `start`
+fn render_sunburst(html: &mut String, node: &ReportNode, depth: usize, start: f64, sweep: f64) {
+    if depth >= SUNBURST_DEPTH || node.size == 0 {
+        return;
+    }
+
+    let inner = SUNBURST_HOLE + depth as f64 * SUNBURST_RING;
+    let outer = inner + SUNBURST_RING;
+    let mut angle = start;
+
+    for child in &node.children {
+        let child_sweep = sweep * child.size as f64 / node.size as f64;
+        if child_sweep >= SUNBURST_MIN_ANGLE {
+            let hue =
+                ((angle + child_sweep / 2.0 + PI / 2.0) / (2.0 * PI) * 360.0).rem_euclid(360.0);
+            let lightness = if child.is_folder { 45 + depth * 6 } else { 75 };
+            let _ = writeln!(
+                html,
+                "<a href=\"#n{}\"><path d=\"{}\" fill=\"hsl({:.0},60%,{}%)\"><title>{} ({})</title></path></a>",
+                child.id,
+                arc_path(inner, outer, angle, child_sweep),
+                hue,
+                lightness.min(85),
+                escape(&child.name),
+                format_file_size(child.size)
+            );
+            render_sunburst(html, child, depth + 1, angle, child_sweep);
+        }
+        angle += child_sweep;
+    }
+}
+
+fn arc_path(inner: f64, outer: f64, start: f64, sweep: f64) -> String {
+    // Full circle can't be drawn with a single arc
+    let sweep = sweep.min(2.0 * PI - 0.0001);
+    let end = start + sweep;
+    let large_arc = if sweep > PI { 1 } else { 0 };
+    let point = |radius: f64, angle: f64| {
+        (
+            SUNBURST_CENTER + radius * angle.cos(),
+            SUNBURST_CENTER + radius * angle.sin(),
+        )
+    };
+    let (x0, y0) = point(outer, start);
+    let (x1, y1) = point(outer, end);
+    let (x2, y2) = point(inner, end);
+    let (x3, y3) = point(inner, start);
+
+    format!(
+        "M{x0:.2},{y0:.2} A{outer},{outer} 0 {large_arc} 1 {x1:.2},{y1:.2} \
+         L{x2:.2},{y2:.2} A{inner},{inner} 0 {large_arc} 0 {x3:.2},{y3:.2} Z"
+    )
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,139 @@
+use crate::fs::folder_entry::FolderEntryType;
+use crate::fs::tree::FolderTree;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Well-known regenerable folder, recognised by its name and a marker file next to it
+#[derive(Debug)]
+pub struct CleanPreset {
+    pub name: &'static str,
+    pub folder: &'static str,
+    /// Any of these must be a sibling of the folder, empty means the name is enough
+    pub markers: &'static [&'static str],
+}
+
+const GRADLE_MARKERS: &[&str] = &[
+    "build.gradle",
+    "build.gradle.kts",
+    "settings.gradle",
+    "settings.gradle.kts",
+];
+
+pub const CLEAN_PRESETS: &[CleanPreset] = &[
+    CleanPreset {
+        name: "Cargo",
+        folder: "target",
+        markers: &["Cargo.toml"],
+    },
+    CleanPreset {
+        name: "Maven",
+        folder: "target",
+        markers: &["pom.xml"],
+    },
+    CleanPreset {
+        name: "Node",
+        folder: "node_modules",
+        markers: &["package.json"],
+    },
+    CleanPreset {
+        name: "Next.js",
+        folder: ".next",
+        markers: &["package.json"],
+    },
+    CleanPreset {
+        name: "Gradle",
+        folder: ".gradle",
+        markers: GRADLE_MARKERS,
+    },
+    CleanPreset {
+        name: "Gradle",
+        folder: "build",
+        markers: GRADLE_MARKERS,
+    },
+    CleanPreset {
+        name: "Python",
+        folder: "__pycache__",
+        markers: &[],
+    },
+    CleanPreset {
+        name: "pytest",
+        folder: ".pytest_cache",
+        markers: &[],
+    },
+    CleanPreset {
+        name: "mypy",
+        folder: ".mypy_cache",
+        markers: &[],
+    },
+    CleanPreset {
+        name: "tox",
+        folder: ".tox",
+        markers: &["tox.ini"],
+    },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cleanable {
+    pub path: PathBuf,
+    pub preset: &'static str,
+    pub size: u64,
+    pub last_used: Option<SystemTime>,
+    pub selected: bool,
+}
+
+/// Finds regenerable folders across already scanned tree, biggest first.
+/// Folders nested inside another match (e.g. `node_modules/x/node_modules`) are skipped.
+pub fn find_cleanables(tree: &FolderTree) -> Vec<Cleanable> {
+    let mut cleanables: Vec<Cleanable> = vec![];
+
+    for (id, folder) in tree.iter() {
+        let has_sibling = |name: &str| {
+            folder
+                .entries
+                .iter()
+                .any(|entry| entry.kind == FolderEntryType::File && entry.title == name)
+        };
+
+        for entry in folder
+            .entries
+            .iter()
+            .filter(|entry| entry.kind == FolderEntryType::Folder)
+        {
+            let preset = CLEAN_PRESETS.iter().find(|preset| {
+                entry.title == preset.folder
+                    && (preset.markers.is_empty()
+                        || preset.markers.iter().any(|marker| has_sibling(marker)))
+            });
+
+            if let Some(preset) = preset {
+                let last_used = entry
+                    .folder()
+                    .and_then(|subfolder| tree.get(subfolder))
+                    .and_then(|subfolder| subfolder.last_modified)
+                    .or(entry.modified());
+
+                cleanables.push(Cleanable {
+                    path: tree.path(id).join(&entry.title),
+                    preset: preset.name,
+                    size: entry.size().unwrap_or(0),
+                    last_used,
+                    selected: false,
+                });
+            }
+        }
+    }
+
+    let nested: Vec<bool> = cleanables
+        .iter()
+        .map(|cleanable| {
+            cleanables.iter().any(|other| {
+                other.path != cleanable.path && cleanable.path.starts_with(&other.path)
+            })
+        })
+        .collect();
+    let mut nested = nested.into_iter();
+    cleanables.retain(|_| !nested.next().unwrap_or(false));
+
+    cleanables.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+    cleanables
+}
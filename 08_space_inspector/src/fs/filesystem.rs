@@ -0,0 +1,182 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSystem {
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    pub total: u64,
+    pub used: u64,
+    /// Space available to unprivileged users
+    pub free: u64,
+}
+
+impl FileSystem {
+    pub fn used_percent(&self) -> f64 {
+        percent_of(self.used, self.total)
+    }
+}
+
+pub fn percent_of(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
+
+/// (total, used, free) bytes of the filesystem containing `path`
+#[cfg(unix)]
+pub fn statvfs(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let fragment_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * fragment_size;
+    let used = (stat.f_blocks as u64).saturating_sub(stat.f_bfree as u64) * fragment_size;
+    let free = stat.f_bavail as u64 * fragment_size;
+
+    Some((total, used, free))
+}
+
+/// No filesystem usage outside unix, so no filesystems are listed
+#[cfg(not(unix))]
+pub fn statvfs(_path: &Path) -> Option<(u64, u64, u64)> {
+    None
+}
+
+/// All mounted filesystems with non-zero capacity, sorted by mount point
+pub fn mounted_filesystems() -> Vec<FileSystem> {
+    let mut filesystems: Vec<FileSystem> = Vec::new();
+
+    for (device, mount_point, fs_type) in read_mounts() {
+        if let Some((total, used, free)) = statvfs(&mount_point) {
+            if total == 0 {
+                continue;
+            }
+            // Later mounts shadow earlier ones on the same mount point
+            filesystems.retain(|fs| fs.mount_point != mount_point);
+            filesystems.push(FileSystem {
+                device,
+                mount_point,
+                fs_type,
+                total,
+                used,
+                free,
+            });
+        }
+    }
+
+    filesystems.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    filesystems
+}
+
+/// Filesystem the `path` lives on, picked by the longest matching mount point.
+/// Only `path` itself is queried, so an unresponsive mount elsewhere can't block
+pub fn filesystem_for_path(path: &Path) -> Option<FileSystem> {
+    let (total, used, free) = statvfs(path)?;
+
+    let (device, mount_point, fs_type) = mount_for_path(read_mounts(), path)
+        .unwrap_or_else(|| (String::new(), PathBuf::from("/"), String::new()));
+
+    Some(FileSystem {
+        device,
+        mount_point,
+        fs_type,
+        total,
+        used,
+        free,
+    })
+}
+
+/// Mount entry with the longest mount point containing `path`; later mounts win ties
+pub fn mount_for_path(
+    mounts: Vec<(String, PathBuf, String)>,
+    path: &Path,
+) -> Option<(String, PathBuf, String)> {
+    mounts
+        .into_iter()
+        .filter(|(_, mount_point, _)| path.starts_with(mount_point))
+        .fold(None, |best, mount| match best {
+            Some(best) if best.1.as_os_str().len() > mount.1.as_os_str().len() => Some(best),
+            _ => Some(mount),
+        })
+}
+
+#[cfg(target_os = "linux")]
+fn read_mounts() -> Vec<(String, PathBuf, String)> {
+    let content = std::fs::read_to_string("/proc/mounts").unwrap_or_default();
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let device = unescape_mount_field(parts.next()?);
+            let mount_point = PathBuf::from(unescape_mount_field(parts.next()?));
+            let fs_type = parts.next()?.to_string();
+            Some((device, mount_point, fs_type))
+        })
+        .collect()
+}
+
+/// `/proc/mounts` escapes whitespace and backslashes as octal (`\040`)
+#[cfg(target_os = "linux")]
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'\\' && index + 3 < bytes.len() {
+            let digits = std::str::from_utf8(&bytes[index + 1..index + 4]).unwrap_or("");
+            if let Ok(code) = u8::from_str_radix(digits, 8) {
+                result.push(code);
+                index += 4;
+                continue;
+            }
+        }
+        result.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(target_os = "macos")]
+fn read_mounts() -> Vec<(String, PathBuf, String)> {
+    let mut buf: *mut libc::statfs = std::ptr::null_mut();
+    let count = unsafe { libc::getmntinfo(&mut buf, libc::MNT_NOWAIT) };
+
+    if count <= 0 || buf.is_null() {
+        return vec![];
+    }
+
+    let entries = unsafe { std::slice::from_raw_parts(buf, count as usize) };
+    entries
+        .iter()
+        .map(|entry| unsafe {
+            (
+                c_chars_to_string(entry.f_mntfromname.as_ptr()),
+                PathBuf::from(c_chars_to_string(entry.f_mntonname.as_ptr())),
+                c_chars_to_string(entry.f_fstypename.as_ptr()),
+            )
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn c_chars_to_string(ptr: *const libc::c_char) -> String {
+    std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_mounts() -> Vec<(String, PathBuf, String)> {
+    vec![]
+}
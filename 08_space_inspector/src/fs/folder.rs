@@ -1,43 +1,80 @@
 use crate::ui::TEXT_PARENT_DIR;
 
-use crate::fs::folder_entry::{FolderEntry, FolderEntryType};
-use std::cmp::Ordering;
+use crate::fs::folder_entry::{FolderEntry, FolderEntryType, ItemCount};
+use crate::fs::name::Name;
+use crate::fs::owner::OwnerUsage;
+use crate::fs::tree::FolderId;
+use std::cmp::{Ordering, Reverse};
+use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
 pub struct Folder {
-    pub title: String,
+    pub title: Name,
     pub cursor_index: usize,
     pub entries: Vec<FolderEntry>,
+    pub owners: OwnerUsage,
+    /// Newest modification time within the subtree
+    pub last_modified: Option<SystemTime>,
+    /// Files and folders within the subtree
+    pub count: ItemCount,
+    /// Containing folder, unless it was not scanned
+    pub parent: Option<FolderId>,
 }
 
 impl Folder {
-    pub fn new(title: String) -> Self {
+    pub fn new(title: Name) -> Self {
         Folder {
             title,
             cursor_index: 0,
-            entries: vec![FolderEntry {
-                kind: FolderEntryType::Parent,
-                title: String::from(TEXT_PARENT_DIR),
-                size: None,
-            }],
+            entries: vec![FolderEntry::new(
+                FolderEntryType::Parent,
+                Name::from(TEXT_PARENT_DIR),
+            )],
+            owners: OwnerUsage::new(),
+            last_modified: None,
+            count: ItemCount::default(),
+            parent: None,
         }
     }
 
     pub fn get_size(&self) -> u64 {
         self.entries
             .iter()
-            .fold(0, |acc, entry| acc + entry.size.unwrap_or(0))
+            .fold(0, |acc, entry| acc + entry.size().unwrap_or(0))
     }
 
     pub fn get_selected_entry_size(&self) -> u64 {
-        self.get_selected_entry().size.unwrap_or(0)
+        self.get_selected_entry().size().unwrap_or(0)
     }
 
-    pub fn remove_selected(&mut self) {
-        self.entries.remove(self.cursor_index);
+    pub fn remove_selected(&mut self) -> FolderEntry {
+        let entry = self.entries.remove(self.cursor_index);
         if self.cursor_index > self.entries.len() - 1 {
             self.cursor_index = self.entries.len() - 1
         }
+        entry
+    }
+
+    pub fn touch(&mut self, modified: Option<SystemTime>) {
+        if modified > self.last_modified {
+            self.last_modified = modified;
+        }
+    }
+
+    /// Removes entry by title keeping cursor on the same entry where possible
+    pub fn remove_entry(&mut self, title: &str) -> Option<FolderEntry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.kind != FolderEntryType::Parent && entry.title == title)?;
+        let entry = self.entries.remove(index);
+        if index < self.cursor_index {
+            self.cursor_index -= 1;
+        }
+        if self.cursor_index > self.entries.len() - 1 {
+            self.cursor_index = self.entries.len() - 1
+        }
+        Some(entry)
     }
 
     pub fn get_selected_entry(&self) -> &FolderEntry {
@@ -48,18 +85,37 @@ impl Folder {
         }
     }
 
-    pub fn to_list(&self) -> Vec<FolderEntry> {
-        vec![&self.entries]
-            .into_iter()
-            .flat_map(|v| v.iter().cloned())
-            .collect()
+    pub fn is_visible(&self, index: usize, is_shown: &dyn Fn(&FolderEntry) -> bool) -> bool {
+        self.entries.get(index).is_some_and(is_shown)
+    }
+
+    pub fn cursor_up(&mut self, is_shown: &dyn Fn(&FolderEntry) -> bool) {
+        if let Some(index) = (0..self.cursor_index).rfind(|index| self.is_visible(*index, is_shown))
+        {
+            self.cursor_index = index;
+        }
+    }
+
+    pub fn cursor_down(&mut self, is_shown: &dyn Fn(&FolderEntry) -> bool) {
+        if let Some(index) = (self.cursor_index + 1..self.entries.len())
+            .find(|index| self.is_visible(*index, is_shown))
+        {
+            self.cursor_index = index;
+        }
+    }
+
+    /// Moves cursor back to the parent entry if selected one is hidden by filter
+    pub fn snap_cursor(&mut self, is_shown: &dyn Fn(&FolderEntry) -> bool) {
+        if !self.is_visible(self.cursor_index, is_shown) {
+            self.cursor_index = 0;
+        }
     }
 
     pub fn get_max_entry_size(&self) -> u64 {
         let mut max_entry_size = 0;
 
         for file in &self.entries {
-            if let Some(size) = file.size {
+            if let Some(size) = file.size() {
                 if size > max_entry_size {
                     max_entry_size = size
                 }
@@ -69,10 +125,22 @@ impl Folder {
         max_entry_size
     }
 
+    /// Titles are unique within a folder, so the order doesn't need to be stable
     pub fn sort_by_title(&mut self) {
         self.entries.sort();
     }
 
+    /// Folders with most items first, then files by title
+    pub fn sort_by_count(&mut self, count: impl Fn(&FolderEntry) -> u64) {
+        self.entries.sort_by_cached_key(|entry| {
+            (
+                entry.kind != FolderEntryType::Parent,
+                Reverse(count(entry)),
+                entry.title.clone(),
+            )
+        });
+    }
+
     pub fn sort_by_size(&mut self) {
         self.entries.sort_by(|a, b| {
             if a.kind == FolderEntryType::Parent || b.kind == FolderEntryType::Parent {
@@ -84,13 +152,13 @@ impl Folder {
                 } else {
                     Ordering::Equal
                 }
-            } else if let (Some(size_a), Some(size_b)) = (a.size, b.size) {
+            } else if let (Some(size_a), Some(size_b)) = (a.size(), b.size()) {
                 // Sort by size in descending order
                 size_b.cmp(&size_a)
-            } else if a.size.is_some() {
+            } else if a.size().is_some() {
                 // Entries with size come before those without
                 Ordering::Greater
-            } else if b.size.is_some() {
+            } else if b.size().is_some() {
                 // Entries without size come after those with
                 Ordering::Less
             } else {
@@ -1,4 +1,8 @@
+use crate::fs::name::Name;
+use crate::fs::owner::Owner;
+use crate::fs::tree::FolderId;
 use std::cmp::Ordering;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq)]
 pub enum FolderEntryType {
@@ -21,11 +25,43 @@ impl Ord for FolderEntryType {
     }
 }
 
+/// Number of files and folders below a folder, counted recursively
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ItemCount {
+    pub files: u64,
+    pub folders: u64,
+}
+
+impl ItemCount {
+    pub fn add(&mut self, other: ItemCount) {
+        self.files += other.files;
+        self.folders += other.folders;
+    }
+
+    pub fn subtract(&mut self, other: ItemCount) {
+        self.files = self.files.saturating_sub(other.files);
+        self.folders = self.folders.saturating_sub(other.folders);
+    }
+}
+
+/// Size or modification time that couldn't be read
+const UNKNOWN: u64 = u64::MAX;
+/// Uid of entries without an owner, `(uid_t)-1` is never a real one
+const NO_OWNER: u32 = u32::MAX;
+
+/// Entry of a scanned folder. Trees hold tens of millions of them, so missing values are
+/// kept as sentinels in plain integers instead of `Option`s and read back through accessors.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct FolderEntry {
-    pub title: String,
-    pub size: Option<u64>,
+    pub title: Name,
     pub kind: FolderEntryType,
+    size: u64,
+    /// Seconds since the epoch
+    modified: u64,
+    uid: u32,
+    gid: u32,
+    /// Scanned folder within `FolderTree`
+    folder: FolderId,
 }
 
 impl Ord for FolderEntry {
@@ -47,17 +83,63 @@ impl PartialOrd for FolderEntry {
 }
 
 impl FolderEntry {
+    pub fn new(kind: FolderEntryType, title: Name) -> Self {
+        FolderEntry {
+            title,
+            kind,
+            size: UNKNOWN,
+            modified: UNKNOWN,
+            uid: NO_OWNER,
+            gid: NO_OWNER,
+            folder: FolderId::NONE,
+        }
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        (self.size != UNKNOWN).then_some(self.size)
+    }
+
+    pub fn set_size(&mut self, size: Option<u64>) {
+        self.size = size.unwrap_or(UNKNOWN);
+    }
+
+    pub fn owner(&self) -> Option<Owner> {
+        (self.uid != NO_OWNER).then_some(Owner {
+            uid: self.uid,
+            gid: self.gid,
+        })
+    }
+
+    pub fn set_owner(&mut self, owner: Option<Owner>) {
+        (self.uid, self.gid) = owner.map_or((NO_OWNER, NO_OWNER), |owner| (owner.uid, owner.gid));
+    }
+
+    /// Modification time, to the second
+    pub fn modified(&self) -> Option<SystemTime> {
+        (self.modified != UNKNOWN).then(|| UNIX_EPOCH + Duration::from_secs(self.modified))
+    }
+
+    pub fn set_modified(&mut self, modified: Option<SystemTime>) {
+        self.modified = modified
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(UNKNOWN, |since_epoch| since_epoch.as_secs());
+    }
+
+    /// Set for scanned folders only
+    pub fn folder(&self) -> Option<FolderId> {
+        (self.folder != FolderId::NONE).then_some(self.folder)
+    }
+
+    pub fn set_folder(&mut self, folder: Option<FolderId>) {
+        self.folder = folder.unwrap_or(FolderId::NONE);
+    }
+
     pub fn sort_by_size(entries: &mut Vec<FolderEntry>) {
-        entries.sort_by(|a, b| {
-            if let (Some(size_a), Some(size_b)) = (a.size, b.size) {
-                size_a.cmp(&size_b)
-            } else if a.size.is_some() {
-                Ordering::Less
-            } else if b.size.is_some() {
-                Ordering::Greater
-            } else {
-                Ordering::Equal
-            }
+        entries.sort_by(|a, b| match (a.size(), b.size()) {
+            (Some(size_a), Some(size_b)) => size_a.cmp(&size_b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
         });
     }
 }
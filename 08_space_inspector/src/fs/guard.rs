@@ -0,0 +1,83 @@
+use crate::fs::storage::Storage;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_PROTECTED_PATHS: [&str; 1] = ["/"];
+/// System directories whose whole contents are protected, not just the directory itself
+const SYSTEM_PATHS: [&str; 12] = [
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/lib64", "/proc", "/sbin", "/sys", "/usr", "/System",
+    "/Library",
+];
+const VCS_MARKERS: [&str; 3] = [".git", ".hg", ".svn"];
+const GB: u64 = 1024 * 1024 * 1024;
+
+/// Decides whether a path may be removed
+#[derive(Debug)]
+pub struct DeleteGuard {
+    pub protected_paths: Vec<PathBuf>,
+    /// Protected together with everything below them
+    pub system_paths: Vec<PathBuf>,
+    pub read_only: bool,
+    /// Permanent deletions of this size or larger require typing the entry name
+    pub confirm_size_threshold: u64,
+}
+
+impl Default for DeleteGuard {
+    fn default() -> Self {
+        DeleteGuard {
+            protected_paths: DEFAULT_PROTECTED_PATHS.iter().map(PathBuf::from).collect(),
+            system_paths: SYSTEM_PATHS.iter().map(PathBuf::from).collect(),
+            read_only: false,
+            confirm_size_threshold: GB,
+        }
+    }
+}
+
+impl DeleteGuard {
+    /// Returns why `path` must not be deleted, if it is protected.
+    /// A path is protected when it is (or contains) a protected path,
+    /// lies inside a system path, is the start directory, or is a version-control root.
+    pub fn protection_reason(
+        &self,
+        storage: &dyn Storage,
+        path: &Path,
+        start_path: &Path,
+    ) -> Option<String> {
+        if let Some(protected) = self
+            .protected_paths
+            .iter()
+            .find(|protected| protected.starts_with(path))
+        {
+            return Some(format!("{} is protected", protected.to_string_lossy()));
+        }
+
+        if let Some(system) = self
+            .system_paths
+            .iter()
+            .find(|system| system.starts_with(path) || path.starts_with(system))
+        {
+            return Some(format!(
+                "{} and its contents are protected",
+                system.to_string_lossy()
+            ));
+        }
+
+        if start_path.starts_with(path) {
+            return Some(String::from(
+                "Start directory and its parents are protected",
+            ));
+        }
+
+        if let Some(marker) = VCS_MARKERS
+            .iter()
+            .find(|marker| storage.exists(&path.join(marker)))
+        {
+            return Some(format!("Repository root ({}) is protected", marker));
+        }
+
+        None
+    }
+
+    pub fn requires_typed_confirmation(&self, size: u64, move_to_trash: bool) -> bool {
+        !move_to_trash && size >= self.confirm_size_threshold
+    }
+}
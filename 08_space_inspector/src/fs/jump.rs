@@ -0,0 +1,55 @@
+use crate::fs::folder_entry::FolderEntryType;
+use crate::fs::tree::FolderTree;
+use std::path::{Component, Path, PathBuf};
+
+/// Turns typed path into an absolute one, resolving `.` and `..` without touching the disk
+pub fn resolve_path(current: &Path, input: &str) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in current.join(input.trim()).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Completes the last component of `input` with names of scanned subfolders.
+/// Returns completed input and the matching names when there is more than one.
+pub fn complete_path(tree: &FolderTree, current: &Path, input: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    };
+    let Some(folder) = tree.folder(&resolve_path(current, dir)) else {
+        return (input.to_string(), vec![]);
+    };
+
+    let mut matches: Vec<String> = folder
+        .entries
+        .iter()
+        .filter(|entry| entry.kind == FolderEntryType::Folder && entry.title.starts_with(prefix))
+        .map(|entry| entry.title.to_string())
+        .collect();
+    matches.sort();
+
+    match matches.as_slice() {
+        [] => (input.to_string(), vec![]),
+        [single] => (format!("{}{}/", dir, single), vec![]),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, name| {
+                let length = common
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((index, c), _)| index + c.len_utf8());
+                &common[..length]
+            });
+            (format!("{}{}", dir, common), matches.clone())
+        }
+    }
+}
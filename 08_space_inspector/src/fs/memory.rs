@@ -0,0 +1,174 @@
+use crate::fs::filesystem::FileSystem;
+use crate::fs::folder_entry::{FolderEntry, FolderEntryType};
+use crate::fs::name::Name;
+use crate::fs::owner::Owner;
+use crate::fs::storage::Storage;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+pub const DEFAULT_OWNER: Owner = Owner {
+    uid: 1000,
+    gid: 1000,
+};
+
+#[derive(Debug, Clone)]
+enum Node {
+    Folder(Owner),
+    File(u64, Owner),
+}
+
+/// In-memory tree for running `App` without touching the disk.
+/// Clones share the same tree, so a test can keep a handle to inspect it.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    nodes: Rc<RefCell<BTreeMap<PathBuf, Node>>>,
+    filesystems: Rc<RefCell<Vec<FileSystem>>>,
+    modified: Rc<RefCell<BTreeMap<PathBuf, SystemTime>>>,
+    unreadable: Rc<RefCell<BTreeSet<PathBuf>>>,
+    home: Rc<RefCell<Option<PathBuf>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_folder(&self, path: &str) {
+        self.add_folder_owned(path, DEFAULT_OWNER);
+    }
+
+    pub fn add_folder_owned(&self, path: &str, owner: Owner) {
+        let path = PathBuf::from(path);
+        self.add_parents(&path);
+        self.nodes.borrow_mut().insert(path, Node::Folder(owner));
+    }
+
+    pub fn add_file(&self, path: &str, size: u64) {
+        self.add_file_owned(path, size, DEFAULT_OWNER);
+    }
+
+    pub fn add_file_owned(&self, path: &str, size: u64, owner: Owner) {
+        let path = PathBuf::from(path);
+        self.add_parents(&path);
+        self.nodes
+            .borrow_mut()
+            .insert(path, Node::File(size, owner));
+    }
+
+    pub fn set_modified(&self, path: &str, modified: SystemTime) {
+        self.modified
+            .borrow_mut()
+            .insert(PathBuf::from(path), modified);
+    }
+
+    /// Makes `read_dir` of the folder fail with permission denied,
+    /// a file is listed with unknown size like when its metadata can't be read
+    pub fn set_unreadable(&self, path: &str) {
+        self.unreadable.borrow_mut().insert(PathBuf::from(path));
+    }
+
+    pub fn set_home_dir(&self, path: &str) {
+        *self.home.borrow_mut() = Some(PathBuf::from(path));
+    }
+
+    pub fn add_filesystem(&self, filesystem: FileSystem) {
+        self.filesystems.borrow_mut().push(filesystem);
+    }
+
+    fn add_parents(&self, path: &Path) {
+        let mut nodes = self.nodes.borrow_mut();
+        for ancestor in path.ancestors().skip(1) {
+            nodes
+                .entry(ancestor.to_path_buf())
+                .or_insert(Node::Folder(DEFAULT_OWNER));
+        }
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let mut nodes = self.nodes.borrow_mut();
+        if nodes.remove(path).is_none() {
+            return Err(io::Error::from(io::ErrorKind::NotFound));
+        }
+        nodes.retain(|node_path, _| !node_path.starts_with(path));
+        Ok(())
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FolderEntry>> {
+        let nodes = self.nodes.borrow();
+        match nodes.get(path) {
+            Some(Node::Folder(_)) => {}
+            _ => return Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+        if self.unreadable.borrow().contains(path) {
+            return Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        }
+
+        let modified = self.modified.borrow();
+        // Descendants of `path` follow it in component order
+        let entries = nodes
+            .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+            .take_while(|(node_path, _)| node_path.starts_with(path))
+            .filter(|(node_path, _)| node_path.parent() == Some(path))
+            .map(|(node_path, node)| {
+                let unreadable = self.unreadable.borrow().contains(node_path);
+                let title = node_path
+                    .file_name()
+                    .map(|name| Name::from(name.to_string_lossy().as_ref()))
+                    .unwrap_or_else(|| Name::from(""));
+                let (kind, size, owner) = match node {
+                    Node::Folder(owner) => (FolderEntryType::Folder, None, owner),
+                    Node::File(size, owner) => {
+                        (FolderEntryType::File, (!unreadable).then_some(*size), owner)
+                    }
+                };
+                let mut entry = FolderEntry::new(kind, title);
+                entry.set_size(size);
+                entry.set_owner(Some(*owner));
+                entry.set_modified(modified.get(node_path).cloned());
+                entry
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.nodes.borrow().contains_key(path)
+    }
+
+    fn delete_folder(&self, path: &Path, _move_to_trash: bool) -> io::Result<()> {
+        self.remove(path)
+    }
+
+    fn delete_file(&self, path: &Path, _move_to_trash: bool) -> io::Result<()> {
+        self.remove(path)
+    }
+
+    fn filesystem_for_path(&self, path: &Path) -> Option<FileSystem> {
+        self.filesystems
+            .borrow()
+            .iter()
+            .filter(|fs| path.starts_with(&fs.mount_point))
+            .max_by_key(|fs| fs.mount_point.as_os_str().len())
+            .cloned()
+    }
+
+    fn mounted_filesystems(&self) -> Vec<FileSystem> {
+        self.filesystems.borrow().clone()
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        self.home.borrow().clone()
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        Ok(PathBuf::from("/"))
+    }
+}
@@ -1,56 +1,80 @@
-use crate::ui::{UIConfig, TEXT_UNKNOWN};
-use std::fs::{read_dir, remove_dir_all, remove_file};
-use std::path::PathBuf;
+use crate::ui::TEXT_UNKNOWN;
+use std::fs::{remove_dir_all, remove_file};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 use trash;
 
+mod cleanable;
+mod filesystem;
 mod folder;
 mod folder_entry;
+mod guard;
+mod jump;
+mod memory;
+mod name;
+mod owner;
+mod stats;
+mod storage;
+mod tree;
+pub use cleanable::{find_cleanables, Cleanable};
+pub use filesystem::{mount_for_path, percent_of, FileSystem};
 pub use folder::Folder;
-pub use folder_entry::{FolderEntry, FolderEntryType};
+pub use folder_entry::{FolderEntry, FolderEntryType, ItemCount};
+pub use guard::DeleteGuard;
+pub use jump::{complete_path, resolve_path};
+pub use memory::{MemoryStorage, DEFAULT_OWNER};
+pub use name::Name;
+pub use owner::{add_owner_usage, subtract_owner_usage, user_name, Owner, OwnerUsage};
+pub use stats::ScanStats;
+pub use storage::{LocalStorage, Storage};
+pub use tree::{FolderId, FolderTree, TreeRow};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum SortBy {
     Title,
     Size,
+    Files,
+    Folders,
 }
 
-pub fn path_to_folder(path: &PathBuf) -> Folder {
+/// Reads folder at `path` into `Folder`, recording time and result to `stats`.
+/// Unreadable folder is recorded as an error and comes back empty.
+pub fn path_to_folder(storage: &dyn Storage, path: &PathBuf, stats: &mut ScanStats) -> Folder {
     let folder_name = path
         .file_name()
         .and_then(|name| name.to_str())
         .unwrap_or(TEXT_UNKNOWN);
-    let mut folder = Folder::new(folder_name.to_string());
+    let mut folder = Folder::new(Name::from(folder_name));
 
-    let path = read_dir(path.clone()).expect("Failed to read directory");
-    for entry in path {
-        if let Ok(entry) = entry {
-            let file_name = entry.file_name();
-            if let Some(file_name) = file_name.to_str() {
-                let mut folder_entry = FolderEntry {
-                    kind: FolderEntryType::File,
-                    title: file_name.to_owned(),
-                    size: None,
-                };
-                if entry.path().is_dir() {
-                    folder_entry.kind = FolderEntryType::Folder;
-                    folder.entries.push(folder_entry);
-                } else {
-                    let metadata = entry.metadata().expect("Failed to get metadata");
-                    let size = metadata.len();
-
-                    folder_entry.size = Some(size);
-                    folder.entries.push(folder_entry);
-                }
-            }
+    let started = Instant::now();
+    let entries = match storage.read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            stats.record_error(path, &err);
+            return folder;
+        }
+    };
+    stats.record_folder(path, started.elapsed(), &entries);
+    // Entries stay for the whole session, don't keep spare capacity around
+    folder.entries.reserve_exact(entries.len());
+    for folder_entry in entries {
+        if let (Some(size), Some(owner)) = (folder_entry.size(), folder_entry.owner()) {
+            *folder.owners.entry(owner.uid).or_insert(0) += size;
+        }
+        folder.touch(folder_entry.modified());
+        match folder_entry.kind {
+            FolderEntryType::Folder => folder.count.folders += 1,
+            _ => folder.count.files += 1,
         }
+        folder.entries.push(folder_entry);
     }
     folder.sort_by_title();
 
     folder
 }
 
-pub fn delete_folder(path: &PathBuf, config: &UIConfig) -> std::io::Result<()> {
-    if config.move_to_trash {
+pub fn delete_folder(path: &Path, move_to_trash: bool) -> std::io::Result<()> {
+    if move_to_trash {
         match trash::delete(path) {
             Ok(_) => Ok(()),
             Err(err) => Err(std::io::Error::new(std::io::ErrorKind::Other, err)),
@@ -61,8 +85,8 @@ pub fn delete_folder(path: &PathBuf, config: &UIConfig) -> std::io::Result<()> {
     }
 }
 
-pub fn delete_file(path: &PathBuf, config: &UIConfig) -> std::io::Result<()> {
-    if config.move_to_trash {
+pub fn delete_file(path: &Path, move_to_trash: bool) -> std::io::Result<()> {
+    if move_to_trash {
         match trash::delete(path) {
             Ok(_) => Ok(()),
             Err(err) => Err(std::io::Error::new(std::io::ErrorKind::Other, err)),
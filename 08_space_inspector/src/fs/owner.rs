@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::fs::Metadata;
+use std::sync::{Mutex, OnceLock};
+
+/// Usage in bytes per uid
+pub type OwnerUsage = HashMap<u32, u64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Owner {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl Owner {
+    #[cfg(unix)]
+    pub fn from_metadata(metadata: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        Some(Owner {
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+        })
+    }
+
+    /// Files have no uid outside unix, so their owner stays unknown
+    #[cfg(not(unix))]
+    pub fn from_metadata(_metadata: &Metadata) -> Option<Self> {
+        None
+    }
+}
+
+pub fn add_owner_usage(target: &mut OwnerUsage, source: &OwnerUsage) {
+    for (uid, size) in source {
+        *target.entry(*uid).or_insert(0) += size;
+    }
+}
+
+pub fn subtract_owner_usage(target: &mut OwnerUsage, source: &OwnerUsage) {
+    for (uid, size) in source {
+        if let Some(usage) = target.get_mut(uid) {
+            *usage = usage.saturating_sub(*size);
+            if *usage == 0 {
+                target.remove(uid);
+            }
+        }
+    }
+}
+
+fn user_names() -> &'static Mutex<HashMap<u32, String>> {
+    static USER_NAMES: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    USER_NAMES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Resolves uid to login name, falls back to the numeric uid
+pub fn user_name(uid: u32) -> String {
+    let mut names = user_names().lock().unwrap();
+    names
+        .entry(uid)
+        .or_insert_with(|| lookup_user_name(uid).unwrap_or_else(|| uid.to_string()))
+        .clone()
+}
+
+#[cfg(unix)]
+fn lookup_user_name(uid: u32) -> Option<String> {
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let mut buf = vec![0 as libc::c_char; 4096];
+
+    let code =
+        unsafe { libc::getpwuid_r(uid, &mut passwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+
+    if code != 0 || result.is_null() {
+        return None;
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn lookup_user_name(_uid: u32) -> Option<String> {
+    None
+}
@@ -0,0 +1,126 @@
+use crate::fs::folder_entry::{FolderEntry, FolderEntryType};
+use crate::ui::format_file_size;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Folders kept in the slowest-to-enumerate list
+const SLOWEST_LIMIT: usize = 10;
+/// Errors kept with their message, the rest is only counted
+const ERROR_SAMPLES_LIMIT: usize = 20;
+const TEXT_NO_METADATA: &str = "Size unknown, metadata couldn't be read";
+
+/// What scanning has done so far, accumulated over all scans of the session
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ScanStats {
+    pub folders: u64,
+    pub files: u64,
+    pub bytes: u64,
+    pub errors: u64,
+    pub error_samples: Vec<(PathBuf, String)>,
+    /// Wall time spent scanning, including aggregation
+    pub elapsed: Duration,
+    /// Folders with the longest `read_dir`, slowest first
+    pub slowest: Vec<(PathBuf, Duration)>,
+}
+
+impl ScanStats {
+    pub fn record_folder(&mut self, path: &Path, duration: Duration, entries: &[FolderEntry]) {
+        self.folders += 1;
+        for entry in entries {
+            if entry.kind == FolderEntryType::File {
+                self.files += 1;
+                match entry.size() {
+                    Some(size) => self.bytes += size,
+                    None => self.add_error(&path.join(&entry.title), TEXT_NO_METADATA),
+                }
+            }
+        }
+
+        let index = self
+            .slowest
+            .iter()
+            .position(|(_, slow)| duration > *slow)
+            .unwrap_or(self.slowest.len());
+        if index < SLOWEST_LIMIT {
+            self.slowest.insert(index, (path.to_path_buf(), duration));
+            self.slowest.truncate(SLOWEST_LIMIT);
+        }
+    }
+
+    pub fn record_error(&mut self, path: &Path, err: &io::Error) {
+        self.add_error(path, &err.to_string());
+    }
+
+    fn add_error(&mut self, path: &Path, message: &str) {
+        self.errors += 1;
+        if self.error_samples.len() < ERROR_SAMPLES_LIMIT {
+            self.error_samples
+                .push((path.to_path_buf(), message.to_string()));
+        }
+    }
+
+    /// Folders and files enumerated per second
+    pub fn entries_per_second(&self) -> f64 {
+        per_second((self.folders + self.files) as f64, self.elapsed)
+    }
+
+    pub fn bytes_per_second(&self) -> f64 {
+        per_second(self.bytes as f64, self.elapsed)
+    }
+
+    /// Human readable summary shared by the stats popup and `--report`
+    pub fn report_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Folders: {} | Files: {} | Size: {} | Errors: {}",
+                self.folders,
+                self.files,
+                format_file_size(self.bytes),
+                self.errors
+            ),
+            format!(
+                "Elapsed: {:.3} s | {:.0} entries/s | {}/s",
+                self.elapsed.as_secs_f64(),
+                self.entries_per_second(),
+                format_file_size(self.bytes_per_second() as u64)
+            ),
+        ];
+
+        if !self.slowest.is_empty() {
+            lines.push(String::new());
+            lines.push(String::from("Slowest folders to enumerate:"));
+            for (path, duration) in &self.slowest {
+                lines.push(format!(
+                    "{:>10.3} ms  {}",
+                    duration.as_secs_f64() * 1000.0,
+                    path.to_string_lossy()
+                ));
+            }
+        }
+
+        if !self.error_samples.is_empty() {
+            lines.push(String::new());
+            lines.push(String::from("Errors:"));
+            for (path, message) in &self.error_samples {
+                lines.push(format!("{}: {}", path.to_string_lossy(), message));
+            }
+            if self.errors > self.error_samples.len() as u64 {
+                lines.push(format!(
+                    "... and {} more",
+                    self.errors - self.error_samples.len() as u64
+                ));
+            }
+        }
+
+        lines
+    }
+}
+
+fn per_second(amount: f64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        amount / elapsed.as_secs_f64()
+    }
+}
@@ -0,0 +1,87 @@
+use crate::fs::filesystem::{self, FileSystem};
+use crate::fs::folder_entry::{FolderEntry, FolderEntryType};
+use crate::fs::name::Name;
+use crate::fs::owner::Owner;
+use crate::fs::{delete_file, delete_folder};
+use std::env;
+use std::fmt::Debug;
+use std::fs::read_dir;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Everything `App` needs from the filesystem.
+/// Folder entries have `size` set for files only, unless their metadata couldn't be read.
+pub trait Storage: Debug {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FolderEntry>>;
+    fn exists(&self, path: &Path) -> bool;
+    fn delete_folder(&self, path: &Path, move_to_trash: bool) -> io::Result<()>;
+    fn delete_file(&self, path: &Path, move_to_trash: bool) -> io::Result<()>;
+    fn filesystem_for_path(&self, path: &Path) -> Option<FileSystem>;
+    fn mounted_filesystems(&self) -> Vec<FileSystem>;
+    /// Base for relative paths given on the command line
+    fn current_dir(&self) -> io::Result<PathBuf>;
+    /// Home directory of the user owning the storage, protected from deletion
+    fn home_dir(&self) -> Option<PathBuf>;
+    /// Tells that every folder under `path` is about to be read,
+    /// so remote storage can fetch them in one request instead of one per folder
+    fn prefetch(&self, _path: &Path) {}
+}
+
+/// Local disk
+#[derive(Debug, Default)]
+pub struct LocalStorage;
+
+impl Storage for LocalStorage {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FolderEntry>> {
+        let mut entries = vec![];
+
+        for entry in read_dir(path)?.flatten() {
+            let file_name = entry.file_name();
+            if let Some(file_name) = file_name.to_str() {
+                let metadata = entry.metadata();
+                let mut folder_entry =
+                    FolderEntry::new(FolderEntryType::File, Name::from(file_name));
+                folder_entry.set_owner(metadata.as_ref().ok().and_then(Owner::from_metadata));
+                folder_entry.set_modified(metadata.as_ref().ok().and_then(|m| m.modified().ok()));
+                // Symlinked folders are listed as links, following them could loop forever
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    folder_entry.kind = FolderEntryType::Folder;
+                } else {
+                    // A file gone or locked meanwhile keeps an unknown size, not failing its siblings
+                    folder_entry.set_size(metadata.as_ref().ok().map(|m| m.len()));
+                }
+                entries.push(folder_entry);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn delete_folder(&self, path: &Path, move_to_trash: bool) -> io::Result<()> {
+        delete_folder(path, move_to_trash)
+    }
+
+    fn delete_file(&self, path: &Path, move_to_trash: bool) -> io::Result<()> {
+        delete_file(path, move_to_trash)
+    }
+
+    fn filesystem_for_path(&self, path: &Path) -> Option<FileSystem> {
+        filesystem::filesystem_for_path(path)
+    }
+
+    fn mounted_filesystems(&self) -> Vec<FileSystem> {
+        filesystem::mounted_filesystems()
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        env::var_os("HOME").map(PathBuf::from)
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        env::current_dir()
+    }
+}
@@ -0,0 +1,310 @@
+use crate::fs::folder::Folder;
+use crate::fs::folder_entry::{FolderEntry, FolderEntryType, ItemCount};
+use crate::fs::name::Name;
+use crate::fs::SortBy;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Index of a folder within `FolderTree`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FolderId(u32);
+
+impl FolderId {
+    /// Stands for no folder in `FolderEntry`
+    pub(crate) const NONE: FolderId = FolderId(u32::MAX);
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Entry of the tree view, with subfolders of expanded folders inlined below it
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeRow {
+    pub depth: usize,
+    pub title: Name,
+    pub kind: FolderEntryType,
+    pub size: Option<u64>,
+    pub folder: Option<FolderId>,
+    pub expanded: bool,
+}
+
+/// Scanned folders stored in one arena. Folders point to their parent and
+/// folder entries to the scanned subfolder, so paths are only kept for roots.
+#[derive(Debug, Default)]
+pub struct FolderTree {
+    folders: Vec<Option<Folder>>,
+    /// Slots of removed folders, reused by `insert`
+    free: Vec<FolderId>,
+    /// Scanned folders whose parent was not scanned
+    roots: Vec<(PathBuf, FolderId)>,
+    /// Scanned subfolders by title, for folders having any
+    children: HashMap<FolderId, HashMap<Name, FolderId>>,
+    names: HashSet<Name>,
+}
+
+impl FolderTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns shared copy of `name`, allocating it on first use only
+    pub fn intern(&mut self, name: &str) -> Name {
+        match self.names.get(name) {
+            Some(interned) => interned.clone(),
+            None => {
+                let interned = Name::from(name);
+                self.names.insert(interned.clone());
+                interned
+            }
+        }
+    }
+
+    #[cfg(test)]
+    pub fn is_interned(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Drops the interned copy of `name` once no folder or entry uses it
+    pub fn release(&mut self, name: Name) {
+        // The interned copy and `name` itself
+        if name.references() == 2 {
+            self.names.remove(&*name);
+        }
+    }
+
+    pub fn get(&self, id: FolderId) -> Option<&Folder> {
+        self.folders.get(id.index()).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, id: FolderId) -> Option<&mut Folder> {
+        self.folders.get_mut(id.index()).and_then(Option::as_mut)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (FolderId, &Folder)> {
+        self.folders
+            .iter()
+            .enumerate()
+            .filter_map(|(index, folder)| Some((FolderId(index as u32), folder.as_ref()?)))
+    }
+
+    /// Finds scanned folder by absolute path, walking down from the root containing it
+    pub fn find(&self, path: &Path) -> Option<FolderId> {
+        self.roots
+            .iter()
+            .filter(|(root_path, _)| path.starts_with(root_path))
+            .find_map(|(root_path, root)| {
+                let relative = path.strip_prefix(root_path).ok()?;
+                relative.iter().try_fold(*root, |id, title| {
+                    self.children.get(&id)?.get(title.to_str()?).copied()
+                })
+            })
+    }
+
+    /// Recursive count of the scanned folder an entry points to
+    pub fn count_of(&self, entry: &FolderEntry) -> Option<ItemCount> {
+        entry
+            .folder()
+            .and_then(|id| self.get(id))
+            .map(|folder| folder.count)
+    }
+
+    /// Whether `entry` is shown under `owner_filter`: files by their owner,
+    /// scanned folders by the usage of that owner anywhere below them
+    pub fn is_owned_by(&self, entry: &FolderEntry, owner_filter: Option<u32>) -> bool {
+        let Some(uid) = owner_filter else {
+            return true;
+        };
+        match (&entry.kind, entry.folder().and_then(|id| self.get(id))) {
+            (FolderEntryType::Parent, _) => true,
+            (FolderEntryType::Folder, Some(folder)) => folder.owners.contains_key(&uid),
+            _ => entry.owner().is_some_and(|owner| owner.uid == uid),
+        }
+    }
+
+    /// Sorts entries of folder `id`, counts of subfolders come from the tree
+    pub fn sort(&mut self, id: FolderId, sort_by: &SortBy) {
+        self.with_taken(id, |folder, tree| {
+            let count = |entry: &FolderEntry| tree.count_of(entry).unwrap_or_default();
+            match sort_by {
+                SortBy::Files => folder.sort_by_count(|entry| count(entry).files),
+                SortBy::Folders => folder.sort_by_count(|entry| count(entry).folders),
+                SortBy::Size => folder.sort_by_size(),
+                SortBy::Title => folder.sort_by_title(),
+            }
+        });
+    }
+
+    pub fn cursor_up(&mut self, id: FolderId, owner_filter: Option<u32>) {
+        self.with_taken(id, |folder, tree| {
+            folder.cursor_up(&|entry| tree.is_owned_by(entry, owner_filter))
+        });
+    }
+
+    pub fn cursor_down(&mut self, id: FolderId, owner_filter: Option<u32>) {
+        self.with_taken(id, |folder, tree| {
+            folder.cursor_down(&|entry| tree.is_owned_by(entry, owner_filter))
+        });
+    }
+
+    /// Moves cursor of folder `id` back to its parent entry if `owner_filter` hides the selected one
+    pub fn snap_cursor(&mut self, id: FolderId, owner_filter: Option<u32>) {
+        self.with_taken(id, |folder, tree| {
+            folder.snap_cursor(&|entry| tree.is_owned_by(entry, owner_filter))
+        });
+    }
+
+    /// Runs `f` on folder `id` taken out of its slot, so it can read the rest of the tree
+    fn with_taken(&mut self, id: FolderId, f: impl FnOnce(&mut Folder, &Self)) {
+        // Its subfolders are still in place meanwhile
+        let Some(mut folder) = self.folders.get_mut(id.index()).and_then(Option::take) else {
+            return;
+        };
+        f(&mut folder, self);
+        self.folders[id.index()] = Some(folder);
+    }
+
+    /// Scanned root whose subtree contains `path`
+    pub fn root_of(&self, path: &Path) -> Option<FolderId> {
+        self.roots
+            .iter()
+            .filter(|(root_path, _)| path.starts_with(root_path))
+            .max_by_key(|(root_path, _)| root_path.as_os_str().len())
+            .map(|(_, root)| *root)
+    }
+
+    pub fn folder(&self, path: &Path) -> Option<&Folder> {
+        self.find(path).and_then(|id| self.get(id))
+    }
+
+    /// Absolute path of a stored folder
+    pub fn path(&self, id: FolderId) -> PathBuf {
+        let mut titles = vec![];
+        let mut current = id;
+        loop {
+            if let Some((root_path, _)) = self.roots.iter().find(|(_, root)| *root == current) {
+                let mut path = root_path.clone();
+                path.extend(titles.iter().rev());
+                return path;
+            }
+            match self.get(current) {
+                Some(folder) => {
+                    titles.push(folder.title.clone());
+                    match folder.parent {
+                        Some(parent) => current = parent,
+                        None => return titles.iter().rev().collect(),
+                    }
+                }
+                None => return titles.iter().rev().collect(),
+            }
+        }
+    }
+
+    /// Stores `folder`, adopting subfolders its entries point to
+    pub fn insert(&mut self, folder: Folder) -> FolderId {
+        let children: HashMap<Name, FolderId> = folder
+            .entries
+            .iter()
+            .filter_map(|entry| Some((entry.title.clone(), entry.folder()?)))
+            .collect();
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.folders[id.index()] = Some(folder);
+                id
+            }
+            None => {
+                self.folders.push(Some(folder));
+                FolderId(self.folders.len() as u32 - 1)
+            }
+        };
+
+        for child in children.values() {
+            if let Some(child_folder) = self.get_mut(*child) {
+                child_folder.parent = Some(id);
+            }
+            self.roots.retain(|(_, root)| root != child);
+        }
+        if !children.is_empty() {
+            self.children.insert(id, children);
+        }
+
+        id
+    }
+
+    /// Marks folder scanned without its parent as reachable from `path`
+    pub fn add_root(&mut self, path: &Path, id: FolderId) {
+        let is_detached = self.get(id).is_some_and(|folder| folder.parent.is_none());
+        if is_detached && !self.roots.iter().any(|(_, root)| *root == id) {
+            self.roots.push((path.to_path_buf(), id));
+        }
+    }
+
+    /// Drops folder and all scanned folders below it.
+    /// Entry pointing to it from the parent is left to the caller.
+    pub fn remove(&mut self, id: FolderId) {
+        if let Some(folder) = self.get(id) {
+            let (parent, title) = (folder.parent, folder.title.clone());
+            if let Some(siblings) = parent.and_then(|parent| self.children.get_mut(&parent)) {
+                siblings.remove(&title);
+            }
+        }
+
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            if let Some(folder) = self.folders.get_mut(current.index()).and_then(Option::take) {
+                pending.extend(folder.entries.iter().filter_map(FolderEntry::folder));
+                self.children.remove(&current);
+                self.free.push(current);
+
+                self.release(folder.title);
+                for entry in folder.entries {
+                    self.release(entry.title);
+                }
+            }
+        }
+        self.roots.retain(|(_, root)| *root != id);
+    }
+
+    /// Entries of folder `id` in their current order, descending into `expanded` folders
+    pub fn rows(
+        &self,
+        id: FolderId,
+        expanded: &HashSet<FolderId>,
+        owner_filter: Option<u32>,
+    ) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        self.push_rows(&mut rows, id, 0, expanded, owner_filter);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        rows: &mut Vec<TreeRow>,
+        id: FolderId,
+        depth: usize,
+        expanded: &HashSet<FolderId>,
+        owner_filter: Option<u32>,
+    ) {
+        let Some(folder) = self.get(id) else {
+            return;
+        };
+        for entry in folder.entries.iter().filter(|entry| {
+            entry.kind != FolderEntryType::Parent && self.is_owned_by(entry, owner_filter)
+        }) {
+            let is_expanded = entry
+                .folder()
+                .is_some_and(|child| expanded.contains(&child));
+            rows.push(TreeRow {
+                depth,
+                title: entry.title.clone(),
+                kind: entry.kind.clone(),
+                size: entry.size(),
+                folder: entry.folder(),
+                expanded: is_expanded,
+            });
+            if let (true, Some(child)) = (is_expanded, entry.folder()) {
+                self.push_rows(rows, child, depth + 1, expanded, owner_filter);
+            }
+        }
+    }
+}
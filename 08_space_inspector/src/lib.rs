@@ -1,22 +1,61 @@
 use opener;
+use std::collections::HashSet;
 use std::io;
-use std::{collections::HashMap, path::PathBuf};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
 use ratatui::prelude::*;
 use std::env;
 
+mod bookmarks;
+pub mod config;
+pub mod events;
+mod export;
 mod fs;
+pub mod remote;
 mod ui;
 
-use fs::{delete_file, delete_folder, path_to_folder, Folder, FolderEntryType, SortBy};
-use ui::UIConfig;
+use bookmarks::{default_bookmarks_file, Bookmarks};
+use config::Config;
+use events::{EventSource, TerminalEvents};
+use fs::{
+    add_owner_usage, complete_path, find_cleanables, path_to_folder, resolve_path,
+    subtract_owner_usage, Cleanable, DeleteGuard, FolderId, FolderTree, ItemCount, Name,
+    OwnerUsage, SortBy, TreeRow,
+};
+pub use fs::{
+    mount_for_path, FileSystem, Folder, FolderEntry, FolderEntryType, LocalStorage, MemoryStorage,
+    Owner, ScanStats, Storage, DEFAULT_OWNER,
+};
+use ui::{
+    builtin_themes, default_theme_file, format_file_size, load_theme_file, UIConfig, View,
+    TEXT_CONFIRM_PURGE, THEME_MONOCHROME,
+};
 
 #[derive(Debug)]
 pub struct App {
+    start_path: PathBuf,
     current_path: PathBuf,
-    file_tree_map: HashMap<String, Folder>,
+    tree: FolderTree,
     ui_config: UIConfig,
+    filesystem: Option<FileSystem>,
+    filesystems: Vec<FileSystem>,
+    filesystems_cursor: usize,
+    delete_guard: DeleteGuard,
+    dry_run_log: Vec<String>,
+    storage: Box<dyn Storage>,
+    cleanables: Vec<Cleanable>,
+    cleanables_cursor: usize,
+    /// Where HTML reports are written
+    export_dir: PathBuf,
+    scan_stats: ScanStats,
+    bookmarks: Bookmarks,
+    bookmarks_cursor: usize,
+    /// Folders expanded inline in the tree view
+    tree_view_expanded: HashSet<FolderId>,
+    tree_view_cursor: usize,
 }
 
 enum DiffKind {
@@ -25,8 +64,18 @@ enum DiffKind {
 
 impl App {
     pub fn new() -> Self {
+        Self::with_storage(Box::new(LocalStorage))
+    }
+
+    /// App reading folders from `storage` instead of the local disk
+    pub fn with_storage(storage: Box<dyn Storage>) -> Self {
+        // Home of the storage side, not of the machine showing the UI
+        let mut delete_guard = DeleteGuard::default();
+        delete_guard.protected_paths.extend(storage.home_dir());
+
         App {
-            file_tree_map: HashMap::new(),
+            tree: FolderTree::new(),
+            start_path: PathBuf::from("."),
             current_path: PathBuf::from("."),
             ui_config: UIConfig {
                 colored: false,
@@ -34,31 +83,153 @@ impl App {
                 sort_by: SortBy::Title,
                 move_to_trash: true,
                 open_file: true,
+                show_owner: false,
+                show_parent_percent: false,
+                show_root_percent: false,
+                show_files: false,
+                show_folders: false,
+                owner_filter: None,
+                view: View::Folder,
+                message: None,
+                typed_confirmation: None,
+                jump_input: None,
+                breadcrumb: vec![],
+                themes: builtin_themes(),
+                theme_index: 0,
             },
+            filesystem: None,
+            filesystems: vec![],
+            filesystems_cursor: 0,
+            delete_guard,
+            dry_run_log: vec![],
+            storage,
+            cleanables: vec![],
+            cleanables_cursor: 0,
+            export_dir: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            scan_stats: ScanStats::default(),
+            bookmarks: Bookmarks::default(),
+            bookmarks_cursor: 0,
+            tree_view_expanded: HashSet::new(),
+            tree_view_cursor: 0,
         }
     }
 
-    pub fn init(&mut self, file_path: Option<String>) {
-        let current_path = match file_path {
-            Some(path) => {
-                let path_buf = PathBuf::from(&path);
-                if path_buf.is_absolute() {
-                    path_buf
-                } else {
-                    let current_dir = env::current_dir().unwrap();
-                    let abs_path = current_dir.join(&path_buf);
-                    abs_path
+    /// Applies command line options, call before `init`
+    pub fn configure(&mut self, config: &Config) {
+        self.delete_guard.read_only = config.read_only;
+        // Relative to the storage, which may be on another host
+        let base = self
+            .storage
+            .current_dir()
+            .unwrap_or_else(|_| PathBuf::from("/"));
+        self.delete_guard
+            .protected_paths
+            .extend(config.protected_paths.iter().map(|path| base.join(path)));
+        if let Some(threshold) = config.confirm_size_threshold {
+            self.delete_guard.confirm_size_threshold = threshold;
+        }
+        // Remote files can't be opened by local applications
+        self.ui_config.open_file = config.remote.is_none();
+        if let Some(export_dir) = &config.export_dir {
+            self.export_dir = to_absolute(export_dir.clone());
+        }
+        self.configure_theme(config);
+        self.configure_bookmarks(config);
+    }
+
+    fn configure_bookmarks(&mut self, config: &Config) {
+        let file = config
+            .bookmarks_file
+            .clone()
+            .or_else(default_bookmarks_file);
+        if let Some(file) = file {
+            match Bookmarks::load(file) {
+                Ok(bookmarks) => self.bookmarks = bookmarks,
+                Err(err) => self.ui_config.message = Some(err),
+            }
+        }
+    }
+
+    /// Loads theme file and picks the starting theme:
+    /// `--theme`, then monochrome for `NO_COLOR`, then the theme file default
+    fn configure_theme(&mut self, config: &Config) {
+        let theme_file = config
+            .theme_file
+            .clone()
+            .or_else(|| default_theme_file().filter(|path| path.exists()));
+        let mut file_theme = None;
+
+        if let Some(path) = theme_file {
+            match load_theme_file(&path) {
+                Ok(file) => {
+                    self.ui_config.add_themes(file.themes);
+                    file_theme = file.default_theme;
                 }
+                Err(err) => self.ui_config.message = Some(err),
             }
-            None => env::current_dir().unwrap(),
+        }
+
+        let theme = match (&config.theme, config.no_color) {
+            (Some(theme), _) => Some(theme.clone()),
+            (None, true) => Some(String::from(THEME_MONOCHROME)),
+            (None, false) => file_theme,
         };
+        if let Some(theme) = theme {
+            if !self.ui_config.select_theme(&theme) {
+                self.ui_config.message = Some(format!("Unknown theme: {}", theme));
+            }
+        }
+    }
 
+    pub fn current_path(&self) -> &PathBuf {
+        &self.current_path
+    }
+
+    pub fn current_folder(&self) -> Option<&Folder> {
+        self.get_current_folder()
+    }
+
+    /// Size "percent of root" is relative to: the start folder while inside it,
+    /// otherwise the scanned root the current folder was reached from
+    fn root_size(&self) -> u64 {
+        let root = if self.current_path.starts_with(&self.start_path) {
+            self.tree.find(&self.start_path)
+        } else {
+            self.tree.root_of(&self.current_path)
+        };
+        root.and_then(|id| self.tree.get(id))
+            .map(|folder| folder.get_size())
+            .unwrap_or(0)
+    }
+
+    /// Totals of every scan done so far
+    pub fn scan_stats(&self) -> &ScanStats {
+        &self.scan_stats
+    }
+
+    /// Removals skipped because of read-only mode
+    pub fn dry_run_log(&self) -> &[String] {
+        &self.dry_run_log
+    }
+
+    pub fn init(&mut self, file_path: Option<String>) {
+        let current_dir = self
+            .storage
+            .current_dir()
+            .expect("Failed to get current directory");
+        let current_path = match file_path {
+            Some(path) => current_dir.join(path),
+            None => current_dir,
+        };
+
+        self.start_path = current_path.clone();
         self.current_path = current_path;
-        self.process_filepath(&self.current_path.clone());
+        self.scan(&self.current_path.clone());
+        self.refresh_filesystem();
     }
 
-    fn get_current_path_string(&self) -> String {
-        self.current_path.to_string_lossy().to_string()
+    fn refresh_filesystem(&mut self) {
+        self.filesystem = self.storage.filesystem_for_path(&self.current_path);
     }
 
     fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
@@ -66,34 +237,651 @@ impl App {
         Ok(())
     }
 
-    pub fn run(&mut self, mut terminal: Terminal<impl Backend>) -> io::Result<()> {
+    pub fn run(&mut self, terminal: Terminal<impl Backend>) -> io::Result<()> {
+        self.run_with_events(terminal, &mut TerminalEvents)
+    }
+
+    /// Runs until quit key is pressed or `events` are exhausted
+    pub fn run_with_events(
+        &mut self,
+        mut terminal: Terminal<impl Backend>,
+        events: &mut impl EventSource,
+    ) -> io::Result<()> {
         loop {
             self.draw(&mut terminal)?;
 
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    use KeyCode::*;
+            match events.next_event()? {
+                Some(event) => {
+                    if self.handle_event(event) {
+                        return Ok(());
+                    }
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Applies a single input event, returns `true` when the app should exit
+    pub fn handle_event(&mut self, event: Event) -> bool {
+        if let Event::Mouse(mouse) = event {
+            self.on_mouse(mouse);
+        }
+        if let Event::Key(key) = event {
+            if key.kind == KeyEventKind::Press {
+                use KeyCode::*;
+                self.ui_config.message = None;
+
+                if self.ui_config.typed_confirmation.is_some() {
                     match key.code {
-                        Char('q') | Esc => return Ok(()),
-                        Char('j') | Down => self.on_cursor_down(),
-                        Char('k') | Up => self.on_cursor_up(),
-                        Char('d') | Delete => self.on_delete(),
-                        Char('s') => self.on_toggle_sorting(),
-                        Char('c') => self.on_toggle_coloring(),
-                        Char('t') => self.on_toggle_move_to_trash(),
-                        Backspace => self.on_backspace(),
-                        Enter => self.on_enter(),
+                        Char(c) => self.on_confirmation_input(c),
+                        Backspace => self.on_confirmation_backspace(),
+                        Enter => self.on_confirmation_submit(),
+                        Esc => self.on_confirmation_cancel(),
                         _ => {}
                     }
+                    return false;
+                }
+
+                if self.ui_config.jump_input.is_some() {
+                    match key.code {
+                        Char(c) => self.on_jump_input(c),
+                        Backspace => self.on_jump_backspace(),
+                        Tab => self.on_jump_complete(),
+                        Enter => self.on_jump_submit(),
+                        Esc => self.ui_config.jump_input = None,
+                        _ => {}
+                    }
+                    return false;
+                }
+
+                match self.ui_config.view {
+                    View::Owners => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('O') | Backspace => self.on_toggle_owners_view(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Stats => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('i') | Backspace => self.on_toggle_stats_view(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Tree => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('j') | Down => self.on_tree_view_cursor_down(),
+                            Char('k') | Up => self.on_tree_view_cursor_up(),
+                            Char('l') | Right => self.on_tree_view_expand(),
+                            Char('h') | Left => self.on_tree_view_collapse(),
+                            Char(' ') | Enter => self.on_tree_view_toggle(),
+                            Char('v') | Backspace => self.on_toggle_tree_view(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Bookmarks => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('j') | Down => self.on_bookmarks_cursor_down(),
+                            Char('k') | Up => self.on_bookmarks_cursor_up(),
+                            Char('d') | Delete => self.on_remove_bookmark(),
+                            Char('B') | Backspace => self.on_toggle_bookmarks_view(),
+                            Enter => self.on_bookmarks_enter(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Filesystems => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('j') | Down => self.on_filesystems_cursor_down(),
+                            Char('k') | Up => self.on_filesystems_cursor_up(),
+                            Char('f') | Backspace => self.on_toggle_filesystems_view(),
+                            Enter => self.on_filesystems_enter(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Cleanable => {
+                        match key.code {
+                            Char('q') | Esc => return true,
+                            Char('j') | Down => self.on_cleanables_cursor_down(),
+                            Char('k') | Up => self.on_cleanables_cursor_up(),
+                            Char(' ') => self.on_toggle_cleanable_selection(),
+                            Char('a') => self.on_toggle_all_cleanables(),
+                            Char('d') | Delete => self.on_purge_cleanables(),
+                            Char('t') => self.on_toggle_move_to_trash(),
+                            Char('C') | Backspace => self.on_toggle_cleanable_view(),
+                            _ => {}
+                        }
+                        return false;
+                    }
+                    View::Folder => {}
+                }
+                match key.code {
+                    Char('q') | Esc => return true,
+                    Char('j') | Down => self.on_cursor_down(),
+                    Char('k') | Up => self.on_cursor_up(),
+                    Char('d') | Delete => self.on_delete(),
+                    Char('s') => self.on_toggle_sorting(),
+                    Char('n') => self.on_toggle_count_sorting(),
+                    Char('c') => self.on_toggle_coloring(),
+                    Char('t') => self.on_toggle_move_to_trash(),
+                    Char('o') => self.on_toggle_owner_column(),
+                    Char('P') => self.on_toggle_parent_percent_column(),
+                    Char('R') => self.on_toggle_root_percent_column(),
+                    Char('F') => self.on_toggle_files_column(),
+                    Char('D') => self.on_toggle_folders_column(),
+                    Char('u') => self.on_cycle_owner_filter(),
+                    Char('O') => self.on_toggle_owners_view(),
+                    Char('f') => self.on_toggle_filesystems_view(),
+                    Char('C') => self.on_toggle_cleanable_view(),
+                    Char('T') => self.on_cycle_theme(),
+                    Char('x') => self.on_export(),
+                    Char('i') => self.on_toggle_stats_view(),
+                    Char(':') => self.ui_config.jump_input = Some(String::new()),
+                    Char('b') => self.on_toggle_bookmark(),
+                    Char('B') => self.on_toggle_bookmarks_view(),
+                    Char('v') => self.on_toggle_tree_view(),
+                    Backspace => self.on_backspace(),
+                    Enter => self.on_enter(),
+                    _ => {}
                 }
             }
         }
+        false
     }
 
     fn on_toggle_move_to_trash(&mut self) {
         self.ui_config.move_to_trash = !self.ui_config.move_to_trash;
     }
 
+    fn on_toggle_owner_column(&mut self) {
+        self.ui_config.show_owner = !self.ui_config.show_owner;
+    }
+
+    fn on_toggle_parent_percent_column(&mut self) {
+        self.ui_config.show_parent_percent = !self.ui_config.show_parent_percent;
+    }
+
+    fn on_toggle_root_percent_column(&mut self) {
+        self.ui_config.show_root_percent = !self.ui_config.show_root_percent;
+    }
+
+    fn on_toggle_files_column(&mut self) {
+        self.ui_config.show_files = !self.ui_config.show_files;
+    }
+
+    fn on_toggle_folders_column(&mut self) {
+        self.ui_config.show_folders = !self.ui_config.show_folders;
+    }
+
+    fn on_toggle_owners_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Owners => View::Folder,
+            _ => View::Owners,
+        };
+    }
+
+    fn on_jump_input(&mut self, c: char) {
+        if let Some(input) = self.ui_config.jump_input.as_mut() {
+            input.push(c);
+        }
+    }
+
+    fn on_jump_backspace(&mut self) {
+        if let Some(input) = self.ui_config.jump_input.as_mut() {
+            input.pop();
+        }
+    }
+
+    /// Completes typed path against scanned folders, listing candidates when ambiguous
+    fn on_jump_complete(&mut self) {
+        if let Some(input) = self.ui_config.jump_input.take() {
+            let (completed, matches) = complete_path(&self.tree, &self.current_path, &input);
+            if !matches.is_empty() {
+                self.ui_config.message = Some(matches.join("  "));
+            }
+            self.ui_config.jump_input = Some(completed);
+        }
+    }
+
+    fn on_jump_submit(&mut self) {
+        if let Some(input) = self.ui_config.jump_input.take() {
+            if !input.trim().is_empty() {
+                self.jump_to(resolve_path(&self.current_path, &input));
+            }
+        }
+    }
+
+    /// Opens any folder, scanning it first when needed
+    fn jump_to(&mut self, path: PathBuf) {
+        if self.tree.find(&path).is_none() {
+            if let Err(err) = self.storage.read_dir(&path) {
+                self.ui_config.message =
+                    Some(format!("Can't open {}: {}", path.to_string_lossy(), err));
+                return;
+            }
+        }
+        self.open_path(path);
+        self.ui_config.view = View::Folder;
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn on_toggle_bookmark(&mut self) {
+        let added = self.bookmarks.toggle(&self.current_path);
+        let verb = if added {
+            "Bookmarked"
+        } else {
+            "Removed bookmark"
+        };
+        self.ui_config.message = Some(match self.bookmarks.save() {
+            Ok(_) => format!("{} {}", verb, self.current_path.to_string_lossy()),
+            Err(err) => err,
+        });
+    }
+
+    fn on_toggle_bookmarks_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Bookmarks => View::Folder,
+            _ => {
+                self.bookmarks_cursor = 0;
+                View::Bookmarks
+            }
+        };
+    }
+
+    fn on_bookmarks_cursor_up(&mut self) {
+        if self.bookmarks_cursor > 0 {
+            self.bookmarks_cursor -= 1;
+        }
+    }
+
+    fn on_bookmarks_cursor_down(&mut self) {
+        if self.bookmarks_cursor + 1 < self.bookmarks.paths.len() {
+            self.bookmarks_cursor += 1;
+        }
+    }
+
+    fn on_bookmarks_enter(&mut self) {
+        if let Some(path) = self.bookmarks.paths.get(self.bookmarks_cursor).cloned() {
+            self.jump_to(path);
+        }
+    }
+
+    fn on_remove_bookmark(&mut self) {
+        self.bookmarks.remove(self.bookmarks_cursor);
+        if self.bookmarks_cursor >= self.bookmarks.paths.len() {
+            self.bookmarks_cursor = self.bookmarks.paths.len().saturating_sub(1);
+        }
+        if let Err(err) = self.bookmarks.save() {
+            self.ui_config.message = Some(err);
+        }
+    }
+
+    /// Left click on a breadcrumb segment jumps to that folder
+    fn on_mouse(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left)
+            || self.ui_config.typed_confirmation.is_some()
+            || self.ui_config.jump_input.is_some()
+        {
+            return;
+        }
+        let position = Position {
+            x: mouse.column,
+            y: mouse.row,
+        };
+        let target = self
+            .ui_config
+            .breadcrumb
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, path)| path.clone());
+        if let Some(path) = target {
+            self.ui_config.message = None;
+            self.jump_to(path);
+        }
+    }
+
+    fn on_toggle_tree_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Tree => View::Folder,
+            _ => {
+                self.tree_view_expanded.clear();
+                self.tree_view_cursor = 0;
+                View::Tree
+            }
+        };
+        self.ui_config.confirming_deletion = false;
+    }
+
+    /// Rows of the tree view rooted at the current folder
+    fn tree_view_rows(&self) -> Vec<TreeRow> {
+        match self.tree.find(&self.current_path) {
+            Some(id) => self
+                .tree
+                .rows(id, &self.tree_view_expanded, self.ui_config.owner_filter),
+            None => vec![],
+        }
+    }
+
+    fn on_tree_view_cursor_up(&mut self) {
+        if self.tree_view_cursor > 0 {
+            self.tree_view_cursor -= 1;
+        }
+    }
+
+    fn on_tree_view_cursor_down(&mut self) {
+        if self.tree_view_cursor + 1 < self.tree_view_rows().len() {
+            self.tree_view_cursor += 1;
+        }
+    }
+
+    fn on_tree_view_toggle(&mut self) {
+        match self.tree_view_rows().get(self.tree_view_cursor) {
+            Some(row) if row.expanded => self.on_tree_view_collapse(),
+            _ => self.on_tree_view_expand(),
+        }
+    }
+
+    /// Shows content of the selected folder below it, sorted like the current folder
+    fn on_tree_view_expand(&mut self) {
+        let rows = self.tree_view_rows();
+        if let Some(id) = rows.get(self.tree_view_cursor).and_then(|row| row.folder) {
+            self.tree.sort(id, &self.ui_config.sort_by);
+            self.tree_view_expanded.insert(id);
+        }
+    }
+
+    /// Collapses the selected folder, or moves to its parent row when already collapsed
+    fn on_tree_view_collapse(&mut self) {
+        let rows = self.tree_view_rows();
+        let Some(row) = rows.get(self.tree_view_cursor) else {
+            return;
+        };
+        match row.folder {
+            Some(id) if row.expanded => {
+                self.tree_view_expanded.remove(&id);
+            }
+            _ => {
+                if let Some(parent_index) = rows[..self.tree_view_cursor]
+                    .iter()
+                    .rposition(|parent| parent.depth + 1 == row.depth)
+                {
+                    self.tree_view_cursor = parent_index;
+                }
+            }
+        }
+    }
+
+    fn on_toggle_stats_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Stats => View::Folder,
+            _ => View::Stats,
+        };
+    }
+
+    /// Writes HTML report of the current folder into `export_dir`
+    fn on_export(&mut self) {
+        let Some(folder) = self.get_current_folder() else {
+            return;
+        };
+        let file_name: String = folder
+            .title
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let report_path = self
+            .export_dir
+            .join(format!("space_inspector_{}.html", file_name));
+        let report = export::html_report(&self.tree, &self.current_path);
+
+        self.ui_config.message = Some(match std::fs::write(&report_path, report) {
+            Ok(_) => format!("Report saved to {}", report_path.to_string_lossy()),
+            Err(err) => format!("Failed to save report: {}", err),
+        });
+    }
+
+    fn on_cycle_theme(&mut self) {
+        self.ui_config.cycle_theme();
+        self.ui_config.message = Some(format!("Theme: {}", self.ui_config.theme().name));
+    }
+
+    fn on_toggle_cleanable_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Cleanable => View::Folder,
+            _ => {
+                self.cleanables = find_cleanables(&self.tree);
+                self.cleanables_cursor = 0;
+                View::Cleanable
+            }
+        };
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn on_cleanables_cursor_up(&mut self) {
+        if self.cleanables_cursor > 0 {
+            self.cleanables_cursor -= 1;
+        }
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn on_cleanables_cursor_down(&mut self) {
+        if self.cleanables_cursor + 1 < self.cleanables.len() {
+            self.cleanables_cursor += 1;
+        }
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn on_toggle_cleanable_selection(&mut self) {
+        if let Some(cleanable) = self.cleanables.get_mut(self.cleanables_cursor) {
+            cleanable.selected = !cleanable.selected;
+        }
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn on_toggle_all_cleanables(&mut self) {
+        let select = self.cleanables.iter().any(|cleanable| !cleanable.selected);
+        for cleanable in self.cleanables.iter_mut() {
+            cleanable.selected = select;
+        }
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn selected_cleanables_size(&self) -> u64 {
+        self.cleanables
+            .iter()
+            .filter(|cleanable| cleanable.selected)
+            .map(|cleanable| cleanable.size)
+            .sum()
+    }
+
+    fn on_purge_cleanables(&mut self) {
+        if !self.cleanables.iter().any(|cleanable| cleanable.selected) {
+            self.ui_config.message = Some(String::from("Nothing selected, \"Space\" - select"));
+            return;
+        }
+
+        if !self.ui_config.confirming_deletion {
+            self.ui_config.confirming_deletion = true;
+        } else if !self.delete_guard.read_only
+            && self.delete_guard.requires_typed_confirmation(
+                self.selected_cleanables_size(),
+                self.ui_config.move_to_trash,
+            )
+        {
+            self.ui_config.typed_confirmation = Some(String::new());
+        } else {
+            self.purge_selected_cleanables();
+        }
+    }
+
+    /// Removes selected cleanables through the same guard rails as single deletion
+    fn purge_selected_cleanables(&mut self) {
+        let selected: Vec<Cleanable> = self
+            .cleanables
+            .iter()
+            .filter(|cleanable| cleanable.selected)
+            .cloned()
+            .collect();
+        let mut purged = 0;
+        let mut freed = 0;
+        let mut skipped = 0;
+
+        for cleanable in selected {
+            if self
+                .delete_guard
+                .protection_reason(self.storage.as_ref(), &cleanable.path, &self.start_path)
+                .is_some()
+            {
+                skipped += 1;
+                continue;
+            }
+
+            if self.delete_guard.read_only {
+                self.dry_run_log.push(format!(
+                    "Dry run: would remove {} ({})",
+                    cleanable.path.to_string_lossy(),
+                    format_file_size(cleanable.size)
+                ));
+                purged += 1;
+                freed += cleanable.size;
+                continue;
+            }
+
+            if self
+                .storage
+                .delete_folder(&cleanable.path, self.ui_config.move_to_trash)
+                .is_ok()
+            {
+                self.remove_folder_from_tree(&cleanable.path, cleanable.size);
+                purged += 1;
+                freed += cleanable.size;
+            } else {
+                skipped += 1;
+            }
+        }
+
+        let verb = if self.delete_guard.read_only {
+            "Dry run: would purge"
+        } else {
+            "Purged"
+        };
+        self.ui_config.message = Some(format!(
+            "{} {} folders, {} | Skipped: {}",
+            verb,
+            purged,
+            format_file_size(freed),
+            skipped
+        ));
+        self.ui_config.confirming_deletion = false;
+        if !self.delete_guard.read_only {
+            self.cleanables = find_cleanables(&self.tree);
+            self.cleanables_cursor = 0;
+        }
+        self.refresh_filesystem();
+    }
+
+    /// Drops deleted folder and everything below it from the scanned tree
+    fn remove_folder_from_tree(&mut self, path: &Path, size: u64) {
+        let subfolder_id = self.tree.find(path);
+        let subfolder = subfolder_id.and_then(|id| self.tree.get(id));
+        let owner_diff = subfolder
+            .map(|folder| folder.owners.clone())
+            .unwrap_or_default();
+        let count_diff = folder_count_diff(subfolder.map(|folder| folder.count));
+
+        if let (Some(parent_id), Some(title)) = (
+            path.parent().and_then(|parent| self.tree.find(parent)),
+            path.file_name(),
+        ) {
+            let title = title.to_string_lossy();
+            self.propagate_size_update_upwards(
+                parent_id,
+                &title,
+                size,
+                &owner_diff,
+                count_diff,
+                DiffKind::Subtract,
+            );
+            if let Some(entry) = self
+                .tree
+                .get_mut(parent_id)
+                .and_then(|parent_folder| parent_folder.remove_entry(&title))
+            {
+                self.tree.release(entry.title);
+            }
+        }
+        if let Some(id) = subfolder_id {
+            self.tree.remove(id);
+        }
+    }
+
+    fn on_toggle_filesystems_view(&mut self) {
+        self.ui_config.view = match self.ui_config.view {
+            View::Filesystems => View::Folder,
+            _ => {
+                self.filesystems = self.storage.mounted_filesystems();
+                self.filesystems_cursor = self
+                    .filesystems
+                    .iter()
+                    .position(|fs| {
+                        Some(&fs.mount_point) == self.filesystem.as_ref().map(|fs| &fs.mount_point)
+                    })
+                    .unwrap_or(0);
+                View::Filesystems
+            }
+        };
+    }
+
+    fn on_filesystems_cursor_up(&mut self) {
+        if self.filesystems_cursor > 0 {
+            self.filesystems_cursor -= 1;
+        }
+    }
+
+    fn on_filesystems_cursor_down(&mut self) {
+        if self.filesystems_cursor + 1 < self.filesystems.len() {
+            self.filesystems_cursor += 1;
+        }
+    }
+
+    /// Starts inspecting the selected filesystem from its mount point
+    fn on_filesystems_enter(&mut self) {
+        if let Some(filesystem) = self.filesystems.get(self.filesystems_cursor) {
+            self.open_path(filesystem.mount_point.clone());
+        }
+        self.ui_config.view = View::Folder;
+        self.ui_config.confirming_deletion = false;
+    }
+
+    /// Cycles through owners of the current subtree: all -> uid1 -> uid2 -> ... -> all
+    fn on_cycle_owner_filter(&mut self) {
+        if let Some(folder) = self.get_current_folder() {
+            let mut uids: Vec<u32> = folder.owners.keys().cloned().collect();
+            uids.sort();
+
+            let next = match self.ui_config.owner_filter {
+                None => uids.first().cloned(),
+                Some(current) => uids.into_iter().find(|uid| *uid > current),
+            };
+            self.ui_config.owner_filter = next;
+        }
+        self.snap_cursor_to_filter();
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn snap_cursor_to_filter(&mut self) {
+        if let Some(id) = self.tree.find(&self.current_path) {
+            self.tree.snap_cursor(id, self.ui_config.owner_filter);
+        }
+    }
+
     fn on_toggle_coloring(&mut self) {
         self.ui_config.colored = !self.ui_config.colored;
     }
@@ -103,7 +891,7 @@ impl App {
             SortBy::Title => {
                 self.ui_config.sort_by = SortBy::Size;
             }
-            SortBy::Size => {
+            _ => {
                 self.ui_config.sort_by = SortBy::Title;
             }
         }
@@ -111,83 +899,177 @@ impl App {
         self.sort_current_folder();
     }
 
+    /// Sorts by recursive file count, then folder count, showing the column sorted by
+    fn on_toggle_count_sorting(&mut self) {
+        match self.ui_config.sort_by {
+            SortBy::Files => {
+                self.ui_config.sort_by = SortBy::Folders;
+                self.ui_config.show_folders = true;
+            }
+            _ => {
+                self.ui_config.sort_by = SortBy::Files;
+                self.ui_config.show_files = true;
+            }
+        }
+
+        self.sort_current_folder();
+    }
+
     fn on_backspace(&mut self) {
         self.navigate_to_parent();
     }
 
     fn sort_current_folder(&mut self) {
-        if let Some(mut folder) = self.get_current_folder().cloned() {
-            match self.ui_config.sort_by {
-                SortBy::Size => {
-                    folder.sort_by_size();
-                }
-                SortBy::Title => {
-                    folder.sort_by_title();
-                }
-            }
-            self.set_current_folder(folder);
+        if let Some(id) = self.tree.find(&self.current_path) {
+            self.tree.sort(id, &self.ui_config.sort_by);
         }
     }
 
-    fn get_current_folder_v2(&mut self) -> Option<&mut Folder> {
-        self.file_tree_map.get_mut(&self.get_current_path_string())
-    }
-
     fn on_cursor_up(&mut self) {
-        if let Some(folder) = self.get_current_folder_v2() {
-            if folder.cursor_index > 0 {
-                folder.cursor_index -= 1;
-            }
+        if let Some(id) = self.tree.find(&self.current_path) {
+            self.tree.cursor_up(id, self.ui_config.owner_filter);
         }
         self.ui_config.confirming_deletion = false;
     }
 
     fn on_cursor_down(&mut self) {
-        if let Some(folder) = self.get_current_folder_v2() {
-            if folder.cursor_index < folder.entries.len() - 1 {
-                folder.cursor_index += 1;
-            }
+        if let Some(id) = self.tree.find(&self.current_path) {
+            self.tree.cursor_down(id, self.ui_config.owner_filter);
         }
         self.ui_config.confirming_deletion = false;
     }
 
     fn get_current_folder(&self) -> Option<&Folder> {
-        self.file_tree_map.get(&self.get_current_path_string())
-    }
-
-    fn set_current_folder(&mut self, folder: Folder) {
-        self.file_tree_map
-            .insert(self.get_current_path_string(), folder);
+        self.tree.folder(&self.current_path)
     }
 
+    /// Applies removal of `child_title` from folder `folder_id` to it and all folders above
     fn propagate_size_update_upwards(
         &mut self,
-        to_delete_path: &PathBuf,
+        folder_id: FolderId,
+        child_title: &str,
         entry_diff: u64,
+        owner_diff: &OwnerUsage,
+        count_diff: ItemCount,
         diff_kind: DiffKind,
     ) {
-        let mut parent_path = to_delete_path.clone();
-        while let Some(parent) = parent_path.parent() {
-            if let Some(parent_folder) = self.file_tree_map.get_mut(parent.to_str().unwrap()) {
-                if let Some(parent_folder_entry) =
-                    parent_folder.entries.get_mut(parent_folder.cursor_index)
-                {
-                    if let Some(size) = parent_folder_entry.size.as_mut() {
-                        match diff_kind {
-                            DiffKind::Subtract => *size -= entry_diff,
-                        }
+        let mut child_title = Name::from(child_title);
+        let mut current = Some(folder_id);
+        while let Some(folder) = current.and_then(|id| self.tree.get_mut(id)) {
+            match diff_kind {
+                DiffKind::Subtract => {
+                    subtract_owner_usage(&mut folder.owners, owner_diff);
+                    folder.count.subtract(count_diff);
+                }
+            }
+            if let Some(folder_entry) = folder
+                .entries
+                .iter_mut()
+                .find(|entry| entry.kind != FolderEntryType::Parent && entry.title == child_title)
+            {
+                if let Some(size) = folder_entry.size() {
+                    match diff_kind {
+                        DiffKind::Subtract => folder_entry.set_size(Some(size - entry_diff)),
                     }
                 }
-                parent_path = parent.to_path_buf();
-            } else {
-                break;
             }
+            child_title = folder.title.clone();
+            current = folder.parent;
         }
     }
 
     fn on_delete(&mut self) {
-        if let Some(mut folder) = self.get_current_folder().cloned() {
+        if let Some(folder) = self.get_current_folder() {
             let entry = folder.get_selected_entry();
+            if entry.kind == FolderEntryType::Parent {
+                return;
+            }
+
+            let to_delete_path = self.current_path.join(&entry.title);
+            let size = entry.size().unwrap_or(0);
+
+            if let Some(reason) = self.delete_guard.protection_reason(
+                self.storage.as_ref(),
+                &to_delete_path,
+                &self.start_path,
+            ) {
+                self.ui_config.message = Some(reason);
+                self.ui_config.confirming_deletion = false;
+                return;
+            }
+
+            if !self.ui_config.confirming_deletion {
+                self.ui_config.confirming_deletion = true;
+            } else if self.delete_guard.read_only {
+                let message = format!(
+                    "Dry run: would remove {} ({})",
+                    to_delete_path.to_string_lossy(),
+                    format_file_size(size)
+                );
+                self.dry_run_log.push(message.clone());
+                self.ui_config.message = Some(message);
+                self.ui_config.confirming_deletion = false;
+            } else if self
+                .delete_guard
+                .requires_typed_confirmation(size, self.ui_config.move_to_trash)
+            {
+                self.ui_config.typed_confirmation = Some(String::new());
+            } else {
+                self.delete_selected();
+            }
+        }
+    }
+
+    fn on_confirmation_input(&mut self, c: char) {
+        if let Some(input) = self.ui_config.typed_confirmation.as_mut() {
+            input.push(c);
+        }
+    }
+
+    fn on_confirmation_backspace(&mut self) {
+        if let Some(input) = self.ui_config.typed_confirmation.as_mut() {
+            input.pop();
+        }
+    }
+
+    fn on_confirmation_cancel(&mut self) {
+        self.ui_config.typed_confirmation = None;
+        self.ui_config.confirming_deletion = false;
+    }
+
+    /// Text that has to be typed to confirm pending permanent deletion
+    fn confirmation_target(&self) -> String {
+        match self.ui_config.view {
+            View::Cleanable => String::from(TEXT_CONFIRM_PURGE),
+            _ => self
+                .get_current_folder()
+                .map(|folder| folder.get_selected_entry().title.to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Deletes only if the confirmation target was typed exactly
+    fn on_confirmation_submit(&mut self) {
+        if let Some(input) = self.ui_config.typed_confirmation.take() {
+            if input == self.confirmation_target() {
+                match self.ui_config.view {
+                    View::Cleanable => self.purge_selected_cleanables(),
+                    _ => self.delete_selected(),
+                }
+            } else {
+                self.ui_config.message =
+                    Some(String::from("Name did not match, nothing was deleted"));
+            }
+        }
+        self.ui_config.confirming_deletion = false;
+    }
+
+    fn delete_selected(&mut self) {
+        let Some(folder_id) = self.tree.find(&self.current_path) else {
+            return;
+        };
+        if let Some(folder) = self.tree.get(folder_id) {
+            let entry = folder.get_selected_entry().clone();
 
             let mut to_delete_path = PathBuf::from(&self.current_path);
             to_delete_path.push(&entry.title);
@@ -195,67 +1077,98 @@ impl App {
             match entry.kind {
                 FolderEntryType::Parent => {}
                 FolderEntryType::Folder => {
-                    if !self.ui_config.confirming_deletion {
-                        self.ui_config.confirming_deletion = true;
-                    } else {
-                        if let Ok(_) = delete_folder(&to_delete_path, &self.ui_config) {
-                            if let Some(subfolder_size) = entry.size {
-                                self.propagate_size_update_upwards(
-                                    &to_delete_path,
-                                    subfolder_size,
-                                    DiffKind::Subtract,
-                                );
-                            }
-                            folder.remove_selected();
-                            let path_string = to_delete_path.to_string_lossy().into_owned();
-                            self.file_tree_map.remove(&path_string);
-                            self.set_current_folder(folder);
-                            self.ui_config.confirming_deletion = false;
+                    if self
+                        .storage
+                        .delete_folder(&to_delete_path, self.ui_config.move_to_trash)
+                        .is_ok()
+                    {
+                        if let Some(subfolder_size) = entry.size() {
+                            let subfolder = entry.folder().and_then(|id| self.tree.get(id));
+                            let owner_diff = subfolder
+                                .map(|subfolder| subfolder.owners.clone())
+                                .unwrap_or_default();
+                            let count_diff =
+                                folder_count_diff(subfolder.map(|subfolder| subfolder.count));
+                            self.propagate_size_update_upwards(
+                                folder_id,
+                                &entry.title,
+                                subfolder_size,
+                                &owner_diff,
+                                count_diff,
+                                DiffKind::Subtract,
+                            );
                         }
+                        if let Some(subfolder) = entry.folder() {
+                            self.tree.remove(subfolder);
+                        }
+                        // Its copy of the title would keep the name interned
+                        drop(entry);
+                        self.remove_selected_entry(folder_id);
                     }
                 }
                 FolderEntryType::File => {
-                    if !self.ui_config.confirming_deletion {
-                        self.ui_config.confirming_deletion = true;
-                    } else {
-                        if let Ok(_) = delete_file(&to_delete_path, &self.ui_config) {
-                            if let Some(subfile_size) = entry.size {
-                                self.propagate_size_update_upwards(
-                                    &to_delete_path,
-                                    subfile_size,
-                                    DiffKind::Subtract,
-                                );
+                    if self
+                        .storage
+                        .delete_file(&to_delete_path, self.ui_config.move_to_trash)
+                        .is_ok()
+                    {
+                        if let Some(subfile_size) = entry.size() {
+                            let mut owner_diff = OwnerUsage::new();
+                            if let Some(owner) = entry.owner() {
+                                owner_diff.insert(owner.uid, subfile_size);
                             }
-                            folder.remove_selected();
-                            self.set_current_folder(folder);
-                            self.ui_config.confirming_deletion = false;
+                            let count_diff = ItemCount {
+                                files: 1,
+                                folders: 0,
+                            };
+                            self.propagate_size_update_upwards(
+                                folder_id,
+                                &entry.title,
+                                subfile_size,
+                                &owner_diff,
+                                count_diff,
+                                DiffKind::Subtract,
+                            );
                         }
+                        drop(entry);
+                        self.remove_selected_entry(folder_id);
                     }
                 }
             }
         }
+        self.ui_config.confirming_deletion = false;
     }
 
-    fn navigate_to_parent(&mut self) {
-        if let Some(parent) = PathBuf::from(&self.current_path).parent() {
-            let parent_buf = parent.to_path_buf();
-            self.current_path = parent_buf.clone();
-            self.process_filepath(&parent_buf);
-            self.sort_current_folder();
+    fn remove_selected_entry(&mut self, folder_id: FolderId) {
+        if let Some(folder) = self.tree.get_mut(folder_id) {
+            let entry = folder.remove_selected();
+            self.tree.release(entry.title);
         }
+        self.refresh_filesystem();
     }
 
-    fn navigate_to_child(&mut self, title: &String) {
-        let mut new_path = PathBuf::from(&self.current_path);
-        new_path.push(title);
-        self.current_path = new_path;
-        self.process_filepath(&PathBuf::from(&self.current_path));
+    /// Makes `path` the current folder, scanning it unless already scanned
+    fn open_path(&mut self, path: PathBuf) {
+        self.current_path = path;
+        self.scan(&self.current_path.clone());
         self.sort_current_folder();
+        self.snap_cursor_to_filter();
+        self.refresh_filesystem();
+    }
+
+    fn navigate_to_parent(&mut self) {
+        if let Some(parent) = self.current_path.parent() {
+            self.open_path(parent.to_path_buf());
+        }
+    }
+
+    fn navigate_to_child(&mut self, title: &str) {
+        self.open_path(self.current_path.join(title));
     }
 
     fn on_enter(&mut self) {
-        if let Some(folder) = self.get_current_folder().cloned() {
-            let entry = folder.get_selected_entry();
+        if let Some(folder) = self.get_current_folder() {
+            let entry = folder.get_selected_entry().clone();
 
             match entry.kind {
                 FolderEntryType::Parent => {
@@ -276,37 +1189,64 @@ impl App {
         self.ui_config.confirming_deletion = false;
     }
 
-    fn process_filepath(&mut self, path_buf: &PathBuf) -> u64 {
-        if !self
-            .file_tree_map
-            .contains_key(&path_buf.to_string_lossy().to_string())
-        {
-            let path_string = path_buf.to_string_lossy().into_owned();
+    /// Scans folders under `path_buf` not scanned yet, timing it in `scan_stats`
+    fn scan(&mut self, path_buf: &PathBuf) {
+        let started = Instant::now();
+        if self.tree.find(path_buf).is_none() {
+            self.storage.prefetch(path_buf);
+        }
+        if let Some(id) = self.process_filepath(path_buf) {
+            self.tree.add_root(path_buf, id);
+        }
+        self.scan_stats.elapsed += started.elapsed();
+    }
 
-            if let Some(folder) = self.file_tree_map.get(&path_string) {
-                return folder.get_size();
-            }
+    /// Scans folder and its subfolders unless already scanned
+    fn process_filepath(&mut self, path_buf: &PathBuf) -> Option<FolderId> {
+        if let Some(id) = self.tree.find(path_buf) {
+            return Some(id);
+        }
 
-            let mut folder = path_to_folder(path_buf);
+        let mut folder = path_to_folder(self.storage.as_ref(), path_buf, &mut self.scan_stats);
+        folder.title = self.tree.intern(&folder.title);
+        let mut subfolders_modified = vec![];
 
-            for child_entry in folder.entries.iter_mut() {
-                if child_entry.kind == FolderEntryType::Folder {
-                    let mut subfolder_path = path_buf.clone();
-                    subfolder_path.push(&child_entry.title);
+        for child_entry in folder.entries.iter_mut() {
+            child_entry.title = self.tree.intern(&child_entry.title);
+            if child_entry.kind == FolderEntryType::Folder {
+                let mut subfolder_path = path_buf.clone();
+                subfolder_path.push(&child_entry.title);
 
-                    let subfolder_size = self.process_filepath(&subfolder_path);
-                    child_entry.size = Some(subfolder_size);
+                child_entry.set_folder(self.process_filepath(&subfolder_path));
+                if let Some(subfolder) = child_entry.folder().and_then(|id| self.tree.get(id)) {
+                    child_entry.set_size(Some(subfolder.get_size()));
+                    add_owner_usage(&mut folder.owners, &subfolder.owners);
+                    folder.count.add(subfolder.count);
+                    subfolders_modified.push(subfolder.last_modified);
                 }
             }
+        }
+        for modified in subfolders_modified {
+            folder.touch(modified);
+        }
 
-            let total_size = folder.get_size();
-
-            self.file_tree_map.insert(path_string, folder);
+        Some(self.tree.insert(folder))
+    }
+}
 
-            return total_size;
-        }
+/// Items removed together with a folder containing `count`
+fn folder_count_diff(count: Option<ItemCount>) -> ItemCount {
+    let mut diff = count.unwrap_or_default();
+    diff.folders += 1;
+    diff
+}
 
-        0
+fn to_absolute(path_buf: PathBuf) -> PathBuf {
+    if path_buf.is_absolute() {
+        path_buf
+    } else {
+        let current_dir = env::current_dir().unwrap();
+        current_dir.join(&path_buf)
     }
 }
 
@@ -1,39 +1,73 @@
-use space_inspector::App;
+use space_inspector::config::{Config, RemoteAgent};
+use space_inspector::remote::{serve, RemoteStorage};
+use space_inspector::{App, LocalStorage, Storage};
 use std::env;
 use std::error::Error;
-use std::io::stdout;
+use std::io::{stdin, stdout};
 use std::process;
 
-mod config;
-
-use config::Config;
-
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::prelude::*;
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let config = Config::build(env::args()).unwrap_or_else(|err| {
+    let mut config = Config::build(env::args()).unwrap_or_else(|err| {
         eprintln!("Problem parsing arguments: {err}");
         process::exit(1);
     });
+    // https://no-color.org
+    config.no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
 
-    let terminal = init_terminal()?;
+    if config.serve {
+        serve(&LocalStorage, stdin().lock(), stdout().lock())?;
+        return Ok(());
+    }
+
+    let remote = match &config.remote {
+        Some(RemoteAgent::Command(command)) => Some(RemoteStorage::spawn(command)),
+        Some(RemoteAgent::Ssh(host)) => Some(RemoteStorage::spawn_ssh(host)),
+        None => None,
+    };
+    let storage: Box<dyn Storage> = match remote {
+        Some(remote) => Box::new(remote.unwrap_or_else(|err| {
+            eprintln!("Problem connecting to remote agent: {err}");
+            process::exit(1);
+        })),
+        None => Box::new(LocalStorage),
+    };
 
-    let mut app = App::new();
+    let mut app = App::with_storage(storage);
+    app.configure(&config);
+
+    if config.report {
+        app.init(config.file_path);
+        println!("{}", app.current_path().to_string_lossy());
+        for line in app.scan_stats().report_lines() {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+
+    let terminal = init_terminal()?;
     app.init(config.file_path);
     app.run(terminal)?;
 
     restore_terminal()?;
 
+    for line in app.dry_run_log() {
+        println!("{}", line);
+    }
+
     Ok(())
 }
 
 fn init_terminal() -> color_eyre::Result<Terminal<impl Backend>> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -41,6 +75,7 @@ fn init_terminal() -> color_eyre::Result<Terminal<impl Backend>> {
 
 fn restore_terminal() -> color_eyre::Result<()> {
     disable_raw_mode()?;
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
@@ -0,0 +1,125 @@
+use crate::fs::{FolderEntryType, Storage};
+use crate::remote::{decode_line, encode_entry, encode_filesystem, encode_line, PROTOCOL_VERSION};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Answers protocol requests from `input` until it is closed.
+/// Used by `--serve`, where `input` and `output` are stdin and stdout.
+pub fn serve(storage: &dyn Storage, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let request = decode_line(&line);
+        match (request.first().map(String::as_str), request.get(1)) {
+            (Some("SCAN"), Some(path)) => scan(storage, Path::new(path), &mut output)?,
+            _ => {
+                for record in handle_request(storage, &request) {
+                    writeln!(output, "{}", encode_line(&record))?;
+                }
+            }
+        }
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Walks `path` and writes each folder as soon as it's read,
+/// so the client gets the whole tree in one request
+fn scan(storage: &dyn Storage, path: &Path, output: &mut impl Write) -> io::Result<()> {
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        let path_field = path.to_string_lossy().into_owned();
+        match storage.read_dir(&path) {
+            Ok(entries) => {
+                writeln!(
+                    output,
+                    "{}",
+                    encode_line(&[String::from("DIR"), path_field])
+                )?;
+                for entry in entries.iter() {
+                    writeln!(output, "{}", encode_line(&encode_entry(entry)))?;
+                    if entry.kind == FolderEntryType::Folder {
+                        pending.push(path.join(&entry.title));
+                    }
+                }
+            }
+            Err(err) => {
+                let record = [String::from("DIR_ERR"), path_field, err.to_string()];
+                writeln!(output, "{}", encode_line(&record))?;
+            }
+        }
+    }
+
+    writeln!(output, "{}", encode_line(&ok(None)))
+}
+
+fn handle_request(storage: &dyn Storage, request: &[String]) -> Vec<Vec<String>> {
+    let command = request.first().map(String::as_str).unwrap_or_default();
+    let path = request.get(1).map(PathBuf::from);
+    let move_to_trash = request.get(2).is_some_and(|flag| flag == "1");
+
+    match (command, path) {
+        ("HELLO", _) => vec![ok(Some(PROTOCOL_VERSION.to_string()))],
+        ("CWD", _) => match storage.current_dir() {
+            Ok(path) => vec![ok(Some(path.to_string_lossy().into_owned()))],
+            Err(err) => vec![error(&err.to_string())],
+        },
+        ("HOME", _) => vec![ok(storage
+            .home_dir()
+            .map(|path| path.to_string_lossy().into_owned()))],
+        ("READ_DIR", Some(path)) => match storage.read_dir(&path) {
+            Ok(entries) => {
+                let mut records: Vec<Vec<String>> = entries.iter().map(encode_entry).collect();
+                records.push(ok(None));
+                records
+            }
+            Err(err) => vec![error(&err.to_string())],
+        },
+        ("EXISTS", Some(path)) => {
+            let exists = if storage.exists(&path) { "1" } else { "0" };
+            vec![ok(Some(String::from(exists)))]
+        }
+        ("DELETE_FOLDER", Some(path)) => match storage.delete_folder(&path, move_to_trash) {
+            Ok(_) => vec![ok(None)],
+            Err(err) => vec![error(&err.to_string())],
+        },
+        ("DELETE_FILE", Some(path)) => match storage.delete_file(&path, move_to_trash) {
+            Ok(_) => vec![ok(None)],
+            Err(err) => vec![error(&err.to_string())],
+        },
+        ("FILESYSTEM", Some(path)) => {
+            let mut records: Vec<Vec<String>> = storage
+                .filesystem_for_path(&path)
+                .iter()
+                .map(encode_filesystem)
+                .collect();
+            records.push(ok(None));
+            records
+        }
+        ("MOUNTS", _) => {
+            let mut records: Vec<Vec<String>> = storage
+                .mounted_filesystems()
+                .iter()
+                .map(encode_filesystem)
+                .collect();
+            records.push(ok(None));
+            records
+        }
+        _ => vec![error(&format!("Unknown request: {}", command))],
+    }
+}
+
+fn ok(value: Option<String>) -> Vec<String> {
+    let mut record = vec![String::from("OK")];
+    record.extend(value);
+    record
+}
+
+fn error(message: &str) -> Vec<String> {
+    vec![String::from("ERR"), message.to_string()]
+}
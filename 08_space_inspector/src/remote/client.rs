@@ -0,0 +1,271 @@
+use crate::fs::{FileSystem, FolderEntry, Storage};
+use crate::remote::{
+    decode_entry, decode_filesystem, decode_line, encode_line, invalid_data, PROTOCOL_VERSION,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+/// Storage served by a `--serve` agent, usually on another host
+pub struct RemoteStorage {
+    connection: RefCell<Connection>,
+    description: String,
+    /// Folders streamed by the last `SCAN`, handed out once by `read_dir`
+    scanned: RefCell<HashMap<PathBuf, ScannedFolder>>,
+}
+
+/// Entries of a scanned folder or the error reading it
+type ScannedFolder = Result<Vec<FolderEntry>, String>;
+
+struct Connection {
+    reader: Box<dyn BufRead>,
+    writer: Box<dyn Write>,
+    child: Option<Child>,
+}
+
+/// Records preceding `OK` and the optional value after it
+type Response = (Vec<Vec<String>>, Option<String>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Debug for RemoteStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteStorage")
+            .field("description", &self.description)
+            .finish()
+    }
+}
+
+impl RemoteStorage {
+    /// Runs `command` with `sh -c` and talks to it over its stdin and stdout,
+    /// e.g. `./space_inspector --serve`
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut shell = Command::new("sh");
+        shell.arg("-c").arg(command);
+        Self::spawn_command(shell, command)
+    }
+
+    /// Starts the agent on `host` with `ssh host space_inspector --serve`.
+    /// `host` is passed as a single argument, never through a shell.
+    pub fn spawn_ssh(host: &str) -> io::Result<Self> {
+        let mut ssh = Command::new("ssh");
+        ssh.arg(host).arg("space_inspector").arg("--serve");
+        Self::spawn_command(ssh, &format!("ssh {}", host))
+    }
+
+    fn spawn_command(mut command: Command, description: &str) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let reader = child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let writer = child.stdin.take().ok_or(io::ErrorKind::BrokenPipe)?;
+
+        Self::connect(
+            Connection {
+                reader: Box::new(BufReader::new(reader)),
+                writer: Box::new(writer),
+                child: Some(child),
+            },
+            description,
+        )
+    }
+
+    /// Talks to an agent over already open streams
+    pub fn new(reader: impl Read + 'static, writer: impl Write + 'static) -> io::Result<Self> {
+        Self::connect(
+            Connection {
+                reader: Box::new(BufReader::new(reader)),
+                writer: Box::new(writer),
+                child: None,
+            },
+            "stream",
+        )
+    }
+
+    fn connect(connection: Connection, description: &str) -> io::Result<Self> {
+        let storage = RemoteStorage {
+            connection: RefCell::new(connection),
+            description: description.to_string(),
+            scanned: RefCell::new(HashMap::new()),
+        };
+
+        let (_, version) = storage.request(&["HELLO", &PROTOCOL_VERSION.to_string()])?;
+        if version != Some(PROTOCOL_VERSION.to_string()) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "Agent speaks protocol version {}, expected {}",
+                    version.unwrap_or_default(),
+                    PROTOCOL_VERSION
+                ),
+            ));
+        }
+
+        Ok(storage)
+    }
+
+    fn request(&self, fields: &[&str]) -> io::Result<Response> {
+        let mut records = vec![];
+        let value = self.request_streamed(fields, |record| {
+            records.push(record);
+            Ok(())
+        })?;
+        Ok((records, value))
+    }
+
+    /// Sends request and passes records to `on_record` as they arrive,
+    /// returns the value after `OK`
+    fn request_streamed(
+        &self,
+        fields: &[&str],
+        mut on_record: impl FnMut(Vec<String>) -> io::Result<()>,
+    ) -> io::Result<Option<String>> {
+        let mut connection = self.connection.borrow_mut();
+        let fields: Vec<String> = fields.iter().map(|field| field.to_string()).collect();
+        writeln!(connection.writer, "{}", encode_line(&fields))?;
+        connection.writer.flush()?;
+
+        // The response is read up to its end even after a bad record to stay in sync
+        let mut record_error = None;
+        loop {
+            let mut line = String::new();
+            if connection.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Remote agent closed connection",
+                ));
+            }
+
+            let record = decode_line(line.trim_end_matches(['\n', '\r']));
+            match record.first().map(String::as_str) {
+                Some("OK") => {
+                    return match record_error {
+                        Some(err) => Err(err),
+                        None => Ok(record.get(1).cloned()),
+                    }
+                }
+                Some("ERR") => {
+                    return Err(io::Error::other(record.get(1).cloned().unwrap_or_default()))
+                }
+                _ => {
+                    if record_error.is_none() {
+                        record_error = on_record(record).err();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lets the agent walk everything under `path` and keeps the streamed folders
+    fn scan(&self, path: &Path) -> io::Result<()> {
+        let mut scanned = HashMap::new();
+        let mut current: Option<(PathBuf, Vec<FolderEntry>)> = None;
+
+        self.request_streamed(&["SCAN", &path.to_string_lossy()], |record| {
+            match record.first().map(String::as_str) {
+                Some("DIR") => {
+                    if let Some((path, entries)) = current.take() {
+                        scanned.insert(path, Ok(entries));
+                    }
+                    let path = record.get(1).ok_or_else(|| invalid_data("Missing path"))?;
+                    current = Some((PathBuf::from(path), vec![]));
+                }
+                Some("DIR_ERR") => {
+                    let path = record.get(1).ok_or_else(|| invalid_data("Missing path"))?;
+                    let message = record.get(2).cloned().unwrap_or_default();
+                    scanned.insert(PathBuf::from(path), Err(message));
+                }
+                Some("ENTRY") => match current.as_mut() {
+                    Some((_, entries)) => entries.push(decode_entry(&record)?),
+                    None => return Err(invalid_data("Entry outside of folder")),
+                },
+                _ => return Err(invalid_data("Unknown record")),
+            }
+            Ok(())
+        })?;
+        if let Some((path, entries)) = current {
+            scanned.insert(path, Ok(entries));
+        }
+
+        *self.scanned.borrow_mut() = scanned;
+        Ok(())
+    }
+
+    fn request_path(&self, command: &str, path: &Path) -> io::Result<Response> {
+        self.request(&[command, &path.to_string_lossy()])
+    }
+}
+
+impl Storage for RemoteStorage {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<FolderEntry>> {
+        if let Some(scanned) = self.scanned.borrow_mut().remove(path) {
+            return scanned.map_err(io::Error::other);
+        }
+
+        let (records, _) = self.request_path("READ_DIR", path)?;
+        records.iter().map(|record| decode_entry(record)).collect()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.request_path("EXISTS", path)
+            .is_ok_and(|(_, value)| value.as_deref() == Some("1"))
+    }
+
+    fn delete_folder(&self, path: &Path, move_to_trash: bool) -> io::Result<()> {
+        let trash = if move_to_trash { "1" } else { "0" };
+        self.request(&["DELETE_FOLDER", &path.to_string_lossy(), trash])?;
+        Ok(())
+    }
+
+    fn delete_file(&self, path: &Path, move_to_trash: bool) -> io::Result<()> {
+        let trash = if move_to_trash { "1" } else { "0" };
+        self.request(&["DELETE_FILE", &path.to_string_lossy(), trash])?;
+        Ok(())
+    }
+
+    fn filesystem_for_path(&self, path: &Path) -> Option<FileSystem> {
+        let (records, _) = self.request_path("FILESYSTEM", path).ok()?;
+        records
+            .first()
+            .and_then(|record| decode_filesystem(record).ok())
+    }
+
+    fn mounted_filesystems(&self) -> Vec<FileSystem> {
+        match self.request(&["MOUNTS"]) {
+            Ok((records, _)) => records
+                .iter()
+                .filter_map(|record| decode_filesystem(record).ok())
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        let (_, path) = self.request(&["CWD"])?;
+        path.map(PathBuf::from)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing directory"))
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        let (_, path) = self.request(&["HOME"]).ok()?;
+        path.map(PathBuf::from)
+    }
+
+    fn prefetch(&self, path: &Path) {
+        // Folders left out by a failed scan are read one by one
+        if self.scan(path).is_err() {
+            self.scanned.borrow_mut().clear();
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Line based protocol between the `--serve` agent and `RemoteStorage`.
+//!
+//! Every request is a single line of tab separated fields:
+//!
+//! ```text
+//! HELLO           <version>
+//! CWD
+//! HOME
+//! READ_DIR        <path>
+//! SCAN            <path>
+//! EXISTS          <path>
+//! DELETE_FOLDER   <path> <move to trash: 0|1>
+//! DELETE_FILE     <path> <move to trash: 0|1>
+//! FILESYSTEM      <path>
+//! MOUNTS
+//! ```
+//!
+//! Response is zero or more records terminated by `OK [value]` or `ERR <message>`:
+//!
+//! ```text
+//! ENTRY   <D|F> <title> <size> <uid> <gid> <modified>
+//! FS      <device> <mount point> <type> <total> <used> <free>
+//! DIR     <path>
+//! DIR_ERR <path> <message>
+//! ```
+//!
+//! `SCAN` walks the whole tree on the agent and streams every folder as `DIR`
+//! followed by its `ENTRY` records, or `DIR_ERR` when the folder can't be read.
+//!
+//! Unknown values are sent as `-`, `modified` is `seconds.nanoseconds` since Unix epoch.
+//! Backslashes, tabs and line breaks inside fields are escaped as `\\`, `\t`, `\n`, `\r`.
+use crate::fs::{FileSystem, FolderEntry, FolderEntryType, Name, Owner};
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+mod agent;
+mod client;
+pub use agent::serve;
+pub use client::RemoteStorage;
+
+pub const PROTOCOL_VERSION: u32 = 2;
+const NONE: &str = "-";
+
+fn encode_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| escape(field))
+        .collect::<Vec<String>>()
+        .join("\t")
+}
+
+fn decode_line(line: &str) -> Vec<String> {
+    line.split('\t').map(unescape).collect()
+}
+
+fn escape(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+fn encode_optional<T: ToString>(value: Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => String::from(NONE),
+    }
+}
+
+fn decode_optional<T: FromStr>(field: Option<&String>) -> io::Result<Option<T>> {
+    match field.map(String::as_str) {
+        None => Err(invalid_data("Missing field")),
+        Some(NONE) => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| invalid_data("Malformed number")),
+    }
+}
+
+fn decode_required<T: FromStr>(field: Option<&String>) -> io::Result<T> {
+    decode_optional(field)?.ok_or_else(|| invalid_data("Missing value"))
+}
+
+fn encode_time(time: SystemTime) -> Option<String> {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|since_epoch| format!("{}.{}", since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+fn decode_time(field: Option<&String>) -> io::Result<Option<SystemTime>> {
+    match field.map(String::as_str) {
+        None => Err(invalid_data("Missing field")),
+        Some(NONE) => Ok(None),
+        Some(value) => {
+            let (seconds, nanos) = value.split_once('.').unwrap_or((value, "0"));
+            match (seconds.parse::<u64>(), nanos.parse::<u32>()) {
+                (Ok(seconds), Ok(nanos)) => {
+                    Ok(Some(SystemTime::UNIX_EPOCH + Duration::new(seconds, nanos)))
+                }
+                _ => Err(invalid_data("Malformed time")),
+            }
+        }
+    }
+}
+
+fn encode_entry(entry: &FolderEntry) -> Vec<String> {
+    let kind = match entry.kind {
+        FolderEntryType::Folder => "D",
+        _ => "F",
+    };
+    vec![
+        String::from("ENTRY"),
+        String::from(kind),
+        entry.title.to_string(),
+        encode_optional(entry.size()),
+        encode_optional(entry.owner().map(|owner| owner.uid)),
+        encode_optional(entry.owner().map(|owner| owner.gid)),
+        encode_optional(entry.modified().and_then(encode_time)),
+    ]
+}
+
+fn decode_entry(fields: &[String]) -> io::Result<FolderEntry> {
+    let kind = match fields.get(1).map(String::as_str) {
+        Some("D") => FolderEntryType::Folder,
+        Some("F") => FolderEntryType::File,
+        _ => return Err(invalid_data("Unknown entry kind")),
+    };
+    let title = fields
+        .get(2)
+        .map(|title| Name::from(title.as_str()))
+        .ok_or_else(|| invalid_data("Missing title"))?;
+    let uid: Option<u32> = decode_optional(fields.get(4))?;
+    let gid: Option<u32> = decode_optional(fields.get(5))?;
+
+    let mut entry = FolderEntry::new(kind, title);
+    entry.set_size(decode_optional(fields.get(3))?);
+    entry.set_owner(uid.map(|uid| Owner {
+        uid,
+        gid: gid.unwrap_or(0),
+    }));
+    entry.set_modified(decode_time(fields.get(6))?);
+    Ok(entry)
+}
+
+fn encode_filesystem(filesystem: &FileSystem) -> Vec<String> {
+    vec![
+        String::from("FS"),
+        filesystem.device.clone(),
+        filesystem.mount_point.to_string_lossy().into_owned(),
+        filesystem.fs_type.clone(),
+        filesystem.total.to_string(),
+        filesystem.used.to_string(),
+        filesystem.free.to_string(),
+    ]
+}
+
+fn decode_filesystem(fields: &[String]) -> io::Result<FileSystem> {
+    let text = |index: usize| {
+        fields
+            .get(index)
+            .cloned()
+            .ok_or_else(|| invalid_data("Missing field"))
+    };
+
+    Ok(FileSystem {
+        device: text(1)?,
+        mount_point: PathBuf::from(text(2)?),
+        fs_type: text(3)?,
+        total: decode_required(fields.get(4))?,
+        used: decode_required(fields.get(5))?,
+        free: decode_required(fields.get(6))?,
+    })
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -1,21 +1,82 @@
 #[cfg(test)]
 mod tests {
-    const TEST_FILE_PATH_VIEW: &str = "./tests/view";
-    const TEST_FILE_PATH_EDIT: &str = "./tests/edit";
+    const TEST_FILE_PATH_VIEW: &str = "/view";
+    const TEST_FILE_PATH_EDIT: &str = "/edit";
+    const TEST_FILE_SIZE: usize = 446;
+    use crate::events::key_press;
     use crate::fs::{FolderEntry, FolderEntryType};
-    use crate::App;
+    use crate::{App, MemoryStorage};
+    use crossterm::event::KeyCode;
+
+    /// Mirrors `tests/view` fixture
+    fn view_storage() -> MemoryStorage {
+        let storage = MemoryStorage::new();
+        storage.add_file("/view/a_folder/folder1_file1.txt", 459);
+        storage.add_file("/view/a_folder/folder1_file2.txt", 917);
+        storage.add_file("/view/a_root_file.txt", 459);
+        storage.add_file("/view/b_folder/folder2_file1.txt", 1375);
+        storage.add_file("/view/b_folder/folder2_file2.txt", 1833);
+        storage.add_file("/view/b_folder/folder2_file3.txt", 2749);
+        storage.add_file("/view/c_folder/folder2_file1.txt", 1375);
+        storage.add_file("/view/c_folder/folder2_file2.txt", 1833);
+        storage.add_file("/view/c_folder/folder2_file3.txt", 2749);
+        storage.add_file("/view/d_root_file.txt", 916);
+        storage.add_file("/view/z_root_file.txt", 459);
+        storage
+    }
 
-    fn setup_app_view() -> App {
-        let mut app = App::new();
-        app.init(Some(TEST_FILE_PATH_VIEW.to_string()));
+    /// - folder_to_delete_1
+    ///     - folder_to_delete_2
+    ///         - folder_to_delete_3
+    ///         - file_to_delete_1
+    ///         - file_to_delete_2
+    ///         - file_to_delete_3
+    ///     - file_to_delete_1
+    ///     - file_to_delete_2
+    ///     - file_to_delete_3
+    /// - file_to_delete_1
+    /// - file_to_delete_2
+    /// - file_to_delete_3
+    fn edit_storage() -> MemoryStorage {
+        let storage = MemoryStorage::new();
+        let mut folder_path = String::from(TEST_FILE_PATH_EDIT);
+
+        for folder_index in 1..4 {
+            for file_index in 1..4 {
+                let file_path = format!("{}/file_to_delete_{}.txt", folder_path, file_index);
+                storage.add_file(&file_path, TEST_FILE_SIZE as u64);
+            }
+
+            folder_path = format!("{}/folder_to_delete_{}", folder_path, folder_index);
+            storage.add_folder(&folder_path);
+        }
+
+        storage
+    }
+
+    fn setup_app(storage: &MemoryStorage, path: &str) -> App {
+        let mut app = App::with_storage(Box::new(storage.clone()));
+        app.init(Some(path.to_string()));
         app.ui_config.open_file = false;
         app
     }
 
+    fn setup_app_view() -> App {
+        setup_app(&view_storage(), TEST_FILE_PATH_VIEW)
+    }
+
     fn setup_app_edit() -> App {
-        let mut app = App::new();
-        app.init(Some(TEST_FILE_PATH_EDIT.to_string()));
-        app
+        setup_app(&edit_storage(), TEST_FILE_PATH_EDIT)
+    }
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_event(key_press(code));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
     }
 
     fn assert_item_at_index_is(app: &App, index: usize, kind: FolderEntryType) {
@@ -118,7 +179,7 @@ mod tests {
         fn test_switching_ordering_to_size() {
             let mut app = setup_app_view();
 
-            app.on_toggle_sorting();
+            press(&mut app, KeyCode::Char('s'));
 
             assert_root_view_folder_sorted_by_size(&app);
         }
@@ -127,9 +188,9 @@ mod tests {
         fn test_ordering_persists_after_navigating_into_folder() {
             let mut app = setup_app_view();
 
-            app.on_toggle_sorting();
-            app.on_cursor_down();
-            app.on_enter();
+            press(&mut app, KeyCode::Char('s'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
 
             assert_item_at_index_title(&app, 0, "..".to_string());
             assert_item_at_index_title(&app, 1, "folder2_file3.txt".to_string());
@@ -141,10 +202,10 @@ mod tests {
         fn test_ordering_persists_after_navigating_to_parent() {
             let mut app = setup_app_view();
 
-            app.on_cursor_down();
-            app.on_enter();
-            app.on_toggle_sorting();
-            app.on_enter();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+            press(&mut app, KeyCode::Char('s'));
+            press(&mut app, KeyCode::Enter);
 
             assert_root_view_folder_sorted_by_size(&app);
         }
@@ -153,8 +214,8 @@ mod tests {
         fn test_switching_ordering_back_to_title() {
             let mut app = setup_app_view();
 
-            app.on_toggle_sorting();
-            app.on_toggle_sorting();
+            press(&mut app, KeyCode::Char('s'));
+            press(&mut app, KeyCode::Char('s'));
 
             assert_root_view_folder_sorted_by_title(&app);
         }
@@ -163,9 +224,7 @@ mod tests {
         fn has_correct_amount_file_tree_keys() {
             let app = setup_app_view();
 
-            let file_tree = app.file_tree_map;
-
-            assert_eq!(file_tree.keys().len(), 4);
+            assert_eq!(app.tree.iter().count(), 4);
         }
     }
 
@@ -176,26 +235,26 @@ mod tests {
         fn updates_cursor_position() {
             let mut app = setup_app_view();
 
-            assert_cursor_index(&mut app, 0);
+            assert_cursor_index(&app, 0);
 
-            app.on_cursor_down();
-            assert_cursor_index(&mut app, 1);
+            press(&mut app, KeyCode::Down);
+            assert_cursor_index(&app, 1);
 
-            app.on_cursor_up();
-            assert_cursor_index(&mut app, 0);
+            press(&mut app, KeyCode::Up);
+            assert_cursor_index(&app, 0);
         }
 
         #[test]
         fn stops_cursor_at_very_top() {
             let mut app = setup_app_view();
 
-            assert_cursor_index(&mut app, 0);
+            assert_cursor_index(&app, 0);
 
             for _ in 0..10 {
-                app.on_cursor_up();
+                press(&mut app, KeyCode::Up);
             }
 
-            assert_cursor_index(&mut app, 0);
+            assert_cursor_index(&app, 0);
         }
 
         #[test]
@@ -203,9 +262,9 @@ mod tests {
             let mut app = setup_app_view();
 
             for _ in 0..20 {
-                app.on_cursor_down();
+                press(&mut app, KeyCode::Down);
             }
-            assert_cursor_index(&mut app, 6);
+            assert_cursor_index(&app, 6);
         }
     }
 
@@ -216,8 +275,8 @@ mod tests {
         fn updates_current_tree_when_enters_subfolder() {
             let mut app = setup_app_view();
 
-            app.on_cursor_down();
-            app.on_enter();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
 
             assert_cursor_index(&app, 0);
             assert_parent_folder_a_state(&app);
@@ -227,12 +286,12 @@ mod tests {
         fn navigates_back_to_parent_folder() {
             let mut app = setup_app_view();
 
-            app.on_cursor_down();
-            app.on_enter();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
 
             assert_parent_folder_a_state(&app);
 
-            app.on_enter();
+            press(&mut app, KeyCode::Enter);
             assert_parent_folder_state(&app);
             assert_cursor_index(&app, 1);
         }
@@ -241,303 +300,2273 @@ mod tests {
         fn does_nothing_when_tries_to_enter_file() {
             let mut app = setup_app_view();
 
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_cursor_down();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
             assert_cursor_index(&app, 5);
 
-            app.on_enter();
+            press(&mut app, KeyCode::Enter);
 
             assert_cursor_index(&app, 5);
             assert_parent_folder_state(&app);
         }
     }
 
-    mod delete {
+    mod owners {
         use super::*;
-        use std::fs::{self, File};
-        use std::io::Write;
-
-        const TEST_FILE_SIZE: usize = 446;
-
-        fn generate_lorem_ipsum() -> String {
-            String::from(
-                "Lorem ipsum dolor sit amet, consectetur adipiscing elit. \
-    Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. \
-    Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi \
-    ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit \
-    in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur \
-    sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt \
-    mollit anim id est laborum.",
-            )
+        use crate::{Owner, DEFAULT_OWNER};
+        use std::collections::HashSet;
+        use std::path::Path;
+
+        const OTHER_OWNER: Owner = Owner {
+            uid: 2000,
+            gid: 2000,
+        };
+
+        fn setup_app_shared() -> App {
+            let storage = MemoryStorage::new();
+            storage.add_file("/shared/mine.txt", 100);
+            storage.add_file_owned("/shared/theirs.txt", 300, OTHER_OWNER);
+            storage.add_folder_owned("/shared/their_folder", OTHER_OWNER);
+            storage.add_file_owned("/shared/their_folder/data.bin", 500, OTHER_OWNER);
+            storage.add_file("/shared/their_folder/mine.txt", 50);
+            setup_app(&storage, "/shared")
         }
 
-        /// - folder_1
-        ///     - folder_2
-        ///         - folder_3
-        ///         - file_1
-        ///         - file_2
-        ///         - file_3
-        ///     - file_1
-        ///     - file_2
-        ///     - file_3
-        /// - file_1
-        /// - file_2
-        /// - file_3
-        fn create_testing_files() {
-            fs::create_dir_all(TEST_FILE_PATH_EDIT).expect("Failed to create test folder");
-
-            let mut folder_path = format!("{}", TEST_FILE_PATH_EDIT);
-
-            for folder_index in 1..4 {
-                for file_index in 1..4 {
-                    let file_name = format!("file_to_delete_{}.txt", file_index);
-                    let file_path = format!("{}/{}", folder_path, file_name);
-                    let mut file = File::create(&file_path).expect("Failed to create test file");
-                    writeln!(file, "{}", generate_lorem_ipsum())
-                        .expect("Failed to write to test file");
-                }
-
-                folder_path = format!("{}/folder_to_delete_{}", folder_path, folder_index);
+        #[test]
+        fn collects_owner_of_every_entry() {
+            let app = setup_app_view();
 
-                fs::create_dir_all(&folder_path).expect("Failed to create test folder");
+            for entry in app.get_current_folder().unwrap().entries.iter().skip(1) {
+                assert_eq!(entry.owner(), Some(DEFAULT_OWNER));
             }
         }
 
-        fn cleanup_testing_files() {
-            if let Err(err) = fs::remove_dir_all(TEST_FILE_PATH_EDIT) {
-                eprintln!("Failed to remove test folder: {}", err);
-            }
+        #[test]
+        fn aggregates_subtree_usage_per_owner() {
+            let app = setup_app_shared();
+            let folder = app.get_current_folder().unwrap();
+
+            assert_eq!(folder.owners.len(), 2);
+            assert_eq!(folder.owners.get(&DEFAULT_OWNER.uid), Some(&150));
+            assert_eq!(folder.owners.get(&OTHER_OWNER.uid), Some(&800));
         }
 
         #[test]
-        fn has_correct_initial_state() {
-            create_testing_files();
-            let app = setup_app_edit();
-            assert_delete_folder_state(&app);
-            cleanup_testing_files();
+        fn cycles_owner_filter() {
+            let mut app = setup_app_shared();
+
+            press(&mut app, KeyCode::Char('u'));
+            assert_eq!(app.ui_config.owner_filter, Some(DEFAULT_OWNER.uid));
+
+            press(&mut app, KeyCode::Char('u'));
+            assert_eq!(app.ui_config.owner_filter, Some(OTHER_OWNER.uid));
+
+            press(&mut app, KeyCode::Char('u'));
+            assert_eq!(app.ui_config.owner_filter, None);
         }
 
         #[test]
-        fn does_nothing_when_cursor_is_at_the_top() {
-            create_testing_files();
-            let mut app = setup_app_edit();
+        fn cursor_skips_entries_of_other_owners() {
+            let mut app = setup_app_shared();
+            press(&mut app, KeyCode::Char('u'));
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            assert_item_at_index_title(
+                &app,
+                app.current_folder().unwrap().cursor_index,
+                "mine.txt".to_string(),
+            );
+
+            press(&mut app, KeyCode::Down);
+            assert_item_at_index_title(
+                &app,
+                app.current_folder().unwrap().cursor_index,
+                "mine.txt".to_string(),
+            );
+        }
+
+        #[test]
+        fn snaps_cursor_to_parent_when_selected_entry_is_filtered_out() {
+            let mut app = setup_app_shared();
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            assert_item_at_index_title(&app, 2, "mine.txt".to_string());
+            press(&mut app, KeyCode::Char('u'));
+            assert_cursor_index(&app, 2);
+            press(&mut app, KeyCode::Char('u'));
+
             assert_cursor_index(&app, 0);
-            assert_delete_folder_state(&app);
-            app.on_delete();
-            app.on_delete();
-            assert_delete_folder_state(&app);
-            cleanup_testing_files();
         }
 
         #[test]
-        fn does_nothing_when_delete_pressed_once() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            assert_delete_folder_state(&app);
-            app.on_cursor_down();
-            app.on_delete();
-            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 3);
+        fn filters_folders_by_usage_below_them() {
+            let storage = MemoryStorage::new();
+            storage.add_folder_owned("/home/root_owned", Owner { uid: 0, gid: 0 });
+            storage.add_file("/home/root_owned/mine.txt", 100);
+            storage.add_folder("/home/my_folder");
+            storage.add_file_owned("/home/my_folder/theirs.txt", 300, OTHER_OWNER);
+            let mut app = setup_app(&storage, "/home");
+            press(&mut app, KeyCode::Char('u'));
+            assert_eq!(app.ui_config.owner_filter, Some(DEFAULT_OWNER.uid));
+
+            let id = app.tree.find(Path::new("/home")).unwrap();
+            let rows = app
+                .tree
+                .rows(id, &HashSet::new(), app.ui_config.owner_filter);
+            let titles: Vec<&str> = rows.iter().map(|row| &*row.title).collect();
+            assert_eq!(titles, vec!["root_owned"]);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            assert_item_at_index_title(
+                &app,
+                app.current_folder().unwrap().cursor_index,
+                "root_owned".to_string(),
+            );
+        }
+    }
+
+    mod filesystems {
+        use super::*;
+        use crate::ui::View;
+        use crate::FileSystem;
+        use std::path::{Path, PathBuf};
+
+        fn test_filesystem(mount_point: &str) -> FileSystem {
+            FileSystem {
+                device: String::from("test"),
+                mount_point: PathBuf::from(mount_point),
+                fs_type: String::from("test"),
+                total: 1000,
+                used: 400,
+                free: 600,
+            }
+        }
+
+        #[test]
+        fn detects_filesystem_of_current_folder() {
+            let storage = view_storage();
+            storage.add_filesystem(test_filesystem("/"));
+            storage.add_filesystem(test_filesystem("/view"));
+            let app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+
+            assert_eq!(app.filesystem, Some(test_filesystem("/view")));
+        }
+
+        #[test]
+        fn opens_and_closes_filesystems_view() {
+            let storage = view_storage();
+            storage.add_filesystem(test_filesystem("/"));
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+
+            press(&mut app, KeyCode::Char('f'));
+            assert_eq!(app.ui_config.view, View::Filesystems);
+            assert_eq!(app.filesystems.len(), 1);
+
+            press(&mut app, KeyCode::Char('f'));
+            assert_eq!(app.ui_config.view, View::Folder);
+        }
+
+        #[test]
+        fn inspects_selected_filesystem_from_its_mount_point() {
+            let storage = view_storage();
+            storage.add_filesystem(test_filesystem("/view"));
+            storage.add_filesystem(test_filesystem("/view/a_folder"));
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+
+            press(&mut app, KeyCode::Char('f'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+
+            assert_eq!(app.ui_config.view, View::Folder);
+            assert_eq!(app.current_path(), &PathBuf::from("/view/a_folder"));
+            assert_parent_folder_a_state(&app);
+        }
+
+        #[test]
+        fn picks_longest_matching_mount_point() {
+            let mount = |point: &str, device: &str| {
+                (
+                    String::from(device),
+                    PathBuf::from(point),
+                    String::from("test"),
+                )
+            };
+            let mounts = vec![
+                mount("/", "root"),
+                mount("/data", "data"),
+                mount("/data/cache", "cache"),
+                mount("/data", "overlay"),
+                mount("/database", "database"),
+            ];
+
+            let found = crate::mount_for_path(mounts.clone(), Path::new("/data/logs"));
+            assert_eq!(found, Some(mount("/data", "overlay")));
+
+            let found = crate::mount_for_path(mounts.clone(), Path::new("/data/cache/x"));
+            assert_eq!(found, Some(mount("/data/cache", "cache")));
+
+            let found = crate::mount_for_path(mounts, Path::new("/home"));
+            assert_eq!(found, Some(mount("/", "root")));
+        }
+    }
+
+    mod guard {
+        use super::*;
+        use crate::fs::Storage;
+        use std::path::{Path, PathBuf};
+
+        /// - folder
+        ///     - file.txt
+        /// - file.txt
+        fn setup_app_guard() -> (App, MemoryStorage) {
+            let storage = MemoryStorage::new();
+            storage.add_file("/guard/folder/file.txt", 14);
+            storage.add_file("/guard/file.txt", 12);
+            (setup_app(&storage, "/guard"), storage)
+        }
+
+        #[test]
+        fn refuses_to_delete_protected_path() {
+            let (mut app, storage) = setup_app_guard();
+            app.delete_guard
+                .protected_paths
+                .push(PathBuf::from("/guard/folder"));
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert!(app.ui_config.message.is_some());
             assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 1);
-            cleanup_testing_files();
+            assert!(storage.exists(Path::new("/guard/folder")));
         }
 
         #[test]
-        fn resets_delete_confirmation_on_cursor_move() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            app.on_delete();
-            app.on_cursor_down();
-            assert_eq!(app.ui_config.confirming_deletion, false);
-            app.on_delete();
-            app.on_cursor_up();
-            assert_eq!(app.ui_config.confirming_deletion, false);
-            cleanup_testing_files();
+        fn refuses_to_delete_start_directory_parents() {
+            let (app, storage) = setup_app_guard();
+
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/"), &app.start_path)
+                .is_some());
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/guard"), &app.start_path)
+                .is_some());
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/guard/folder"), &app.start_path)
+                .is_none());
         }
 
         #[test]
-        fn resets_delete_confirmation_on_folder_enter() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_enter();
-            assert_eq!(app.ui_config.confirming_deletion, false);
-            cleanup_testing_files();
+        fn refuses_to_delete_inside_system_paths() {
+            let (app, storage) = setup_app_guard();
+
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/usr/lib"), &app.start_path)
+                .is_some());
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/etc/hosts"), &app.start_path)
+                .is_some());
+            assert!(app
+                .delete_guard
+                .protection_reason(&storage, Path::new("/usrdata"), &app.start_path)
+                .is_none());
         }
 
         #[test]
-        fn resets_delete_confirmation_after_deleting_folder() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
-            assert_eq!(app.ui_config.confirming_deletion, false);
-            cleanup_testing_files();
+        fn refuses_to_delete_repository_root() {
+            let (mut app, storage) = setup_app_guard();
+            storage.add_folder("/guard/folder/.git");
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert!(app.ui_config.message.is_some());
+            assert!(storage.exists(Path::new("/guard/folder")));
         }
 
         #[test]
-        fn resets_delete_confirmation_after_deleting_file() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
+        fn logs_instead_of_deleting_in_read_only_mode() {
+            let (mut app, storage) = setup_app_guard();
+            app.delete_guard.read_only = true;
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(app.dry_run_log().len(), 1);
+            assert!(app.dry_run_log()[0].ends_with("/guard/file.txt (12 bytes)"));
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 1);
+            assert!(storage.exists(Path::new("/guard/file.txt")));
             assert_eq!(app.ui_config.confirming_deletion, false);
-            cleanup_testing_files();
         }
 
         #[test]
-        fn deletes_folder() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            assert_delete_folder_state(&app);
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
-            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 3);
+        fn requires_typed_name_for_large_permanent_deletion() {
+            let (mut app, storage) = setup_app_guard();
+            app.ui_config.move_to_trash = false;
+            app.delete_guard.confirm_size_threshold = 1;
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(app.ui_config.typed_confirmation, Some(String::new()));
+            assert!(storage.exists(Path::new("/guard/folder")));
+
+            type_text(&mut app, "folder");
+            press(&mut app, KeyCode::Enter);
+
+            assert_eq!(app.ui_config.typed_confirmation, None);
             assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 0);
-            cleanup_testing_files();
+            assert!(!storage.exists(Path::new("/guard/folder")));
         }
 
         #[test]
-        fn deletes_file() {
-            create_testing_files();
-            let mut app = setup_app_edit();
-            assert_delete_folder_state(&app);
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
-            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 2);
-            assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 1);
-            cleanup_testing_files();
+        fn keeps_entry_when_typed_name_does_not_match() {
+            let (mut app, storage) = setup_app_guard();
+            app.ui_config.move_to_trash = false;
+            app.delete_guard.confirm_size_threshold = 1;
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            type_text(&mut app, "x");
+            press(&mut app, KeyCode::Enter);
+
+            assert!(app.ui_config.message.is_some());
+            assert!(storage.exists(Path::new("/guard/folder")));
+        }
+    }
+
+    mod cleanable {
+        use super::*;
+        use crate::fs::{find_cleanables, Storage};
+        use crate::ui::View;
+        use std::path::{Path, PathBuf};
+        use std::time::{Duration, SystemTime};
+
+        /// - rust
+        ///     - Cargo.toml
+        ///     - target
+        /// - web
+        ///     - package.json
+        ///     - node_modules
+        ///         - dep
+        ///             - package.json
+        ///             - node_modules
+        /// - notes
+        ///     - target
+        fn setup_app_cleanable() -> (App, MemoryStorage) {
+            let storage = MemoryStorage::new();
+            storage.add_file("/projects/rust/Cargo.toml", 100);
+            storage.add_file("/projects/rust/target/debug/app", 5000);
+            storage.add_file("/projects/web/package.json", 200);
+            storage.add_file("/projects/web/node_modules/dep/package.json", 300);
+            storage.add_file("/projects/web/node_modules/dep/node_modules/x.js", 700);
+            storage.add_file("/projects/notes/target/plan.txt", 50);
+            (setup_app(&storage, "/projects"), storage)
+        }
+
+        fn cleanable_paths(app: &App) -> Vec<PathBuf> {
+            app.cleanables
+                .iter()
+                .map(|cleanable| cleanable.path.clone())
+                .collect()
         }
 
         #[test]
-        fn updated_current_folder_size() {
-            create_testing_files();
-            let mut app = setup_app_edit();
+        fn finds_folders_next_to_their_marker_file() {
+            let (mut app, _) = setup_app_cleanable();
 
-            let root_entry = app.get_current_folder().unwrap();
-            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+            press(&mut app, KeyCode::Char('C'));
 
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
+            assert_eq!(app.ui_config.view, View::Cleanable);
+            assert_eq!(
+                cleanable_paths(&app),
+                vec![
+                    PathBuf::from("/projects/rust/target"),
+                    PathBuf::from("/projects/web/node_modules"),
+                ]
+            );
+            assert_eq!(app.cleanables[0].preset, "Cargo");
+            assert_eq!(app.cleanables[1].size, 1000);
+        }
 
-            let root_entry_updated = app.get_current_folder().unwrap();
-            assert_eq!(root_entry_updated.get_size(), (TEST_FILE_SIZE * 8) as u64);
+        #[test]
+        fn takes_last_use_from_newest_file_inside() {
+            let (_, storage) = setup_app_cleanable();
+            let newest = SystemTime::UNIX_EPOCH + Duration::from_secs(2000);
+            storage.set_modified(
+                "/projects/rust/target/debug/app",
+                SystemTime::UNIX_EPOCH + Duration::from_secs(1000),
+            );
+            storage.set_modified("/projects/rust/target/debug/app.d", newest);
+            storage.add_file("/projects/rust/target/debug/app.d", 10);
+            let app = setup_app(&storage, "/projects");
+
+            let cleanables = find_cleanables(&app.tree);
 
-            cleanup_testing_files();
+            assert_eq!(cleanables[0].last_used, Some(newest));
         }
 
         #[test]
-        fn deleting_file_updates_parent_folders_sizes() {
-            create_testing_files();
-            let mut app = setup_app_edit();
+        fn toggles_move_to_trash_in_cleanable_view() {
+            let (mut app, _) = setup_app_cleanable();
+            let move_to_trash = app.ui_config.move_to_trash;
 
-            let root_entry = app.get_current_folder().unwrap();
-            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+            press(&mut app, KeyCode::Char('C'));
+            press(&mut app, KeyCode::Char('t'));
 
-            app.on_cursor_down();
-            app.on_enter();
+            assert_eq!(app.ui_config.view, View::Cleanable);
+            assert_eq!(app.ui_config.move_to_trash, !move_to_trash);
+        }
 
-            let folder_1 = app.get_current_folder().unwrap();
-            assert_eq!(folder_1.get_size(), (TEST_FILE_SIZE * 6) as u64);
+        #[test]
+        fn purges_selected_folders_and_updates_sizes() {
+            let (mut app, storage) = setup_app_cleanable();
+            app.ui_config.move_to_trash = false;
+
+            press(&mut app, KeyCode::Char('C'));
+            press(&mut app, KeyCode::Char('a'));
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert!(!storage.exists(Path::new("/projects/rust/target")));
+            assert!(!storage.exists(Path::new("/projects/web/node_modules")));
+            assert!(storage.exists(Path::new("/projects/notes/target")));
+            assert!(app.cleanables.is_empty());
+            assert!(app
+                .tree
+                .find(Path::new("/projects/web/node_modules/dep"))
+                .is_none());
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 350);
+        }
 
-            app.on_cursor_down();
-            app.on_enter();
+        #[test]
+        fn purges_only_selected_folders() {
+            let (mut app, storage) = setup_app_cleanable();
+            app.ui_config.move_to_trash = false;
+
+            press(&mut app, KeyCode::Char('C'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char(' '));
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert!(storage.exists(Path::new("/projects/rust/target")));
+            assert!(!storage.exists(Path::new("/projects/web/node_modules")));
+            assert_eq!(
+                cleanable_paths(&app),
+                vec![PathBuf::from("/projects/rust/target")]
+            );
+        }
 
-            let folder_2 = app.get_current_folder().unwrap();
-            assert_eq!(folder_2.get_size(), (TEST_FILE_SIZE * 3) as u64);
+        #[test]
+        fn requires_typed_confirmation_for_large_purge() {
+            let (mut app, storage) = setup_app_cleanable();
+            app.ui_config.move_to_trash = false;
+            app.delete_guard.confirm_size_threshold = 1;
 
-            app.on_cursor_down();
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
+            press(&mut app, KeyCode::Char('C'));
+            press(&mut app, KeyCode::Char(' '));
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
 
-            let folder_2_upd = app.get_current_folder().unwrap();
-            assert_eq!(folder_2_upd.get_size(), (TEST_FILE_SIZE * 2) as u64);
+            assert_eq!(app.ui_config.typed_confirmation, Some(String::new()));
+            assert!(storage.exists(Path::new("/projects/rust/target")));
 
-            app.on_cursor_up();
-            app.on_cursor_up();
-            app.on_enter();
+            type_text(&mut app, "purge");
+            press(&mut app, KeyCode::Enter);
 
-            let folder_1_upd = app.get_current_folder().unwrap();
-            assert_eq!(folder_1_upd.get_size(), (TEST_FILE_SIZE * 5) as u64);
+            assert!(!storage.exists(Path::new("/projects/rust/target")));
+        }
+
+        #[test]
+        fn logs_instead_of_purging_in_read_only_mode() {
+            let (mut app, storage) = setup_app_cleanable();
+            app.delete_guard.read_only = true;
+
+            press(&mut app, KeyCode::Char('C'));
+            press(&mut app, KeyCode::Char('a'));
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(app.dry_run_log().len(), 2);
+            assert!(storage.exists(Path::new("/projects/rust/target")));
+            assert_eq!(app.cleanables.len(), 2);
+        }
+    }
+
+    mod config {
+        use crate::config::{parse_size, Config, RemoteAgent};
+
+        fn build(args: &[&str]) -> Result<Config, &'static str> {
+            Config::build(
+                std::iter::once("space_inspector")
+                    .chain(args.iter().cloned())
+                    .map(String::from),
+            )
+        }
+
+        #[test]
+        fn parses_guard_options() {
+            let config = build(&["--read-only", "--protect", "/data", "some/path"]).unwrap();
+
+            assert!(config.read_only);
+            assert_eq!(config.protected_paths.len(), 1);
+            assert_eq!(config.file_path, Some(String::from("some/path")));
+        }
+
+        #[test]
+        fn parses_size_suffixes() {
+            assert_eq!(parse_size("512"), Ok(512));
+            assert_eq!(parse_size("2K"), Ok(2048));
+            assert_eq!(parse_size("1.5g"), Ok(1024 * 1024 * 1024 * 3 / 2));
+            assert!(parse_size("lots").is_err());
+        }
+
+        #[test]
+        fn fails_on_missing_option_value() {
+            assert!(build(&["--confirm-above"]).is_err());
+        }
+
+        #[test]
+        fn parses_remote_options() {
+            assert!(build(&["--serve"]).unwrap().serve);
             assert_eq!(
-                folder_1_upd.get_selected_entry_size(),
-                (TEST_FILE_SIZE * 2) as u64
+                build(&["--ssh", "backup; rm -rf ~", "/srv"])
+                    .unwrap()
+                    .remote,
+                Some(RemoteAgent::Ssh(String::from("backup; rm -rf ~")))
+            );
+            assert_eq!(
+                build(&["--remote", "./agent --serve"]).unwrap().remote,
+                Some(RemoteAgent::Command(String::from("./agent --serve")))
+            );
+            assert!(build(&["--ssh"]).is_err());
+            assert!(build(&["--ssh", "-oProxyCommand=touch /tmp/x"]).is_err());
+        }
+
+        #[test]
+        fn parses_bookmarks_file() {
+            assert_eq!(
+                build(&["--bookmarks-file", "/tmp/marks"])
+                    .unwrap()
+                    .bookmarks_file,
+                Some(std::path::PathBuf::from("/tmp/marks"))
             );
+            assert!(build(&["--bookmarks-file"]).is_err());
+        }
+
+        #[test]
+        fn parses_report_flag() {
+            assert!(build(&["--report", "/srv"]).unwrap().report);
+            assert!(!build(&["/srv"]).unwrap().report);
+        }
+    }
 
-            app.on_cursor_up();
-            app.on_enter();
+    mod remote {
+        use super::*;
+        use crate::fs::Storage;
+        use crate::remote::{serve, RemoteStorage};
+        use crate::FileSystem;
+        use std::io::{self, BufReader, Cursor};
+        use std::path::{Path, PathBuf};
+        use std::thread;
+        use std::time::{Duration, SystemTime};
+
+        /// Runs agent over `build()` in a thread, connected with a pair of pipes
+        fn connect(build: fn() -> MemoryStorage) -> RemoteStorage {
+            let (request_reader, request_writer) = io::pipe().unwrap();
+            let (response_reader, response_writer) = io::pipe().unwrap();
+            thread::spawn(move || {
+                let storage = build();
+                serve(&storage, BufReader::new(request_reader), response_writer).unwrap();
+            });
+            RemoteStorage::new(response_reader, request_writer).unwrap()
+        }
+
+        fn tricky_storage() -> MemoryStorage {
+            let storage = MemoryStorage::new();
+            storage.add_file_owned(
+                "/data/tab\tand\nnewline\\.txt",
+                42,
+                crate::Owner { uid: 7, gid: 8 },
+            );
+            storage.set_modified(
+                "/data/tab\tand\nnewline\\.txt",
+                SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+            );
+            storage.add_folder("/data/-");
+            storage.add_filesystem(FileSystem {
+                device: String::from("/dev/sda1"),
+                mount_point: PathBuf::from("/"),
+                fs_type: String::from("ext4"),
+                total: 1000,
+                used: 400,
+                free: 600,
+            });
+            storage
+        }
+
+        #[test]
+        fn reads_same_tree_as_local_app() {
+            let local = setup_app_view();
+            let mut remote = App::with_storage(Box::new(connect(view_storage)));
+            remote.init(Some(String::from(TEST_FILE_PATH_VIEW)));
 
-            let root_entry_upd = app.get_current_folder().unwrap();
-            assert_eq!(root_entry_upd.get_size(), (TEST_FILE_SIZE * 8) as u64);
             assert_eq!(
-                root_entry_upd.get_selected_entry_size(),
-                (TEST_FILE_SIZE * 5) as u64
+                remote.current_folder().unwrap().entries,
+                local.current_folder().unwrap().entries
+            );
+            assert_eq!(
+                remote.current_folder().unwrap().get_size(),
+                local.current_folder().unwrap().get_size()
             );
+        }
 
-            cleanup_testing_files();
+        #[test]
+        fn transfers_entries_with_escaped_names() {
+            let remote = connect(tricky_storage);
+
+            let mut entries = remote.read_dir(Path::new("/data")).unwrap();
+            entries.sort();
+
+            assert_eq!(entries, {
+                let mut expected = tricky_storage().read_dir(Path::new("/data")).unwrap();
+                expected.sort();
+                expected
+            });
         }
 
         #[test]
-        fn deleting_folder_updates_parent_folders_sizes() {
-            create_testing_files();
-            let mut app = setup_app_edit();
+        fn transfers_filesystems() {
+            let remote = connect(tricky_storage);
 
-            let root_entry = app.get_current_folder().unwrap();
-            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+            assert_eq!(
+                remote.filesystem_for_path(Path::new("/data")),
+                tricky_storage().filesystem_for_path(Path::new("/data"))
+            );
+            assert_eq!(remote.mounted_filesystems().len(), 1);
+            assert_eq!(remote.current_dir().unwrap(), PathBuf::from("/"));
+        }
 
-            app.on_cursor_down();
-            app.on_enter();
+        #[test]
+        fn deletes_through_agent() {
+            let remote = connect(view_storage);
+            let path = PathBuf::from("/view/a_folder");
 
-            let folder_1 = app.get_current_folder().unwrap();
-            assert_eq!(folder_1.get_size(), (TEST_FILE_SIZE * 6) as u64);
+            assert!(remote.exists(&path));
+            remote.delete_folder(&path, false).unwrap();
 
-            app.on_cursor_down();
-            app.on_delete();
-            app.on_delete();
+            assert!(!remote.exists(&path));
+            assert!(remote.read_dir(&path).is_err());
+        }
 
-            let folder_1_upd = app.get_current_folder().unwrap();
-            assert_eq!(folder_1_upd.get_size(), (TEST_FILE_SIZE * 3) as u64);
+        #[cfg(unix)]
+        #[test]
+        fn scans_past_symlink_cycles() {
+            let root =
+                std::env::temp_dir().join(format!("space_inspector_cycle_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(root.join("a")).unwrap();
+            std::os::unix::fs::symlink("..", root.join("a/link")).unwrap();
+            let request = format!("SCAN\t{}\n", root.display());
+
+            let mut output = vec![];
+            serve(&crate::LocalStorage, Cursor::new(request), &mut output).unwrap();
+            std::fs::remove_dir_all(&root).unwrap();
+
+            let output = String::from_utf8(output).unwrap();
+            let folders = output.lines().filter(|line| line.starts_with("DIR\t"));
+            assert_eq!(folders.count(), 2);
+            assert!(output.contains("ENTRY\tF\tlink\t"));
+            assert!(output.ends_with("OK\n"));
+        }
+
+        #[test]
+        fn reads_scanned_folders_without_further_requests() {
+            // Agent answering HELLO and a single SCAN, then gone
+            let agent_output = Cursor::new(
+                b"OK\t2\n\
+                DIR\t/data\n\
+                ENTRY\tD\tlogs\t-\t-\t-\t-\n\
+                ENTRY\tD\tlocked\t-\t-\t-\t-\n\
+                DIR\t/data/logs\n\
+                ENTRY\tF\tapp.log\t12\t-\t-\t-\n\
+                DIR_ERR\t/data/locked\tPermission denied\n\
+                OK\n"
+                    .to_vec(),
+            );
+            let remote = RemoteStorage::new(agent_output, io::sink()).unwrap();
 
-            app.on_cursor_up();
-            app.on_enter();
+            remote.prefetch(Path::new("/data"));
 
-            let root_entry_upd = app.get_current_folder().unwrap();
-            assert_eq!(root_entry_upd.get_size(), (TEST_FILE_SIZE * 6) as u64);
+            assert_eq!(remote.read_dir(Path::new("/data")).unwrap().len(), 2);
+            let logs = remote.read_dir(Path::new("/data/logs")).unwrap();
+            assert_eq!(logs[0].size(), Some(12));
             assert_eq!(
-                root_entry_upd.get_selected_entry_size(),
-                (TEST_FILE_SIZE * 3) as u64
+                remote
+                    .read_dir(Path::new("/data/locked"))
+                    .unwrap_err()
+                    .to_string(),
+                "Permission denied"
             );
-
-            cleanup_testing_files();
         }
 
         #[test]
-        fn moves_cursor_one_step_up_after_deleting_bottom_entry() {
-            create_testing_files();
-            let mut app = setup_app_edit();
+        fn scans_unreadable_folders_like_local_app() {
+            fn storage() -> MemoryStorage {
+                let storage = view_storage();
+                storage.set_unreadable("/view/b_folder");
+                storage
+            }
+            let mut local = App::with_storage(Box::new(storage()));
+            local.init(Some(String::from(TEST_FILE_PATH_VIEW)));
+            let mut remote = App::with_storage(Box::new(connect(storage)));
+            remote.init(Some(String::from(TEST_FILE_PATH_VIEW)));
 
-            for _ in 1..20 {
-                app.on_cursor_down();
+            assert_eq!(
+                remote.current_folder().unwrap().entries,
+                local.current_folder().unwrap().entries
+            );
+            assert_eq!(remote.scan_stats().errors, 1);
+        }
+
+        #[test]
+        fn protects_home_of_agent() {
+            fn storage() -> MemoryStorage {
+                let storage = view_storage();
+                storage.set_home_dir("/view/a_folder");
+                storage
             }
+            let remote = App::with_storage(Box::new(connect(storage)));
 
-            assert_eq!(app.get_current_folder().unwrap().cursor_index, 4);
-            app.on_delete();
-            app.on_delete();
-            assert_eq!(app.get_current_folder().unwrap().cursor_index, 3);
+            assert!(remote
+                .delete_guard
+                .protected_paths
+                .contains(&PathBuf::from("/view/a_folder")));
+        }
+
+        #[test]
+        fn rejects_agent_of_other_version() {
+            let agent_output = Cursor::new(b"OK\t99\n".to_vec());
 
-            cleanup_testing_files();
+            assert!(RemoteStorage::new(agent_output, io::sink()).is_err());
+        }
+
+        #[test]
+        fn fails_when_agent_exits() {
+            assert!(RemoteStorage::new(io::empty(), io::sink()).is_err());
+        }
+    }
+
+    mod local_storage {
+        use super::*;
+
+        const TEST_FIXTURE_PATH_VIEW: &str = "./tests/view";
+
+        fn setup_app_local() -> App {
+            let mut app = App::new();
+            app.init(Some(TEST_FIXTURE_PATH_VIEW.to_string()));
+            app.ui_config.open_file = false;
+            app
+        }
+
+        #[test]
+        fn reads_same_tree_as_in_memory_fixture() {
+            let local = setup_app_local();
+            let memory = setup_app_view();
+
+            assert_eq!(local.tree.iter().count(), 4);
+            assert_eq!(
+                local.current_folder().unwrap().get_size(),
+                memory.current_folder().unwrap().get_size()
+            );
+            assert_root_view_folder_sorted_by_title(&local);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn collects_owner_from_metadata() {
+            use std::os::unix::fs::MetadataExt;
+
+            let app = setup_app_local();
+            let uid = std::fs::metadata(TEST_FIXTURE_PATH_VIEW).unwrap().uid();
+
+            for entry in app.current_folder().unwrap().entries.iter().skip(1) {
+                assert_eq!(entry.owner().unwrap().uid, uid);
+            }
+        }
+
+        #[test]
+        fn detects_filesystem_of_current_folder() {
+            let app = setup_app_local();
+            let filesystem = app.filesystem.unwrap();
+
+            assert!(filesystem.total > 0);
+            assert!(filesystem.used <= filesystem.total);
+            assert!(app.current_path.starts_with(&filesystem.mount_point));
+        }
+    }
+
+    mod headless {
+        use super::*;
+        use crate::events::ScriptedEvents;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        fn run(app: &mut App, keys: &[KeyCode]) {
+            let terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            app.run_with_events(terminal, &mut ScriptedEvents::new(keys))
+                .unwrap();
+        }
+
+        #[test]
+        fn runs_until_events_are_exhausted() {
+            let mut app = setup_app_view();
+
+            run(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+
+            assert_parent_folder_a_state(&app);
+        }
+
+        #[test]
+        fn stops_on_quit_key() {
+            let mut app = setup_app_view();
+
+            run(
+                &mut app,
+                &[KeyCode::Char('q'), KeyCode::Down, KeyCode::Enter],
+            );
+
+            assert_parent_folder_state(&app);
+        }
+    }
+
+    mod render {
+        use super::*;
+        use ratatui::{backend::TestBackend, buffer::Buffer, style::Color, Terminal};
+
+        const WIDTH: u16 = 140;
+        const HEIGHT: u16 = 20;
+
+        fn render(app: &mut App) -> Buffer {
+            let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+            app.draw(&mut terminal).unwrap();
+            terminal.backend().buffer().clone()
+        }
+
+        fn line(buffer: &Buffer, y: u16) -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect()
+        }
+
+        fn find_line(buffer: &Buffer, text: &str) -> u16 {
+            (0..buffer.area.height)
+                .find(|y| line(buffer, *y).contains(text))
+                .unwrap_or_else(|| panic!("\"{}\" is not rendered", text))
+        }
+
+        fn find_cell(buffer: &Buffer, text: &str) -> (u16, u16) {
+            let y = find_line(buffer, text);
+            let row = line(buffer, y);
+            let x = row.chars().take(row.find(text).unwrap()).count() as u16;
+            (x, y)
+        }
+
+        #[test]
+        fn renders_header_with_folder_name_and_size() {
+            let mut app = setup_app_view();
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, 0).contains("Space inspector | view | 14.77 KB"));
+        }
+
+        #[test]
+        fn renders_stats_popup_over_table() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('i'));
+            let buffer = render(&mut app);
+
+            find_line(&buffer, "Scan statistics");
+            assert!(line(&buffer, find_line(&buffer, "Folders: 4")).contains("Files: 11"));
+            find_line(&buffer, "Slowest folders to enumerate:");
+            find_line(&buffer, "/view/b_folder");
+        }
+
+        #[test]
+        fn renders_jump_prompt_and_bookmarks() {
+            let mut app = setup_app_view();
+            app.bookmarks.paths = vec![
+                std::path::PathBuf::from("/view/b_folder"),
+                std::path::PathBuf::from("/elsewhere"),
+            ];
+            press(&mut app, KeyCode::Char(':'));
+            type_text(&mut app, "/va");
+            let buffer = render(&mut app);
+            find_line(&buffer, "Jump to: /va█");
+            find_line(&buffer, "\"Tab\" - complete");
+
+            press(&mut app, KeyCode::Esc);
+            press(&mut app, KeyCode::Char('B'));
+            let buffer = render(&mut app);
+            assert!(line(&buffer, find_line(&buffer, "/view/b_folder")).contains("5.82 KB"));
+            assert!(line(&buffer, find_line(&buffer, "/elsewhere")).contains("not scanned"));
+        }
+
+        #[test]
+        fn renders_breadcrumb_and_tree_view() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('v'));
+            press(&mut app, KeyCode::Right);
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, 2).contains("/ › view"));
+            let (x, y) = find_cell(&buffer, "▾ a_folder");
+            assert_eq!(find_cell(&buffer, "▸ b_folder").0, x);
+            assert_eq!(find_cell(&buffer, "folder1_file1.txt"), (x + 4, y + 1));
+            assert!(line(&buffer, y).contains("1.34 KB"));
+            find_line(&buffer, "\"Space\" - toggle");
+        }
+
+        #[test]
+        fn renders_entries_sorted_by_title() {
+            let mut app = setup_app_view();
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, find_line(&buffer, "Name")).contains("Name ↓"));
+            assert!(find_line(&buffer, "a_folder") < find_line(&buffer, "b_folder"));
+            assert!(find_line(&buffer, "b_folder") < find_line(&buffer, "c_folder"));
+            assert!(find_line(&buffer, "a_root_file") < find_line(&buffer, "z_root_file"));
+        }
+
+        #[test]
+        fn renders_entries_sorted_by_size() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('s'));
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, find_line(&buffer, "Size")).contains("Size ↓"));
+            assert!(find_line(&buffer, "b_folder") < find_line(&buffer, "a_folder"));
+            assert!(find_line(&buffer, "d_root_file") < find_line(&buffer, "a_root_file"));
+        }
+
+        #[test]
+        fn renders_bars_without_color_by_default() {
+            let mut app = setup_app_view();
+            let buffer = render(&mut app);
+
+            let (x, y) = find_cell(&buffer, "████");
+            assert_ne!(buffer.get(x, y).fg, Color::Rgb(255, 0, 0));
+        }
+
+        #[test]
+        fn renders_colored_bars() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('c'));
+            let buffer = render(&mut app);
+
+            let largest = find_line(&buffer, "b_folder");
+            let row = line(&buffer, largest);
+            let x = row.chars().position(|c| c == '█').unwrap() as u16;
+            assert_eq!(buffer.get(x, largest).fg, Color::Rgb(255, 0, 0));
+
+            let smallest = find_line(&buffer, "z_root_file");
+            let row = line(&buffer, smallest);
+            let x = row.chars().position(|c| c == '█').unwrap() as u16;
+            assert_eq!(buffer.get(x, smallest).fg, Color::Rgb(0, 128, 0));
+        }
+
+        #[test]
+        fn colors_filesystem_bars_only_when_colored() {
+            let storage = view_storage();
+            for (device, mount_point) in [("sda1", "/"), ("sdb1", "/view")] {
+                storage.add_filesystem(crate::FileSystem {
+                    device: String::from(device),
+                    mount_point: std::path::PathBuf::from(mount_point),
+                    fs_type: String::from("test"),
+                    total: 1000,
+                    used: 900,
+                    free: 100,
+                });
+            }
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+            press(&mut app, KeyCode::Char('f'));
+            let text = app.ui_config.theme().text;
+
+            // Filesystem of the current folder is highlighted, the other one isn't
+            let buffer = render(&mut app);
+            let y = find_line(&buffer, "sda1");
+            let x = line(&buffer, y).chars().position(|c| c == '█').unwrap() as u16;
+            assert_eq!(buffer.get(x, y).fg, text);
+
+            app.ui_config.colored = true;
+            let buffer = render(&mut app);
+            assert_ne!(buffer.get(x, y).fg, text);
+        }
+
+        #[test]
+        fn highlights_selected_row() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Down);
+            let buffer = render(&mut app);
+
+            let (x, y) = find_cell(&buffer, "a_folder");
+            assert!(line(&buffer, y).contains(">>>"));
+            assert_eq!(
+                buffer.get(x, y).bg,
+                crate::ui::Theme::dark().selected.bg.unwrap()
+            );
+        }
+
+        #[test]
+        fn highlights_row_pending_deletion() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            let buffer = render(&mut app);
+
+            let (x, y) = find_cell(&buffer, "a_folder");
+            assert_eq!(
+                buffer.get(x, y).bg,
+                crate::ui::Theme::dark().pre_deleted.bg.unwrap()
+            );
+        }
+
+        #[test]
+        fn renders_typed_confirmation_prompt() {
+            let mut app = setup_app_view();
+            app.ui_config.move_to_trash = false;
+            app.delete_guard.confirm_size_threshold = 1;
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            type_text(&mut app, "a_fo");
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, find_line(&buffer, "Permanently delete?"))
+                .contains("Type \"a_folder\" and press Enter (Esc - cancel): a_fo"));
+        }
+
+        #[test]
+        fn renders_owner_column() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('o'));
+            let buffer = render(&mut app);
+
+            assert!(line(&buffer, find_line(&buffer, "Name")).contains("Owner"));
+        }
+
+        #[test]
+        fn renders_percent_and_count_columns() {
+            let mut app = setup_app_view();
+            for key in ['P', 'R', 'F', 'D'] {
+                press(&mut app, KeyCode::Char(key));
+            }
+            let buffer = render(&mut app);
+
+            let header = line(&buffer, find_line(&buffer, "Name"));
+            assert!(header.contains("% Parent"));
+            assert!(header.contains("% Root"));
+            assert!(header.contains("Files"));
+            assert!(header.contains("Folders"));
+            let row = line(&buffer, find_line(&buffer, "b_folder"));
+            assert_eq!(row.matches("39.4%").count(), 2);
+            assert!(row.contains(" 3 "));
+        }
+
+        #[test]
+        fn renders_percent_of_root_in_subfolder() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('P'));
+            press(&mut app, KeyCode::Char('R'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+            let buffer = render(&mut app);
+
+            let row = line(&buffer, find_line(&buffer, "folder1_file2"));
+            assert!(row.contains("66.6%"));
+            assert!(row.contains("6.1%"));
+        }
+
+        #[test]
+        fn renders_bars_with_colorblind_safe_ramp() {
+            let mut app = setup_app_view();
+            app.ui_config.select_theme("colorblind-safe");
+            press(&mut app, KeyCode::Char('c'));
+            let buffer = render(&mut app);
+
+            let largest = find_line(&buffer, "b_folder");
+            let row = line(&buffer, largest);
+            let x = row.chars().position(|c| c == '█').unwrap() as u16;
+            assert_eq!(buffer.get(x, largest).fg, Color::Rgb(213, 94, 0));
+        }
+
+        #[test]
+        fn highlights_selected_row_with_reversed_video_in_monochrome() {
+            let mut app = setup_app_view();
+            app.ui_config.select_theme(crate::ui::THEME_MONOCHROME);
+            press(&mut app, KeyCode::Down);
+            let buffer = render(&mut app);
+
+            let (x, y) = find_cell(&buffer, "a_folder");
+            assert_eq!(buffer.get(x, y).bg, Color::Reset);
+            assert!(buffer
+                .get(x, y)
+                .modifier
+                .contains(ratatui::style::Modifier::REVERSED));
+        }
+    }
+
+    mod theme {
+        use super::*;
+        use crate::config::Config;
+        use crate::ui::theme::parse_theme_file;
+        use crate::ui::THEME_MONOCHROME;
+        use ratatui::style::Color;
+        use std::path::PathBuf;
+
+        fn config() -> Config {
+            let mut config =
+                Config::build(std::iter::once(String::from("space_inspector"))).unwrap();
+            // Keep tests independent from the user's theme file
+            config.theme_file = Some(PathBuf::from("/nonexistent/themes.conf"));
+            config
+        }
+
+        #[test]
+        fn cycles_themes_with_key() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char('T'));
+
+            assert_eq!(app.ui_config.theme().name, "light");
+            assert_eq!(app.ui_config.message, Some(String::from("Theme: light")));
+
+            for _ in 1..app.ui_config.themes.len() {
+                press(&mut app, KeyCode::Char('T'));
+            }
+            assert_eq!(app.ui_config.theme().name, "dark");
+        }
+
+        #[test]
+        fn starts_monochrome_when_no_color_is_set() {
+            let mut app = setup_app_view();
+            let mut config = config();
+            config.no_color = true;
+
+            app.configure(&config);
+
+            assert_eq!(app.ui_config.theme().name, THEME_MONOCHROME);
+        }
+
+        #[test]
+        fn prefers_explicit_theme_over_no_color() {
+            let mut app = setup_app_view();
+            let mut config = config();
+            config.no_color = true;
+            config.theme = Some(String::from("high-contrast"));
+
+            app.configure(&config);
+
+            assert_eq!(app.ui_config.theme().name, "high-contrast");
+        }
+
+        #[test]
+        fn reports_unknown_theme() {
+            let mut app = setup_app_view();
+            let mut config = config();
+            config.theme = Some(String::from("neon"));
+
+            app.configure(&config);
+
+            assert_eq!(app.ui_config.theme().name, "dark");
+            assert!(app.ui_config.message.is_some());
+        }
+
+        #[test]
+        fn parses_theme_file() {
+            let file = parse_theme_file(
+                "# comment\n\
+                 theme = ocean\n\
+                 \n\
+                 [ocean]\n\
+                 base = light\n\
+                 background = #001b2e\n\
+                 bar = blue 208 white\n\
+                 [dark]\n\
+                 text = red\n",
+            )
+            .unwrap();
+
+            assert_eq!(file.default_theme, Some(String::from("ocean")));
+            assert_eq!(file.themes.len(), 2);
+            assert_eq!(file.themes[0].name, "ocean");
+            assert_eq!(file.themes[0].background, Color::Rgb(0, 0x1b, 0x2e));
+            assert_eq!(
+                file.themes[0].bar,
+                vec![Color::Blue, Color::Indexed(208), Color::White]
+            );
+            assert_eq!(
+                file.themes[0].header_bg,
+                crate::ui::Theme::light().header_bg
+            );
+            assert_eq!(file.themes[1].text, Color::Red);
+        }
+
+        #[test]
+        fn reports_theme_file_errors_with_line() {
+            assert_eq!(
+                parse_theme_file("[x]\nbackground = nope"),
+                Err(String::from("Theme file line 2: invalid color \"nope\""))
+            );
+            assert!(parse_theme_file("text = red").is_err());
+            assert!(parse_theme_file("[x]\nbase = neon").is_err());
+            assert!(parse_theme_file("[x]\nglitter = red").is_err());
+        }
+
+        #[test]
+        fn file_themes_replace_builtin_ones_with_same_name() {
+            let mut app = setup_app_view();
+            let file = parse_theme_file("[dark]\ntext = red").unwrap();
+
+            app.ui_config.add_themes(file.themes);
+
+            assert_eq!(app.ui_config.themes.len(), 5);
+            assert_eq!(app.ui_config.theme().text, Color::Red);
+        }
+    }
+
+    mod counts {
+        use super::*;
+        use crate::fs::{ItemCount, SortBy};
+        use std::path::{Path, PathBuf};
+
+        fn root_count(app: &App, path: &str) -> ItemCount {
+            app.tree.folder(Path::new(path)).unwrap().count
+        }
+
+        fn entry_count(app: &App, title: &str) -> Option<ItemCount> {
+            let entry = app
+                .get_current_folder()
+                .unwrap()
+                .entries
+                .iter()
+                .find(|entry| entry.title == title)
+                .unwrap();
+            app.tree.count_of(entry)
+        }
+
+        #[test]
+        fn counts_items_recursively_during_scan() {
+            let app = setup_app_edit();
+
+            assert_eq!(
+                root_count(&app, TEST_FILE_PATH_EDIT),
+                ItemCount {
+                    files: 9,
+                    folders: 3
+                }
+            );
+            assert_eq!(
+                entry_count(&app, "folder_to_delete_1"),
+                Some(ItemCount {
+                    files: 6,
+                    folders: 2
+                })
+            );
+            assert_eq!(entry_count(&app, "file_to_delete_1.txt"), None);
+        }
+
+        #[test]
+        fn deleting_folder_updates_counts() {
+            let mut app = setup_app_edit();
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(
+                root_count(&app, TEST_FILE_PATH_EDIT),
+                ItemCount {
+                    files: 3,
+                    folders: 0
+                }
+            );
+        }
+
+        #[test]
+        fn deleting_nested_file_updates_parent_counts() {
+            let mut app = setup_app_edit();
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Backspace);
+
+            assert_eq!(
+                root_count(&app, TEST_FILE_PATH_EDIT),
+                ItemCount {
+                    files: 8,
+                    folders: 3
+                }
+            );
+            assert_eq!(
+                entry_count(&app, "folder_to_delete_1"),
+                Some(ItemCount {
+                    files: 5,
+                    folders: 2
+                })
+            );
+        }
+
+        #[test]
+        fn root_percent_follows_jump_outside_start_path() {
+            let storage = view_storage();
+            storage.add_file("/other/big.bin", 5000);
+            storage.add_file("/other/sub/small.bin", 1000);
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+            let start_size = app.root_size();
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+            assert_eq!(app.root_size(), start_size);
+
+            app.jump_to(PathBuf::from("/other/sub"));
+            assert_eq!(app.root_size(), 1000);
+
+            app.jump_to(PathBuf::from("/other"));
+            assert_eq!(app.root_size(), 6000);
+        }
+
+        #[test]
+        fn sorts_by_file_count_then_folder_count() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char('n'));
+
+            assert_eq!(app.ui_config.sort_by, SortBy::Files);
+            assert!(app.ui_config.show_files);
+            assert_item_at_index_title(&app, 0, "..".to_string());
+            assert_item_at_index_title(&app, 1, "b_folder".to_string());
+            assert_item_at_index_title(&app, 2, "c_folder".to_string());
+            assert_item_at_index_title(&app, 3, "a_folder".to_string());
+            assert_item_at_index_title(&app, 4, "a_root_file.txt".to_string());
+
+            press(&mut app, KeyCode::Char('n'));
+            assert_eq!(app.ui_config.sort_by, SortBy::Folders);
+
+            press(&mut app, KeyCode::Char('s'));
+            assert_root_view_folder_sorted_by_title(&app);
+        }
+    }
+
+    mod export {
+        use super::*;
+        use crate::export::html_report;
+        use std::path::Path;
+
+        #[test]
+        fn renders_collapsible_tree_and_sunburst() {
+            let app = setup_app_view();
+
+            let html = html_report(&app.tree, Path::new(TEST_FILE_PATH_VIEW));
+
+            assert!(html.starts_with("<!DOCTYPE html>"));
+            assert!(html.contains("<h1>/view</h1>"));
+            assert_eq!(html.matches("<details").count(), 4);
+            assert!(html.contains("b_folder</span><span class=\"size\">5.82 KB</span>"));
+            assert!(html.contains("folder1_file2.txt"));
+            assert!(html.contains("<svg"));
+            assert!(html.contains("<title>b_folder (5.82 KB)</title>"));
+        }
+
+        #[test]
+        fn links_chart_segments_to_tree_nodes() {
+            let app = setup_app_view();
+
+            let html = html_report(&app.tree, Path::new("/view/a_folder"));
+
+            assert!(html.contains("<details id=\"n0\" open>"));
+            assert!(html.contains("<a href=\"#n1\">"));
+            assert!(html.contains("<div class=\"file\" id=\"n1\">"));
+        }
+
+        #[test]
+        fn has_no_external_assets() {
+            let app = setup_app_view();
+
+            let html = html_report(&app.tree, Path::new(TEST_FILE_PATH_VIEW));
+
+            assert!(!html.contains("<script"));
+            assert!(!html.contains("<link"));
+            assert!(!html.contains("src="));
+            assert!(!html.contains("url("));
+        }
+
+        #[test]
+        fn escapes_names() {
+            let storage = MemoryStorage::new();
+            storage.add_file("/report/<b>&\"x\".txt", 10);
+            let app = setup_app(&storage, "/report");
+
+            let html = html_report(&app.tree, Path::new("/report"));
+
+            assert!(html.contains("&lt;b&gt;&amp;&quot;x&quot;.txt"));
+            assert!(!html.contains("<b>"));
+        }
+
+        #[test]
+        fn summarizes_small_files_of_large_folders() {
+            let storage = MemoryStorage::new();
+            for index in 0..25 {
+                storage.add_file(&format!("/report/file_{:02}.txt", index), 100 + index);
+            }
+            let app = setup_app(&storage, "/report");
+
+            let html = html_report(&app.tree, Path::new("/report"));
+
+            assert_eq!(html.matches("<div class=\"file\"").count(), 20);
+            assert!(html.contains("5 more files"));
+            assert!(html.contains("file_24.txt"));
+            assert!(!html.contains("file_00.txt"));
+        }
+
+        #[test]
+        fn writes_report_of_current_folder_on_key_press() {
+            let mut app = setup_app_view();
+            let export_dir =
+                std::env::temp_dir().join(format!("space_inspector_export_{}", std::process::id()));
+            std::fs::create_dir_all(&export_dir).unwrap();
+            app.export_dir = export_dir.clone();
+
+            press(&mut app, KeyCode::Char('x'));
+
+            let report_path = export_dir.join("space_inspector_view.html");
+            let html = std::fs::read_to_string(&report_path).unwrap();
+            assert!(html.contains("a_root_file.txt"));
+            assert_eq!(
+                app.ui_config.message,
+                Some(format!("Report saved to {}", report_path.to_string_lossy()))
+            );
+            std::fs::remove_dir_all(export_dir).unwrap();
+        }
+    }
+
+    mod stats {
+        use super::*;
+        use crate::ui::View;
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        #[test]
+        fn counts_visited_folders_files_and_bytes() {
+            let app = setup_app_view();
+            let stats = app.scan_stats();
+
+            assert_eq!(stats.folders, 4);
+            assert_eq!(stats.files, 11);
+            assert_eq!(stats.bytes, 15124);
+            assert_eq!(stats.errors, 0);
+            assert_eq!(stats.slowest.len(), 4);
+            assert!(stats.slowest[0].1 >= stats.slowest[3].1);
+        }
+
+        #[test]
+        fn records_unreadable_folder_instead_of_failing() {
+            let storage = view_storage();
+            storage.set_unreadable("/view/b_folder");
+
+            let app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+            let stats = app.scan_stats();
+
+            assert_eq!(stats.folders, 3);
+            assert_eq!(stats.files, 8);
+            assert_eq!(stats.errors, 1);
+            assert_eq!(stats.error_samples[0].0, PathBuf::from("/view/b_folder"));
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 9167);
+        }
+
+        #[test]
+        fn keeps_siblings_of_file_without_metadata() {
+            let storage = view_storage();
+            storage.set_unreadable("/view/a_root_file.txt");
+
+            let app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+            let stats = app.scan_stats();
+
+            assert_eq!(stats.files, 11);
+            assert_eq!(stats.errors, 1);
+            assert_eq!(
+                stats.error_samples[0].0,
+                PathBuf::from("/view/a_root_file.txt")
+            );
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 15124 - 459);
+        }
+
+        #[test]
+        fn accumulates_over_scans_of_new_folders() {
+            let storage = MemoryStorage::new();
+            storage.add_file("/a/b/file.txt", 10);
+            storage.add_file("/a/c/file.txt", 20);
+            let mut app = setup_app(&storage, "/a/b");
+            assert_eq!(app.scan_stats().folders, 1);
+
+            app.navigate_to_parent();
+
+            assert_eq!(app.scan_stats().folders, 3);
+            assert_eq!(app.scan_stats().bytes, 30);
+        }
+
+        #[test]
+        fn keeps_ten_slowest_folders() {
+            let mut stats = crate::ScanStats::default();
+            for millis in 0..15 {
+                let path = PathBuf::from(format!("/{}", millis));
+                stats.record_folder(&path, Duration::from_millis(millis), &[]);
+            }
+
+            assert_eq!(stats.slowest.len(), 10);
+            assert_eq!(stats.slowest[0].1, Duration::from_millis(14));
+            assert_eq!(stats.slowest[9].1, Duration::from_millis(5));
+        }
+
+        #[test]
+        fn reports_throughput() {
+            let stats = crate::ScanStats {
+                folders: 100,
+                files: 900,
+                bytes: 2048,
+                elapsed: Duration::from_secs(2),
+                ..Default::default()
+            };
+
+            assert_eq!(stats.entries_per_second(), 500.0);
+            let lines = stats.report_lines();
+            assert_eq!(
+                lines[0],
+                "Folders: 100 | Files: 900 | Size: 2.00 KB | Errors: 0"
+            );
+            assert_eq!(lines[1], "Elapsed: 2.000 s | 500 entries/s | 1.00 KB/s");
+        }
+
+        #[test]
+        fn toggles_stats_popup() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char('i'));
+            assert_eq!(app.ui_config.view, View::Stats);
+            press(&mut app, KeyCode::Char('j'));
+            assert_eq!(app.get_current_folder().unwrap().cursor_index, 0);
+            press(&mut app, KeyCode::Char('i'));
+            assert_eq!(app.ui_config.view, View::Folder);
+        }
+
+        #[test]
+        fn renders_popup_in_narrow_terminal() {
+            use ratatui::{backend::TestBackend, Terminal};
+
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('i'));
+            let mut terminal = Terminal::new(TestBackend::new(30, 20)).unwrap();
+
+            app.draw(&mut terminal).unwrap();
+
+            let buffer = terminal.backend().buffer();
+            let rendered: String = (0..buffer.area.height)
+                .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+                .map(|(x, y)| buffer.get(x, y).symbol().to_string())
+                .collect();
+            assert!(rendered.contains("Scan statistics"));
+        }
+    }
+
+    mod tree {
+        use super::*;
+        use std::path::{Path, PathBuf};
+
+        #[test]
+        fn reuses_scanned_folder_when_navigating_to_parent() {
+            let storage = MemoryStorage::new();
+            storage.add_file("/a/b/file.txt", 10);
+            storage.add_file("/a/c/file.txt", 20);
+            let mut app = setup_app(&storage, "/a/b");
+
+            press(&mut app, KeyCode::Backspace);
+
+            assert_eq!(app.current_path(), &PathBuf::from("/a"));
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 30);
+            assert_eq!(app.scan_stats().folders, 3);
+            let b = app.tree.find(Path::new("/a/b")).unwrap();
+            assert_eq!(
+                app.tree.get(b).unwrap().parent,
+                app.tree.find(Path::new("/a"))
+            );
+            assert_eq!(app.tree.path(b), PathBuf::from("/a/b"));
+        }
+
+        #[test]
+        fn shares_interned_names() {
+            let app = setup_app_view();
+            let name = |folder: &str, file: &str| {
+                let folder = app
+                    .tree
+                    .folder(&Path::new(TEST_FILE_PATH_VIEW).join(folder))
+                    .unwrap();
+                folder
+                    .entries
+                    .iter()
+                    .find(|entry| entry.title == file)
+                    .unwrap()
+                    .title
+                    .as_ptr()
+            };
+
+            assert_eq!(
+                name("b_folder", "folder2_file1.txt"),
+                name("c_folder", "folder2_file1.txt")
+            );
+        }
+
+        #[test]
+        fn frees_deleted_subtree() {
+            let mut app = setup_app_edit();
+            app.ui_config.move_to_trash = false;
+            assert_eq!(app.tree.iter().count(), 4);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(app.tree.iter().count(), 1);
+            assert!(app
+                .tree
+                .find(Path::new("/edit/folder_to_delete_1/folder_to_delete_2"))
+                .is_none());
+            assert_eq!(app.get_current_folder().unwrap().count.folders, 0);
+        }
+
+        #[test]
+        fn releases_names_no_longer_used() {
+            let mut app = setup_app_edit();
+            app.ui_config.move_to_trash = false;
+            assert!(app.tree.is_interned("folder_to_delete_3"));
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            for name in [
+                "folder_to_delete_1",
+                "folder_to_delete_2",
+                "folder_to_delete_3",
+            ] {
+                assert!(!app.tree.is_interned(name), "{} is still interned", name);
+            }
+            // Files of the same name are left in the current folder
+            assert!(app.tree.is_interned("file_to_delete_1.txt"));
+        }
+
+        #[test]
+        fn keeps_entries_compact() {
+            assert!(std::mem::size_of::<crate::FolderEntry>() <= 48);
+        }
+
+        #[test]
+        fn sorts_current_folder_in_place() {
+            let mut app = setup_app_view();
+            let entries = app.get_current_folder().unwrap().entries.as_ptr();
+
+            press(&mut app, KeyCode::Char('s'));
+
+            assert_root_view_folder_sorted_by_size(&app);
+            assert_eq!(app.get_current_folder().unwrap().entries.as_ptr(), entries);
+        }
+    }
+
+    mod jump {
+        use super::*;
+        use crate::fs::resolve_path;
+        use std::path::{Path, PathBuf};
+
+        fn jump(app: &mut App, input: &str) {
+            press(app, KeyCode::Char(':'));
+            type_text(app, input);
+            press(app, KeyCode::Enter);
+        }
+
+        #[test]
+        fn resolves_relative_and_absolute_paths() {
+            let current = Path::new("/var/log");
+
+            assert_eq!(
+                resolve_path(current, "nginx"),
+                PathBuf::from("/var/log/nginx")
+            );
+            assert_eq!(
+                resolve_path(current, "../lib/./docker"),
+                PathBuf::from("/var/lib/docker")
+            );
+            assert_eq!(
+                resolve_path(current, "/home/user/"),
+                PathBuf::from("/home/user")
+            );
+            assert_eq!(resolve_path(current, "../../.."), PathBuf::from("/"));
+        }
+
+        #[test]
+        fn jumps_to_relative_path() {
+            let mut app = setup_app_view();
+
+            jump(&mut app, "b_folder");
+
+            assert_eq!(app.current_path(), &PathBuf::from("/view/b_folder"));
+            assert_eq!(app.ui_config.jump_input, None);
+        }
+
+        #[test]
+        fn scans_folder_outside_of_scanned_tree() {
+            let storage = view_storage();
+            storage.add_file("/var/log/syslog", 300);
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+
+            jump(&mut app, "/var/log");
+
+            assert_eq!(app.current_path(), &PathBuf::from("/var/log"));
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 300);
+            jump(&mut app, "/view/a_folder");
+            assert_eq!(app.get_current_folder().unwrap().get_size(), 1376);
+        }
+
+        #[test]
+        fn stays_when_path_does_not_exist() {
+            let mut app = setup_app_view();
+
+            jump(&mut app, "/missing");
+
+            assert_eq!(app.current_path(), &PathBuf::from(TEST_FILE_PATH_VIEW));
+            assert!(app
+                .ui_config
+                .message
+                .as_ref()
+                .unwrap()
+                .starts_with("Can't open /missing"));
+        }
+
+        #[test]
+        fn cancels_prompt_on_escape() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char(':'));
+            type_text(&mut app, "b_folder");
+            press(&mut app, KeyCode::Esc);
+
+            assert_eq!(app.ui_config.jump_input, None);
+            assert_eq!(app.current_path(), &PathBuf::from(TEST_FILE_PATH_VIEW));
+        }
+
+        #[test]
+        fn completes_unique_folder_name() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char(':'));
+            type_text(&mut app, "/view/b");
+            press(&mut app, KeyCode::Tab);
+
+            assert_eq!(
+                app.ui_config.jump_input,
+                Some(String::from("/view/b_folder/"))
+            );
+        }
+
+        #[test]
+        fn completes_common_prefix_and_lists_candidates() {
+            let storage = MemoryStorage::new();
+            storage.add_folder("/p/project_a");
+            storage.add_folder("/p/project_b");
+            storage.add_file("/p/project_file", 1);
+            let mut app = setup_app(&storage, "/p");
+
+            press(&mut app, KeyCode::Char(':'));
+            type_text(&mut app, "pro");
+            press(&mut app, KeyCode::Tab);
+
+            assert_eq!(app.ui_config.jump_input, Some(String::from("project_")));
+            assert_eq!(
+                app.ui_config.message,
+                Some(String::from("project_a  project_b"))
+            );
+        }
+    }
+
+    mod bookmarks {
+        use super::*;
+        use crate::bookmarks::Bookmarks;
+        use crate::ui::View;
+        use std::path::PathBuf;
+
+        fn bookmarks_file(name: &str) -> PathBuf {
+            std::env::temp_dir()
+                .join(format!("space_inspector_bookmarks_{}", std::process::id()))
+                .join(name)
+        }
+
+        #[test]
+        fn toggles_and_persists_current_folder() {
+            let file = bookmarks_file("toggle");
+            let mut app = setup_app_view();
+            app.bookmarks = Bookmarks::load(file.clone()).unwrap();
+
+            press(&mut app, KeyCode::Char('b'));
+            assert_eq!(
+                app.ui_config.message,
+                Some(String::from("Bookmarked /view"))
+            );
+            assert_eq!(
+                Bookmarks::load(file.clone()).unwrap().paths,
+                vec![PathBuf::from("/view")]
+            );
+
+            press(&mut app, KeyCode::Char('b'));
+            assert!(Bookmarks::load(file.clone()).unwrap().paths.is_empty());
+            std::fs::remove_file(file).unwrap();
+        }
+
+        #[test]
+        fn loads_paths_skipping_comments() {
+            let file = bookmarks_file("load");
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(&file, "# logs\n/var/log\n\n/home/user\n").unwrap();
+
+            let bookmarks = Bookmarks::load(file.clone()).unwrap();
+
+            assert_eq!(
+                bookmarks.paths,
+                vec![PathBuf::from("/var/log"), PathBuf::from("/home/user")]
+            );
+            std::fs::remove_file(file).unwrap();
+        }
+
+        #[test]
+        fn jumps_to_selected_bookmark() {
+            let storage = view_storage();
+            storage.add_file("/var/log/syslog", 300);
+            let mut app = setup_app(&storage, TEST_FILE_PATH_VIEW);
+            app.bookmarks.paths = vec![PathBuf::from("/view/a_folder"), PathBuf::from("/var/log")];
+
+            press(&mut app, KeyCode::Char('B'));
+            assert_eq!(app.ui_config.view, View::Bookmarks);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+
+            assert_eq!(app.ui_config.view, View::Folder);
+            assert_eq!(app.current_path(), &PathBuf::from("/var/log"));
+        }
+
+        #[test]
+        fn removes_selected_bookmark() {
+            let mut app = setup_app_view();
+            app.bookmarks.paths = vec![PathBuf::from("/a"), PathBuf::from("/b")];
+
+            press(&mut app, KeyCode::Char('B'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+
+            assert_eq!(app.bookmarks.paths, vec![PathBuf::from("/a")]);
+            assert_eq!(app.bookmarks_cursor, 0);
+        }
+    }
+
+    mod breadcrumb {
+        use super::*;
+        use crate::events::mouse_click;
+        use crate::ui::breadcrumb_areas;
+        use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+        use std::path::{Path, PathBuf};
+
+        fn render(app: &mut App) {
+            let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            app.draw(&mut terminal).unwrap();
+        }
+
+        fn segment_area(app: &App, path: &str) -> Rect {
+            app.ui_config
+                .breadcrumb
+                .iter()
+                .find(|(_, segment)| segment == Path::new(path))
+                .map(|(area, _)| *area)
+                .unwrap()
+        }
+
+        #[test]
+        fn lays_out_every_folder_from_root() {
+            let areas = breadcrumb_areas(Rect::new(0, 2, 40, 1), Path::new("/view/b_folder"));
+
+            assert_eq!(
+                areas,
+                vec![
+                    (Rect::new(10, 2, 1, 1), PathBuf::from("/")),
+                    (Rect::new(14, 2, 4, 1), PathBuf::from("/view")),
+                    (Rect::new(21, 2, 8, 1), PathBuf::from("/view/b_folder")),
+                ]
+            );
+        }
+
+        #[test]
+        fn drops_leading_folders_that_do_not_fit() {
+            let areas = breadcrumb_areas(Rect::new(0, 0, 12, 1), Path::new("/view/b_folder"));
+
+            assert_eq!(
+                areas,
+                vec![(Rect::new(4, 0, 8, 1), PathBuf::from("/view/b_folder"))]
+            );
+        }
+
+        #[test]
+        fn jumps_to_clicked_ancestor() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char(':'));
+            type_text(&mut app, "b_folder");
+            press(&mut app, KeyCode::Enter);
+            render(&mut app);
+
+            let area = segment_area(&app, "/view");
+            app.handle_event(mouse_click(area.x + 1, area.y));
+
+            assert_eq!(app.current_path(), &PathBuf::from("/view"));
+        }
+
+        #[test]
+        fn ignores_clicks_outside_of_segments() {
+            let mut app = setup_app_view();
+            render(&mut app);
+
+            let area = segment_area(&app, "/");
+            app.handle_event(mouse_click(area.x, area.y + 1));
+            app.handle_event(mouse_click(0, area.y));
+
+            assert_eq!(app.current_path(), &PathBuf::from(TEST_FILE_PATH_VIEW));
+        }
+    }
+
+    mod tree_view {
+        use super::*;
+        use crate::ui::View;
+
+        fn row_titles(app: &App) -> Vec<(usize, String)> {
+            app.tree_view_rows()
+                .iter()
+                .map(|row| (row.depth, row.title.to_string()))
+                .collect()
+        }
+
+        #[test]
+        fn toggles_tree_view() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char('v'));
+            assert_eq!(app.ui_config.view, View::Tree);
+            press(&mut app, KeyCode::Char('v'));
+            assert_eq!(app.ui_config.view, View::Folder);
+        }
+
+        #[test]
+        fn expands_folder_inline() {
+            let mut app = setup_app_view();
+
+            press(&mut app, KeyCode::Char('v'));
+            press(&mut app, KeyCode::Right);
+
+            assert_eq!(
+                row_titles(&app)[..4],
+                [
+                    (0, String::from("a_folder")),
+                    (1, String::from("folder1_file1.txt")),
+                    (1, String::from("folder1_file2.txt")),
+                    (0, String::from("b_folder")),
+                ]
+            );
+            assert!(app.tree_view_rows()[0].expanded);
+        }
+
+        #[test]
+        fn collapsing_child_row_moves_to_its_folder() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('v'));
+            press(&mut app, KeyCode::Right);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+
+            press(&mut app, KeyCode::Left);
+            assert_eq!(app.tree_view_cursor, 0);
+            press(&mut app, KeyCode::Left);
+            assert_eq!(row_titles(&app).len(), 6);
+        }
+
+        #[test]
+        fn toggles_selected_folder() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('v'));
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+
+            press(&mut app, KeyCode::Char(' '));
+            assert_eq!(row_titles(&app).len(), 9);
+            press(&mut app, KeyCode::Enter);
+            assert_eq!(row_titles(&app).len(), 6);
+        }
+
+        #[test]
+        fn does_not_move_cursor_past_last_row() {
+            let mut app = setup_app_view();
+            press(&mut app, KeyCode::Char('v'));
+
+            for _ in 0..10 {
+                press(&mut app, KeyCode::Down);
+            }
+
+            assert_eq!(app.tree_view_cursor, 5);
+        }
+    }
+
+    mod delete {
+        use super::*;
+
+        #[test]
+        fn has_correct_initial_state() {
+            let app = setup_app_edit();
+            assert_delete_folder_state(&app);
+        }
+
+        #[test]
+        fn does_nothing_when_cursor_is_at_the_top() {
+            let mut app = setup_app_edit();
+            assert_cursor_index(&app, 0);
+            assert_delete_folder_state(&app);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_delete_folder_state(&app);
+        }
+
+        #[test]
+        fn does_nothing_when_delete_pressed_once() {
+            let mut app = setup_app_edit();
+            assert_delete_folder_state(&app);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 3);
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 1);
+        }
+
+        #[test]
+        fn resets_delete_confirmation_on_cursor_move() {
+            let mut app = setup_app_edit();
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Down);
+            assert_eq!(app.ui_config.confirming_deletion, false);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Up);
+            assert_eq!(app.ui_config.confirming_deletion, false);
+        }
+
+        #[test]
+        fn resets_delete_confirmation_on_folder_enter() {
+            let mut app = setup_app_edit();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Enter);
+            assert_eq!(app.ui_config.confirming_deletion, false);
+        }
+
+        #[test]
+        fn resets_delete_confirmation_after_deleting_folder() {
+            let mut app = setup_app_edit();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(app.ui_config.confirming_deletion, false);
+        }
+
+        #[test]
+        fn resets_delete_confirmation_after_deleting_file() {
+            let mut app = setup_app_edit();
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(app.ui_config.confirming_deletion, false);
+        }
+
+        #[test]
+        fn deletes_folder() {
+            let mut app = setup_app_edit();
+            assert_delete_folder_state(&app);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 3);
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 0);
+        }
+
+        #[test]
+        fn deletes_file() {
+            let mut app = setup_app_edit();
+            assert_delete_folder_state(&app);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::File).len(), 2);
+            assert_eq!(get_entry_by_kind(&app, FolderEntryType::Folder).len(), 1);
+        }
+
+        #[test]
+        fn updated_current_folder_size() {
+            let mut app = setup_app_edit();
+
+            let root_entry = app.get_current_folder().unwrap();
+            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            let root_entry_updated = app.get_current_folder().unwrap();
+            assert_eq!(root_entry_updated.get_size(), (TEST_FILE_SIZE * 8) as u64);
+        }
+
+        #[test]
+        fn deleting_file_updates_parent_folders_sizes() {
+            let mut app = setup_app_edit();
+
+            let root_entry = app.get_current_folder().unwrap();
+            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+
+            let folder_1 = app.get_current_folder().unwrap();
+            assert_eq!(folder_1.get_size(), (TEST_FILE_SIZE * 6) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+
+            let folder_2 = app.get_current_folder().unwrap();
+            assert_eq!(folder_2.get_size(), (TEST_FILE_SIZE * 3) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            let folder_2_upd = app.get_current_folder().unwrap();
+            assert_eq!(folder_2_upd.get_size(), (TEST_FILE_SIZE * 2) as u64);
+
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Enter);
+
+            let folder_1_upd = app.get_current_folder().unwrap();
+            assert_eq!(folder_1_upd.get_size(), (TEST_FILE_SIZE * 5) as u64);
+            assert_eq!(
+                folder_1_upd.get_selected_entry_size(),
+                (TEST_FILE_SIZE * 2) as u64
+            );
+
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Enter);
+
+            let root_entry_upd = app.get_current_folder().unwrap();
+            assert_eq!(root_entry_upd.get_size(), (TEST_FILE_SIZE * 8) as u64);
+            assert_eq!(
+                root_entry_upd.get_selected_entry_size(),
+                (TEST_FILE_SIZE * 5) as u64
+            );
+        }
+
+        #[test]
+        fn deleting_folder_updates_parent_folders_sizes() {
+            let mut app = setup_app_edit();
+
+            let root_entry = app.get_current_folder().unwrap();
+            assert_eq!(root_entry.get_size(), (TEST_FILE_SIZE * 9) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+
+            let folder_1 = app.get_current_folder().unwrap();
+            assert_eq!(folder_1.get_size(), (TEST_FILE_SIZE * 6) as u64);
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            let folder_1_upd = app.get_current_folder().unwrap();
+            assert_eq!(folder_1_upd.get_size(), (TEST_FILE_SIZE * 3) as u64);
+
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Enter);
+
+            let root_entry_upd = app.get_current_folder().unwrap();
+            assert_eq!(root_entry_upd.get_size(), (TEST_FILE_SIZE * 6) as u64);
+            assert_eq!(
+                root_entry_upd.get_selected_entry_size(),
+                (TEST_FILE_SIZE * 3) as u64
+            );
+        }
+
+        #[test]
+        fn deleting_file_updates_owner_usage() {
+            let mut app = setup_app_edit();
+
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Enter);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+
+            let folder_1_usage: u64 = app.get_current_folder().unwrap().owners.values().sum();
+            assert_eq!(folder_1_usage, (TEST_FILE_SIZE * 5) as u64);
+
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Up);
+            press(&mut app, KeyCode::Enter);
+
+            let root_usage: u64 = app.get_current_folder().unwrap().owners.values().sum();
+            assert_eq!(root_usage, (TEST_FILE_SIZE * 8) as u64);
+        }
+
+        #[test]
+        fn moves_cursor_one_step_up_after_deleting_bottom_entry() {
+            let mut app = setup_app_edit();
+
+            for _ in 1..20 {
+                press(&mut app, KeyCode::Down);
+            }
+
+            assert_eq!(app.get_current_folder().unwrap().cursor_index, 4);
+            press(&mut app, KeyCode::Char('d'));
+            press(&mut app, KeyCode::Char('d'));
+            assert_eq!(app.get_current_folder().unwrap().cursor_index, 3);
         }
     }
 }
@@ -1,24 +1,49 @@
-use crate::fs::FolderEntryType;
 use crate::fs::SortBy;
+use crate::fs::{
+    percent_of, user_name, Cleanable, FileSystem, FolderEntryType, FolderTree, OwnerUsage,
+    ScanStats, TreeRow,
+};
 use crate::App;
 use crate::Folder;
-use ratatui::{prelude::*, style::palette::tailwind, widgets::*};
-
-const NORMAL_ROW_COLOR: Color = tailwind::SLATE.c950;
-const TEXT_COLOR: Color = tailwind::SLATE.c200;
-const TABLE_HEADER_FG: Color = tailwind::SLATE.c200;
-const TABLE_HEADER_BG: Color = tailwind::SLATE.c900;
-const TEXT_SELECTED_BG: Color = tailwind::SLATE.c700;
-const TEXT_PRE_DELETED_BG: Color = tailwind::RED.c600;
+use ratatui::{prelude::*, widgets::*};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub mod theme;
+pub use theme::{builtin_themes, default_theme_file, load_theme_file, Theme, THEME_MONOCHROME};
+
 const TABLE_SPACE_WIDTH: usize = 40;
+const TABLE_OWNER_WIDTH: u16 = 16;
+const TABLE_PERCENT_WIDTH: u16 = 9;
+const TABLE_COUNT_WIDTH: u16 = 10;
 
 // Texts
 pub const TEXT_UNKNOWN: &str = "N/A";
 pub const TEXT_PARENT_DIR: &str = "..";
 const TEXT_TITLE: &str = "Space inspector";
-const TEXT_HINT_L1: &str = "↓↑ - move | \"Enter\" - select | \"Backspace\" - parent";
+const TEXT_NOT_SCANNED: &str = "not scanned";
+const TEXT_HIGHLIGHT_SYMBOL: &str = ">>> ";
+const TEXT_BREADCRUMB_SEPARATOR: &str = " › ";
+const TEXT_BREADCRUMB_TRUNCATED: &str = "… › ";
+pub const TEXT_CONFIRM_PURGE: &str = "purge";
+const TEXT_HINT_L1: &str =
+    "↓↑ - move | \"Enter\" - select | \"Backspace\" - parent | \":\" - jump to path | \"x\" - export HTML";
 const TEXT_HINT_L2: &str =
-    "\"d-d\" - delete | \"s\" - sort | \"c\" - color | \"t\" - trash | \"q\" - exit";
+    "\"d-d\" - delete | \"s\" - sort | \"c\" - color | \"T\" - theme | \"t\" - trash | \"i\" - scan stats | \"q\" - exit";
+const TEXT_HINT_L3: &str = "\"o\" - owner column | \"u\" - filter by owner | \"O\" - owners | \"f\" - filesystems | \"C\" - cleanable | \"b\" - bookmark | \"B\" - bookmarks";
+const TEXT_HINT_L4: &str = "\"P\" - % of parent | \"R\" - % of root | \"F\" - files | \"D\" - folders | \"n\" - sort by count | \"v\" - tree view";
+const TEXT_HINT_TREE: &str =
+    "↓↑ - move | →← - expand / collapse | \"Space\" - toggle | \"v\" / \"Backspace\" - back | \"q\" - exit";
+const TEXT_HINT_STATS: &str = "\"i\" / \"Backspace\" - back | \"q\" - exit";
+const TEXT_HINT_BOOKMARKS: &str =
+    "↓↑ - move | \"Enter\" - jump | \"d\" - remove | \"B\" / \"Backspace\" - back | \"q\" - exit";
+const TEXT_HINT_JUMP: &str = "\"Tab\" - complete | \"Enter\" - jump | \"Esc\" - cancel";
+const TEXT_HINT_OWNERS: &str = "\"O\" / \"Backspace\" - back | \"q\" - exit";
+const TEXT_HINT_FILESYSTEMS: &str =
+    "↓↑ - move | \"Enter\" - inspect | \"f\" / \"Backspace\" - back | \"q\" - exit";
+const TEXT_HINT_CLEANABLE: &str =
+    "↓↑ - move | \"Space\" - select | \"a\" - select all | \"d-d\" - purge selected";
+const TEXT_HINT_CLEANABLE_L2: &str = "\"t\" - trash | \"C\" / \"Backspace\" - back | \"q\" - exit";
 
 #[derive(Debug)]
 pub struct UIConfig {
@@ -27,22 +52,186 @@ pub struct UIConfig {
     pub sort_by: SortBy,
     pub move_to_trash: bool,
     pub open_file: bool,
+    pub show_owner: bool,
+    pub show_parent_percent: bool,
+    pub show_root_percent: bool,
+    pub show_files: bool,
+    pub show_folders: bool,
+    pub owner_filter: Option<u32>,
+    pub view: View,
+    /// One-off status shown above the hints until the next key press
+    pub message: Option<String>,
+    /// Input typed so far while permanent deletion waits for the entry name
+    pub typed_confirmation: Option<String>,
+    /// Path typed so far in the `:` prompt
+    pub jump_input: Option<String>,
+    /// Clickable path segments of the header, filled in while rendering
+    pub breadcrumb: Vec<(Rect, PathBuf)>,
+    /// Built-in themes followed by the ones from theme file
+    pub themes: Vec<Theme>,
+    pub theme_index: usize,
+}
+
+impl UIConfig {
+    pub fn theme(&self) -> &Theme {
+        &self.themes[self.theme_index]
+    }
+
+    pub fn cycle_theme(&mut self) {
+        self.theme_index = (self.theme_index + 1) % self.themes.len();
+    }
+
+    /// Returns `false` when there is no theme called `name`
+    pub fn select_theme(&mut self, name: &str) -> bool {
+        match self.themes.iter().position(|theme| theme.name == name) {
+            Some(index) => {
+                self.theme_index = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds themes, replacing existing ones with the same name
+    pub fn add_themes(&mut self, themes: Vec<Theme>) {
+        for theme in themes {
+            match self
+                .themes
+                .iter()
+                .position(|known| known.name == theme.name)
+            {
+                Some(index) => self.themes[index] = theme,
+                None => self.themes.push(theme),
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum View {
+    Folder,
+    Owners,
+    Filesystems,
+    Cleanable,
+    /// Scan statistics over the folder table
+    Stats,
+    Bookmarks,
+    /// Current folder with subfolders expanded inline
+    Tree,
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let vertical = Layout::vertical([
-            Constraint::Length(2),
-            Constraint::Fill(1),
             Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(4),
         ]);
-        let [header_area, rest_area, footer_area] = vertical.areas(area);
+        let [header_area, rest_area, status_area, footer_area] = vertical.areas(area);
+        let [_, _, breadcrumb_area] =
+            Layout::vertical([Constraint::Length(1); 3]).areas(header_area);
+        self.ui_config.breadcrumb = breadcrumb_areas(breadcrumb_area, &self.current_path);
 
         let maybe_folder = self.get_current_folder();
 
-        render_title(header_area, buf, maybe_folder, &self.ui_config);
-        render_table(rest_area, buf, maybe_folder, &self.ui_config);
-        render_footer(footer_area, buf);
+        render_title(
+            header_area,
+            buf,
+            maybe_folder,
+            self.filesystem.as_ref(),
+            self.delete_guard.read_only,
+            &self.ui_config,
+        );
+        render_status(
+            status_area,
+            buf,
+            &self.confirmation_target(),
+            &self.ui_config,
+        );
+        let root_size = self.root_size();
+        match self.ui_config.view {
+            View::Folder => {
+                render_table(
+                    rest_area,
+                    buf,
+                    maybe_folder,
+                    &self.tree,
+                    root_size,
+                    &self.ui_config,
+                );
+                let hints: &[&str] = match self.ui_config.jump_input {
+                    Some(_) => &[TEXT_HINT_JUMP],
+                    None => &[TEXT_HINT_L1, TEXT_HINT_L2, TEXT_HINT_L3, TEXT_HINT_L4],
+                };
+                render_footer(footer_area, buf, hints);
+            }
+            View::Tree => {
+                let folder_size = maybe_folder.map(|folder| folder.get_size()).unwrap_or(0);
+                render_tree_view(
+                    rest_area,
+                    buf,
+                    &self.tree_view_rows(),
+                    self.tree_view_cursor,
+                    folder_size,
+                    &self.ui_config,
+                );
+                render_footer(footer_area, buf, &[TEXT_HINT_TREE]);
+            }
+            View::Bookmarks => {
+                render_bookmarks(
+                    rest_area,
+                    buf,
+                    &self.bookmarks.paths,
+                    self.bookmarks_cursor,
+                    &self.tree,
+                    self.ui_config.theme(),
+                );
+                render_footer(footer_area, buf, &[TEXT_HINT_BOOKMARKS]);
+            }
+            View::Stats => {
+                render_table(
+                    rest_area,
+                    buf,
+                    maybe_folder,
+                    &self.tree,
+                    root_size,
+                    &self.ui_config,
+                );
+                render_stats(rest_area, buf, &self.scan_stats, self.ui_config.theme());
+                render_footer(footer_area, buf, &[TEXT_HINT_STATS]);
+            }
+            View::Owners => {
+                if let Some(folder) = maybe_folder {
+                    render_owners(rest_area, buf, &folder.owners, self.ui_config.theme());
+                }
+                render_footer(footer_area, buf, &[TEXT_HINT_OWNERS]);
+            }
+            View::Filesystems => {
+                render_filesystems(
+                    rest_area,
+                    buf,
+                    &self.filesystems,
+                    self.filesystems_cursor,
+                    &self.ui_config,
+                );
+                render_footer(footer_area, buf, &[TEXT_HINT_FILESYSTEMS]);
+            }
+            View::Cleanable => {
+                render_cleanables(
+                    rest_area,
+                    buf,
+                    &self.cleanables,
+                    self.cleanables_cursor,
+                    &self.ui_config,
+                );
+                render_footer(
+                    footer_area,
+                    buf,
+                    &[TEXT_HINT_CLEANABLE, TEXT_HINT_CLEANABLE_L2],
+                );
+            }
+        }
     }
 }
 
@@ -53,9 +242,22 @@ fn value_to_box(value: &bool) -> String {
     }
 }
 
-fn render_title(area: Rect, buf: &mut Buffer, maybe_folder: Option<&Folder>, config: &UIConfig) {
+fn render_title(
+    area: Rect,
+    buf: &mut Buffer,
+    maybe_folder: Option<&Folder>,
+    maybe_filesystem: Option<&FileSystem>,
+    read_only: bool,
+    config: &UIConfig,
+) {
+    let [top, filesystem_line, breadcrumb_line] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(area);
     let vertical_layout = Layout::horizontal([Constraint::Min(1), Constraint::Min(1)]);
-    let [left, right] = vertical_layout.areas(area);
+    let [left, right] = vertical_layout.areas(top);
 
     if let Some(folder) = maybe_folder {
         Paragraph::new(format!(
@@ -69,34 +271,189 @@ fn render_title(area: Rect, buf: &mut Buffer, maybe_folder: Option<&Folder>, con
         .render(left, buf);
     }
 
+    let owner_text = match config.owner_filter {
+        Some(uid) => user_name(uid),
+        None => String::from("all"),
+    };
     let config_text = Text::from(format!(
-        "Colored: {} | Trash: {}",
+        "Colored: {} | Trash: {} | Read-only: {} | Owner: {}",
         value_to_box(&config.colored),
-        value_to_box(&config.move_to_trash)
+        value_to_box(&config.move_to_trash),
+        value_to_box(&read_only),
+        owner_text
     ));
     Paragraph::new(config_text)
         .right_aligned()
         .render(right, buf);
+
+    if let Some(filesystem) = maybe_filesystem {
+        let folder_size = maybe_folder.map(|folder| folder.get_size()).unwrap_or(0);
+        Paragraph::new(format!(
+            "{} ({}) on {} | Total: {} | Used: {} ({:.1}%) | Free: {} | Folder: {:.1}% of used",
+            filesystem.device,
+            filesystem.fs_type,
+            filesystem.mount_point.to_string_lossy(),
+            format_file_size(filesystem.total),
+            format_file_size(filesystem.used),
+            filesystem.used_percent(),
+            format_file_size(filesystem.free),
+            percent_of(folder_size, filesystem.used),
+        ))
+        .centered()
+        .render(filesystem_line, buf);
+    }
+
+    render_breadcrumb(breadcrumb_line, buf, &config.breadcrumb);
 }
 
-fn render_table(area: Rect, buf: &mut Buffer, maybe_folder: Option<&Folder>, config: &UIConfig) {
+fn breadcrumb_label(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().into_owned(),
+        None => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Lays out every folder from the root down to `path` centered on `area`.
+/// Leading folders that don't fit are dropped.
+pub fn breadcrumb_areas(area: Rect, path: &Path) -> Vec<(Rect, PathBuf)> {
+    let mut segments: Vec<(usize, &Path)> = path
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .map(|ancestor| (Span::raw(breadcrumb_label(ancestor)).width(), ancestor))
+        .collect();
+    segments.reverse();
+
+    let separator_width = Span::raw(TEXT_BREADCRUMB_SEPARATOR).width();
+    let truncated_width = Span::raw(TEXT_BREADCRUMB_TRUNCATED).width();
+    let line_width = |segments: &[(usize, &Path)], truncated: bool| {
+        let labels: usize = segments.iter().map(|(width, _)| width).sum();
+        let separators = segments.len().saturating_sub(1) * separator_width;
+        labels + separators + if truncated { truncated_width } else { 0 }
+    };
+    let mut skipped = 0;
+    while skipped + 1 < segments.len()
+        && line_width(&segments[skipped..], skipped > 0) > area.width as usize
+    {
+        skipped += 1;
+    }
+
+    let visible = &segments[skipped..];
+    let width = line_width(visible, skipped > 0) as u16;
+    let mut x = area.x + area.width.saturating_sub(width) / 2;
+    if skipped > 0 {
+        x += truncated_width as u16;
+    }
+    let mut areas = vec![];
+    for (width, path) in visible {
+        let segment = Rect::new(x, area.y, *width as u16, 1).intersection(area);
+        areas.push((segment, path.to_path_buf()));
+        x = x.saturating_add(*width as u16 + separator_width as u16);
+    }
+    areas
+}
+
+/// Ancestors are underlined to show they can be clicked, current folder is bold
+fn render_breadcrumb(area: Rect, buf: &mut Buffer, segments: &[(Rect, PathBuf)]) {
+    let Some((first, first_path)) = segments.first() else {
+        return;
+    };
+    if first_path.parent().is_some() {
+        let width = Span::raw(TEXT_BREADCRUMB_TRUNCATED).width() as u16;
+        let x = first.x.saturating_sub(width).max(area.x);
+        buf.set_stringn(
+            x,
+            area.y,
+            TEXT_BREADCRUMB_TRUNCATED,
+            width as usize,
+            Style::default(),
+        );
+    }
+
+    for (index, (segment, path)) in segments.iter().enumerate() {
+        let is_current = index + 1 == segments.len();
+        let style = match is_current {
+            true => Style::default().bold(),
+            false => Style::default().underlined(),
+        };
+        buf.set_stringn(
+            segment.x,
+            segment.y,
+            breadcrumb_label(path),
+            segment.width as usize,
+            style,
+        );
+        if !is_current && segment.right() < area.right() {
+            buf.set_stringn(
+                segment.right(),
+                segment.y,
+                TEXT_BREADCRUMB_SEPARATOR,
+                (area.right() - segment.right()) as usize,
+                Style::default(),
+            );
+        }
+    }
+}
+
+fn render_table(
+    area: Rect,
+    buf: &mut Buffer,
+    maybe_folder: Option<&Folder>,
+    tree: &FolderTree,
+    root_size: u64,
+    config: &UIConfig,
+) {
     if let Some(folder) = maybe_folder {
         let block = Block::default()
             .borders(Borders::ALL)
-            .fg(TEXT_COLOR)
-            .bg(NORMAL_ROW_COLOR);
+            .fg(config.theme().text)
+            .bg(config.theme().background);
 
-        let header_style = Style::default().fg(TABLE_HEADER_FG).bg(TABLE_HEADER_BG);
+        let header_style = config.theme().header_style();
         let selected_style = if config.confirming_deletion {
-            Style::default().bg(TEXT_PRE_DELETED_BG)
+            config.theme().pre_deleted
         } else {
-            Style::default().bg(TEXT_SELECTED_BG)
+            config.theme().selected
         };
 
-        let header_titles = match config.sort_by {
-            SortBy::Title => ["", "Name ↓", "Size", "Space"],
-            SortBy::Size => ["", "Name", "Size ↓", "Space"],
+        let sort_title = |sort_by: SortBy, title: &'static str, sorted: &'static str| {
+            if config.sort_by == sort_by {
+                sorted
+            } else {
+                title
+            }
         };
+        let mut header_titles = vec![
+            "",
+            sort_title(SortBy::Title, "Name", "Name ↓"),
+            sort_title(SortBy::Size, "Size", "Size ↓"),
+        ];
+        let mut widths = vec![
+            Constraint::Length(3),
+            Constraint::Length(40),
+            Constraint::Length(20),
+        ];
+        if config.show_parent_percent {
+            header_titles.push("% Parent");
+            widths.push(Constraint::Length(TABLE_PERCENT_WIDTH));
+        }
+        if config.show_root_percent {
+            header_titles.push("% Root");
+            widths.push(Constraint::Length(TABLE_PERCENT_WIDTH));
+        }
+        if config.show_files {
+            header_titles.push(sort_title(SortBy::Files, "Files", "Files ↓"));
+            widths.push(Constraint::Length(TABLE_COUNT_WIDTH));
+        }
+        if config.show_folders {
+            header_titles.push(sort_title(SortBy::Folders, "Folders", "Folders ↓"));
+            widths.push(Constraint::Length(TABLE_COUNT_WIDTH));
+        }
+        if config.show_owner {
+            header_titles.push("Owner");
+            widths.push(Constraint::Length(TABLE_OWNER_WIDTH));
+        }
+        header_titles.push("Space");
+        widths.push(Constraint::Length(TABLE_SPACE_WIDTH as u16));
 
         let header = header_titles
             .into_iter()
@@ -105,40 +462,55 @@ fn render_table(area: Rect, buf: &mut Buffer, maybe_folder: Option<&Folder>, con
             .style(header_style)
             .height(1);
 
-        let rows = folder_to_rows(&folder, &config);
-
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(3),
-                Constraint::Length(40),
-                Constraint::Length(20),
-                Constraint::Length(TABLE_SPACE_WIDTH as u16),
-            ],
-        )
-        .block(block)
-        .header(header)
-        .highlight_symbol(">>> ")
-        .highlight_style(selected_style)
-        .highlight_spacing(HighlightSpacing::Always);
+        let rows = folder_to_rows(folder, tree, root_size, config);
+
+        let selected_row = (0..folder.cursor_index)
+            .filter(|index| {
+                folder.is_visible(*index, &|entry| {
+                    tree.is_owned_by(entry, config.owner_filter)
+                })
+            })
+            .count();
+
+        let table = Table::new(rows, widths)
+            .block(block)
+            .header(header)
+            .highlight_symbol(TEXT_HIGHLIGHT_SYMBOL)
+            .highlight_style(selected_style)
+            .highlight_spacing(HighlightSpacing::Always);
 
         StatefulWidget::render(
             table,
             area,
             buf,
-            &mut TableState::default().with_selected(Some(folder.cursor_index)),
+            &mut TableState::default().with_selected(Some(selected_row)),
         );
     }
 }
 
-fn folder_to_rows<'a>(folder: &'a Folder, config: &'a UIConfig) -> Vec<Row<'a>> {
+fn folder_to_rows<'a>(
+    folder: &'a Folder,
+    tree: &FolderTree,
+    root_size: u64,
+    config: &'a UIConfig,
+) -> Vec<Row<'a>> {
     let max_entry_size = folder.get_max_entry_size();
+    let folder_size = folder.get_size();
+    let percent_cell = |size: Option<u64>, whole: u64| match size {
+        Some(size) => Text::from(format!("{:.1}%", percent_of(size, whole))),
+        None => Text::from(""),
+    };
+    let count_cell = |count: Option<u64>| match count {
+        Some(count) => Text::from(count.to_string()),
+        None => Text::from(""),
+    };
 
     folder
-        .to_list()
+        .entries
         .iter()
+        .filter(|item| tree.is_owned_by(item, config.owner_filter))
         .map(|item| {
-            let (item_size, bar, color) = match item.size {
+            let (item_size, bar, color) = match item.size() {
                 Some(size) => {
                     let percent = if max_entry_size == 0 {
                         0
@@ -146,13 +518,17 @@ fn folder_to_rows<'a>(folder: &'a Folder, config: &'a UIConfig) -> Vec<Row<'a>>
                         (size * TABLE_SPACE_WIDTH as u64 / max_entry_size).div_euclid(1)
                     };
                     let mut b = String::new();
-                    let color = calculate_color(percent, max_entry_size);
+                    let color = config.theme().bar_color(percent, TABLE_SPACE_WIDTH as u64);
                     for _ in 0..percent {
                         b.push('█');
                     }
                     (Text::from(format_file_size(size)), Text::from(b), color)
                 }
-                None => (Text::from(TEXT_UNKNOWN), Text::from(" "), NORMAL_ROW_COLOR),
+                None => (
+                    Text::from(TEXT_UNKNOWN),
+                    Text::from(" "),
+                    config.theme().background,
+                ),
             };
             let prefix = match item.kind == FolderEntryType::Folder {
                 true => Text::from("[ ]"),
@@ -164,28 +540,436 @@ fn folder_to_rows<'a>(folder: &'a Folder, config: &'a UIConfig) -> Vec<Row<'a>>
                 bar_style = bar_style.fg(color);
             }
 
+            let mut cells = vec![prefix, Text::from(&*item.title), item_size];
+            if config.show_parent_percent {
+                cells.push(percent_cell(item.size(), folder_size));
+            }
+            if config.show_root_percent {
+                cells.push(percent_cell(item.size(), root_size));
+            }
+            if config.show_files {
+                cells.push(count_cell(tree.count_of(item).map(|count| count.files)));
+            }
+            if config.show_folders {
+                cells.push(count_cell(tree.count_of(item).map(|count| count.folders)));
+            }
+            if config.show_owner {
+                let owner = match item.owner() {
+                    Some(owner) => user_name(owner.uid),
+                    None => String::new(),
+                };
+                cells.push(Text::from(owner));
+            }
+            cells.push(bar.style(bar_style));
+
+            Row::new(cells)
+        })
+        .collect()
+}
+
+/// Entries of the current folder with expanded subfolders indented below them
+fn render_tree_view(
+    area: Rect,
+    buf: &mut Buffer,
+    rows: &[TreeRow],
+    cursor: usize,
+    folder_size: u64,
+    config: &UIConfig,
+) {
+    let theme = config.theme();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Tree")
+        .fg(theme.text)
+        .bg(theme.background);
+
+    let header = ["Name", "Size", "%", "Space"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(theme.header_style())
+        .height(1);
+
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .map(|row| {
+            let marker = match (&row.kind, row.folder, row.expanded) {
+                (FolderEntryType::Folder, Some(_), true) => "▾ ",
+                (FolderEntryType::Folder, _, _) => "▸ ",
+                _ => "  ",
+            };
+            let name = format!("{}{}{}", "  ".repeat(row.depth), marker, row.title);
+            let (size, percent, bar) = match row.size {
+                Some(size) => {
+                    let filled = (size * TABLE_SPACE_WIDTH as u64)
+                        .checked_div(folder_size)
+                        .unwrap_or(0);
+                    let mut bar_style = Style::default();
+                    if config.colored {
+                        bar_style = bar_style.fg(theme.bar_color(filled, TABLE_SPACE_WIDTH as u64));
+                    }
+                    (
+                        Text::from(format_file_size(size)),
+                        Text::from(format!("{:.1}%", percent_of(size, folder_size))),
+                        Text::from("█".repeat(filled as usize)).style(bar_style),
+                    )
+                }
+                None => (Text::from(TEXT_UNKNOWN), Text::from(""), Text::from("")),
+            };
+            Row::new(vec![Text::from(name), size, percent, bar])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Fill(1),
+        Constraint::Length(12),
+        Constraint::Length(TABLE_PERCENT_WIDTH),
+        Constraint::Length(TABLE_SPACE_WIDTH as u16),
+    ];
+    let table = Table::new(table_rows, widths)
+        .block(block)
+        .header(header)
+        .highlight_symbol(TEXT_HIGHLIGHT_SYMBOL)
+        .highlight_style(theme.selected)
+        .highlight_spacing(HighlightSpacing::Always);
+
+    StatefulWidget::render(
+        table,
+        area,
+        buf,
+        &mut TableState::default().with_selected(Some(cursor)),
+    );
+}
+
+/// Popup centered over `area` with `stats.report_lines()`
+fn render_stats(area: Rect, buf: &mut Buffer, stats: &ScanStats, theme: &Theme) {
+    let lines = stats.report_lines();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count() as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .clamp(40.min(area.width), area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Scan statistics")
+        .padding(Padding::horizontal(1))
+        .fg(theme.text)
+        .bg(theme.background);
+    Clear.render(popup, buf);
+    Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<Line>>())
+        .block(block)
+        .render(popup, buf);
+}
+
+fn render_owners(area: Rect, buf: &mut Buffer, owners: &OwnerUsage, theme: &Theme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Usage by owner")
+        .fg(theme.text)
+        .bg(theme.background);
+
+    let header_style = theme.header_style();
+    let header = ["Owner", "UID", "Size", "Space"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+
+    let mut usage: Vec<(&u32, &u64)> = owners.iter().collect();
+    usage.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+
+    let total_size: u64 = owners.values().sum();
+    let rows: Vec<Row> = usage
+        .into_iter()
+        .map(|(uid, size)| {
+            let percent = (size * TABLE_SPACE_WIDTH as u64)
+                .checked_div(total_size)
+                .unwrap_or(0);
+            Row::new(vec![
+                Text::from(user_name(*uid)),
+                Text::from(uid.to_string()),
+                Text::from(format_file_size(*size)),
+                Text::from("█".repeat(percent as usize)),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(TABLE_OWNER_WIDTH),
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(TABLE_SPACE_WIDTH as u16),
+        ],
+    )
+    .block(block)
+    .header(header);
+
+    Widget::render(table, area, buf);
+}
+
+fn render_filesystems(
+    area: Rect,
+    buf: &mut Buffer,
+    filesystems: &[FileSystem],
+    cursor: usize,
+    config: &UIConfig,
+) {
+    let theme = config.theme();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Mounted filesystems")
+        .fg(theme.text)
+        .bg(theme.background);
+
+    let header_style = theme.header_style();
+    let header = [
+        "Mounted on",
+        "Device",
+        "Type",
+        "Size",
+        "Used",
+        "Free",
+        "Use%",
+        "Space",
+    ]
+    .into_iter()
+    .map(Cell::from)
+    .collect::<Row>()
+    .style(header_style)
+    .height(1);
+
+    let widths = [
+        Constraint::Length(30),
+        Constraint::Length(20),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(7),
+        Constraint::Length(TABLE_SPACE_WIDTH as u16),
+    ];
+    // Laid out like the table does, a bar wider than its column would make ratatui
+    // cut a multi-byte character in half
+    let [_, columns_area] = Layout::horizontal([
+        Constraint::Length(TEXT_HIGHLIGHT_SYMBOL.len() as u16),
+        Constraint::Fill(0),
+    ])
+    .areas(block.inner(area));
+    let space_width = Layout::horizontal(widths)
+        .spacing(1)
+        .split(columns_area)
+        .last()
+        .map_or(0, |column| column.width as u64);
+
+    let rows: Vec<Row> = filesystems
+        .iter()
+        .map(|filesystem| {
+            let percent = filesystem.used_percent();
+            let bar_width = (percent * space_width as f64 / 100.0).round() as u64;
+            let mut bar_style = Style::default();
+            if config.colored {
+                bar_style = bar_style.fg(theme.bar_color(bar_width, space_width));
+            }
+            Row::new(vec![
+                Text::from(filesystem.mount_point.to_string_lossy().into_owned()),
+                Text::from(filesystem.device.clone()),
+                Text::from(filesystem.fs_type.clone()),
+                Text::from(format_file_size(filesystem.total)),
+                Text::from(format_file_size(filesystem.used)),
+                Text::from(format_file_size(filesystem.free)),
+                Text::from(format!("{:.1}%", percent)),
+                Text::from("█".repeat(bar_width as usize)).style(bar_style),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows, widths)
+        .block(block)
+        .header(header)
+        .highlight_symbol(TEXT_HIGHLIGHT_SYMBOL)
+        .highlight_style(theme.selected)
+        .highlight_spacing(HighlightSpacing::Always);
+
+    StatefulWidget::render(
+        table,
+        area,
+        buf,
+        &mut TableState::default().with_selected(Some(cursor)),
+    );
+}
+
+fn render_bookmarks(
+    area: Rect,
+    buf: &mut Buffer,
+    bookmarks: &[PathBuf],
+    cursor: usize,
+    tree: &FolderTree,
+    theme: &Theme,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Bookmarks")
+        .fg(theme.text)
+        .bg(theme.background);
+
+    let header = ["Path", "Size"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(theme.header_style())
+        .height(1);
+
+    let rows: Vec<Row> = bookmarks
+        .iter()
+        .map(|path| {
+            let size = match tree.folder(path) {
+                Some(folder) => format_file_size(folder.get_size()),
+                None => String::from(TEXT_NOT_SCANNED),
+            };
             Row::new(vec![
-                prefix,
-                Text::from(item.title.clone()),
-                item_size,
-                bar.style(bar_style),
+                Text::from(path.to_string_lossy().into_owned()),
+                Text::from(size),
             ])
         })
-        .collect()
+        .collect();
+
+    let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(12)])
+        .block(block)
+        .header(header)
+        .highlight_symbol(TEXT_HIGHLIGHT_SYMBOL)
+        .highlight_style(theme.selected)
+        .highlight_spacing(HighlightSpacing::Always);
+
+    StatefulWidget::render(
+        table,
+        area,
+        buf,
+        &mut TableState::default().with_selected(Some(cursor)),
+    );
 }
 
-fn render_footer(area: Rect, buf: &mut Buffer) {
-    let vertical_layout = Layout::vertical([Constraint::Min(1), Constraint::Min(1)]);
-    let [first_line, second_line] = vertical_layout.areas(area);
-    Paragraph::new(TEXT_HINT_L1)
-        .centered()
-        .render(first_line, buf);
-    Paragraph::new(TEXT_HINT_L2)
+fn render_cleanables(
+    area: Rect,
+    buf: &mut Buffer,
+    cleanables: &[Cleanable],
+    cursor: usize,
+    config: &UIConfig,
+) {
+    let total_size: u64 = cleanables.iter().map(|cleanable| cleanable.size).sum();
+    let selected_size: u64 = cleanables
+        .iter()
+        .filter(|cleanable| cleanable.selected)
+        .map(|cleanable| cleanable.size)
+        .sum();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Cleanable build artifacts: {} in {} folders | Selected: {}",
+            format_file_size(total_size),
+            cleanables.len(),
+            format_file_size(selected_size)
+        ))
+        .fg(config.theme().text)
+        .bg(config.theme().background);
+
+    let header_style = config.theme().header_style();
+    let selected_style = if config.confirming_deletion {
+        config.theme().pre_deleted
+    } else {
+        config.theme().selected
+    };
+    let header = ["", "Path", "Kind", "Size", "Last used"]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(header_style)
+        .height(1);
+
+    let rows: Vec<Row> = cleanables
+        .iter()
+        .map(|cleanable| {
+            let last_used = match cleanable.last_used {
+                Some(time) => format_age(time),
+                None => String::from(TEXT_UNKNOWN),
+            };
+            Row::new(vec![
+                Text::from(value_to_box(&cleanable.selected)),
+                Text::from(cleanable.path.to_string_lossy().into_owned()),
+                Text::from(cleanable.preset),
+                Text::from(format_file_size(cleanable.size)),
+                Text::from(last_used),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Length(14),
+        ],
+    )
+    .block(block)
+    .header(header)
+    .highlight_symbol(TEXT_HIGHLIGHT_SYMBOL)
+    .highlight_style(selected_style)
+    .highlight_spacing(HighlightSpacing::Always);
+
+    StatefulWidget::render(
+        table,
+        area,
+        buf,
+        &mut TableState::default().with_selected(Some(cursor)),
+    );
+}
+
+fn render_status(area: Rect, buf: &mut Buffer, confirmation_target: &str, config: &UIConfig) {
+    if let Some(input) = &config.typed_confirmation {
+        Paragraph::new(format!(
+            "Permanently delete? Type \"{}\" and press Enter (Esc - cancel): {}█",
+            confirmation_target, input
+        ))
+        .fg(config.theme().warning)
+        .bold()
         .centered()
-        .render(second_line, buf);
+        .render(area, buf);
+    } else if let Some(input) = &config.jump_input {
+        let mut prompt = format!("Jump to: {}█", input);
+        if let Some(message) = &config.message {
+            prompt = format!("{}   {}", prompt, message);
+        }
+        Paragraph::new(prompt).bold().centered().render(area, buf);
+    } else if let Some(message) = &config.message {
+        Paragraph::new(message.as_str())
+            .bold()
+            .centered()
+            .render(area, buf);
+    }
+}
+
+fn render_footer(area: Rect, buf: &mut Buffer, hints: &[&str]) {
+    let vertical_layout = Layout::vertical(hints.iter().map(|_| Constraint::Length(1)));
+    for (hint, line) in hints.iter().zip(vertical_layout.split(area).iter()) {
+        Paragraph::new(*hint).centered().render(*line, buf);
+    }
 }
 
-fn format_file_size(size: u64) -> String {
+pub fn format_file_size(size: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -204,18 +988,35 @@ fn format_file_size(size: u64) -> String {
     }
 }
 
-fn calculate_color(percent: u64, _max_entry_size: u64) -> Color {
-    let colors = [
-        Color::Rgb(0, 128, 0),    // Green
-        Color::Rgb(50, 205, 50),  // LimeGreen
-        Color::Rgb(173, 255, 47), // GreenYellow
-        Color::Rgb(255, 255, 0),  // Yellow
-        Color::Rgb(255, 165, 0),  // Orange
-        Color::Rgb(255, 0, 0),    // Red
-    ];
+/// Coarse age like "3 days ago", relative to now
+pub fn format_age(time: SystemTime) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = MINUTE * 60;
+    const DAY: u64 = HOUR * 24;
+    const MONTH: u64 = DAY * 30;
+    const YEAR: u64 = DAY * 365;
+
+    let seconds = SystemTime::now()
+        .duration_since(time)
+        .map(|age| age.as_secs())
+        .unwrap_or(0);
 
-    let index =
-        ((percent as f64 / TABLE_SPACE_WIDTH as f64) * (colors.len() - 1) as f64).round() as usize;
+    let (amount, unit) = if seconds >= YEAR {
+        (seconds / YEAR, "year")
+    } else if seconds >= MONTH {
+        (seconds / MONTH, "month")
+    } else if seconds >= DAY {
+        (seconds / DAY, "day")
+    } else if seconds >= HOUR {
+        (seconds / HOUR, "hour")
+    } else if seconds >= MINUTE {
+        (seconds / MINUTE, "minute")
+    } else {
+        return String::from("just now");
+    };
 
-    colors[index]
+    match amount {
+        1 => format!("1 {} ago", unit),
+        _ => format!("{} {}s ago", amount, unit),
+    }
 }
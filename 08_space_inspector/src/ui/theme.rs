@@ -0,0 +1,253 @@
+use crate::config::config_dir;
+use ratatui::{prelude::*, style::palette::tailwind};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const TEXT_SELECTED_BG: Color = tailwind::SLATE.c700;
+const TEXT_PRE_DELETED_BG: Color = tailwind::RED.c600;
+pub const THEME_MONOCHROME: &str = "monochrome";
+const THEME_FILE: &str = "themes.conf";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub text: Color,
+    pub header_fg: Color,
+    pub header_bg: Color,
+    pub selected: Style,
+    pub pre_deleted: Style,
+    /// Color of the typed confirmation prompt
+    pub warning: Color,
+    /// Bar colors from the smallest to the largest entry
+    pub bar: Vec<Color>,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            name: String::from("dark"),
+            background: tailwind::SLATE.c950,
+            text: tailwind::SLATE.c200,
+            header_fg: tailwind::SLATE.c200,
+            header_bg: tailwind::SLATE.c900,
+            selected: Style::default().bg(TEXT_SELECTED_BG),
+            pre_deleted: Style::default().bg(TEXT_PRE_DELETED_BG),
+            warning: TEXT_PRE_DELETED_BG,
+            bar: vec![
+                Color::Rgb(0, 128, 0),    // Green
+                Color::Rgb(50, 205, 50),  // LimeGreen
+                Color::Rgb(173, 255, 47), // GreenYellow
+                Color::Rgb(255, 255, 0),  // Yellow
+                Color::Rgb(255, 165, 0),  // Orange
+                Color::Rgb(255, 0, 0),    // Red
+            ],
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            name: String::from("light"),
+            background: tailwind::SLATE.c50,
+            text: tailwind::SLATE.c900,
+            header_fg: tailwind::SLATE.c900,
+            header_bg: tailwind::SLATE.c300,
+            selected: Style::default().bg(tailwind::SKY.c200),
+            pre_deleted: Style::default().bg(tailwind::RED.c300),
+            warning: tailwind::RED.c700,
+            bar: vec![
+                tailwind::GREEN.c700,
+                tailwind::LIME.c700,
+                tailwind::YELLOW.c600,
+                tailwind::AMBER.c600,
+                tailwind::ORANGE.c700,
+                tailwind::RED.c700,
+            ],
+        }
+    }
+
+    /// Basic 16 colors only, readable on low quality displays and projectors
+    pub fn high_contrast() -> Self {
+        Theme {
+            name: String::from("high-contrast"),
+            background: Color::Black,
+            text: Color::White,
+            header_fg: Color::Black,
+            header_bg: Color::White,
+            selected: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            pre_deleted: Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+            warning: Color::LightRed,
+            bar: vec![Color::LightCyan, Color::LightYellow, Color::LightMagenta],
+        }
+    }
+
+    /// Okabe-Ito palette, bars go from blue to vermillion without relying on red/green
+    pub fn colorblind_safe() -> Self {
+        Theme {
+            name: String::from("colorblind-safe"),
+            bar: vec![
+                Color::Rgb(0, 114, 178),  // Blue
+                Color::Rgb(86, 180, 233), // Sky blue
+                Color::Rgb(240, 228, 66), // Yellow
+                Color::Rgb(230, 159, 0),  // Orange
+                Color::Rgb(213, 94, 0),   // Vermillion
+            ],
+            pre_deleted: Style::default().bg(Color::Rgb(213, 94, 0)),
+            warning: Color::Rgb(230, 159, 0),
+            ..Self::dark()
+        }
+    }
+
+    /// Terminal default colors, selection shown with reversed video
+    pub fn monochrome() -> Self {
+        Theme {
+            name: String::from(THEME_MONOCHROME),
+            background: Color::Reset,
+            text: Color::Reset,
+            header_fg: Color::Reset,
+            header_bg: Color::Reset,
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            pre_deleted: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            warning: Color::Reset,
+            bar: vec![Color::Reset],
+        }
+    }
+
+    pub fn header_style(&self) -> Style {
+        Style::default().fg(self.header_fg).bg(self.header_bg)
+    }
+
+    /// Picks bar color for a bar filling `percent` of `width`
+    pub fn bar_color(&self, percent: u64, width: u64) -> Color {
+        if self.bar.is_empty() || width == 0 {
+            return Color::Reset;
+        }
+        let index = ((percent.min(width) as f64 / width as f64) * (self.bar.len() - 1) as f64)
+            .round() as usize;
+
+        self.bar[index]
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "background" => self.background = parse_color(value)?,
+            "text" => self.text = parse_color(value)?,
+            "header_fg" => self.header_fg = parse_color(value)?,
+            "header_bg" => self.header_bg = parse_color(value)?,
+            "selected_fg" => self.selected = self.selected.fg(parse_color(value)?),
+            "selected_bg" => self.selected = self.selected.bg(parse_color(value)?),
+            "pre_deleted_fg" => self.pre_deleted = self.pre_deleted.fg(parse_color(value)?),
+            "pre_deleted_bg" => self.pre_deleted = self.pre_deleted.bg(parse_color(value)?),
+            "warning" => self.warning = parse_color(value)?,
+            "bar" => {
+                self.bar = value
+                    .split_whitespace()
+                    .map(parse_color)
+                    .collect::<Result<Vec<Color>, String>>()?
+            }
+            _ => return Err(format!("unknown key \"{}\"", key)),
+        }
+        Ok(())
+    }
+}
+
+pub fn builtin_themes() -> Vec<Theme> {
+    vec![
+        Theme::dark(),
+        Theme::light(),
+        Theme::high_contrast(),
+        Theme::colorblind_safe(),
+        Theme::monochrome(),
+    ]
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    Color::from_str(value).map_err(|_| format!("invalid color \"{}\"", value))
+}
+
+/// Themes defined in a theme file and the one to start with
+#[derive(Debug, Default, PartialEq)]
+pub struct ThemeFile {
+    pub default_theme: Option<String>,
+    pub themes: Vec<Theme>,
+}
+
+/// Parses theme file:
+///
+/// ```text
+/// theme = ocean
+///
+/// [ocean]
+/// base = dark
+/// background = #001b2e
+/// bar = blue cyan white
+/// ```
+///
+/// `base` copies a built-in theme and has to come first in a section.
+/// Colors are names, `#rrggbb` or 256-color indexes.
+pub fn parse_theme_file(content: &str) -> Result<ThemeFile, String> {
+    let mut file = ThemeFile::default();
+    let mut current: Option<Theme> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        let error = |message: &str| format!("Theme file line {}: {}", index + 1, message);
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            file.themes.extend(current.take());
+            current = Some(Theme {
+                name: name.trim().to_string(),
+                ..Theme::dark()
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected \"key = value\""))?;
+        let (key, value) = (key.trim(), value.trim());
+
+        match current.as_mut() {
+            None if key == "theme" => file.default_theme = Some(value.to_string()),
+            None => return Err(error("setting outside of a [theme] section")),
+            Some(theme) if key == "base" => {
+                let base = builtin_themes()
+                    .into_iter()
+                    .find(|builtin| builtin.name == value)
+                    .ok_or_else(|| error(&format!("unknown base theme \"{}\"", value)))?;
+                *theme = Theme {
+                    name: theme.name.clone(),
+                    ..base
+                };
+            }
+            Some(theme) => theme.set(key, value).map_err(|message| error(&message))?,
+        }
+    }
+    file.themes.extend(current);
+
+    Ok(file)
+}
+
+pub fn load_theme_file(path: &Path) -> Result<ThemeFile, String> {
+    let content = read_to_string(path)
+        .map_err(|err| format!("Can't read {}: {}", path.to_string_lossy(), err))?;
+    parse_theme_file(&content)
+}
+
+/// `themes.conf` in the config directory
+pub fn default_theme_file() -> Option<PathBuf> {
+    Some(config_dir()?.join(THEME_FILE))
+}